@@ -0,0 +1,142 @@
+//! Buffers with a controlled alignment and placement.
+//!
+//! Handy to exercise alignment and boundary related output deterministically in examples and tests, e.g. a buffer
+//! starting exactly on a page, or one crossing a page boundary in its middle.
+
+use alloc::alloc::{alloc_zeroed, dealloc, handle_alloc_error, Layout};
+use core::ops::{Deref, DerefMut};
+
+/// Size of a regular memory page assumed by the helpers, 4 KiB.
+pub const PAGE_SIZE: usize = 4096;
+
+/// Size of a large (huge) page on x86-64 and AArch64, 2 MiB.
+pub const LARGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+
+/// A zero-initialized heap buffer placed at a chosen alignment or across a chosen boundary.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_buf = AlignedBuf::straddling_page(32);
+/// assert_eq!(my_buf.boundary(), Some(my_buf.as_ptr().addr() + 16));
+/// view_mem!(*my_buf);
+/// ```
+pub struct AlignedBuf {
+    base: *mut u8,
+    layout: Layout,
+    offset: usize,
+    len: usize,
+    boundary: Option<usize>,
+}
+
+impl AlignedBuf {
+    /// Allocates `len` bytes starting at an address multiple of `align`.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The length of the buffer.
+    /// * `align` - The alignment of the first byte, a power of two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two.
+    pub fn new(len: usize, align: usize) -> Self {
+        Self::allocate(len, align, 0)
+    }
+
+    /// Allocates `len` bytes starting at the beginning of a page.
+    pub fn page_aligned(len: usize) -> Self {
+        Self::new(len, PAGE_SIZE)
+    }
+
+    /// Allocates `len` bytes starting at a 2 MiB boundary.
+    pub fn large_page_aligned(len: usize) -> Self {
+        Self::new(len, LARGE_PAGE_SIZE)
+    }
+
+    /// Allocates `len` bytes crossing an address multiple of `boundary`, the first `len / 2` bytes lying before it.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The length of the buffer.
+    /// * `boundary` - The boundary to cross, a power of two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `boundary` is not a power of two or is smaller than `len / 2`.
+    pub fn straddling(len: usize, boundary: usize) -> Self {
+        assert!(len / 2 <= boundary, "the buffer must fit around the boundary");
+        let before = len / 2;
+        let mut buf = Self::allocate(len, boundary, boundary - before);
+        buf.boundary = Some(buf.base.addr() + boundary);
+        buf
+    }
+
+    /// Allocates `len` bytes crossing a page boundary in their middle.
+    pub fn straddling_page(len: usize) -> Self {
+        Self::straddling(len, PAGE_SIZE)
+    }
+
+    fn allocate(len: usize, align: usize, offset: usize) -> Self {
+        // Never allocate 0 bytes, it is not allowed by `alloc`.
+        let layout = Layout::from_size_align((offset + len).max(1), align).expect("align must be a power of two");
+        let base = unsafe { alloc_zeroed(layout) };
+        if base.is_null() {
+            handle_alloc_error(layout);
+        }
+        AlignedBuf {
+            base,
+            layout,
+            offset,
+            len,
+            boundary: None,
+        }
+    }
+
+    /// Returns the address of the boundary crossed by a buffer made with [`AlignedBuf::straddling`].
+    pub fn boundary(&self) -> Option<usize> {
+        self.boundary
+    }
+
+    /// Returns a pointer to the first byte of the buffer.
+    pub fn as_ptr(&self) -> *const u8 {
+        unsafe { self.base.add(self.offset) }
+    }
+
+    /// Returns a mutable pointer to the first byte of the buffer.
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        unsafe { self.base.add(self.offset) }
+    }
+}
+
+// The buffer owns its allocation like a `Box<[u8]>`.
+unsafe impl Send for AlignedBuf {}
+unsafe impl Sync for AlignedBuf {}
+
+impl Deref for AlignedBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for AlignedBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { core::slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+}
+
+impl Drop for AlignedBuf {
+    fn drop(&mut self) {
+        unsafe { dealloc(self.base, self.layout) };
+    }
+}
+
+impl core::fmt::Debug for AlignedBuf {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AlignedBuf").field("addr", &self.as_ptr()).field("len", &self.len).field("boundary", &self.boundary).finish()
+    }
+}
@@ -0,0 +1,151 @@
+//! Allocator level statistics of jemalloc and mimalloc.
+//!
+//! Print these summaries next to the dumps to cross-check the numbers reported by the allocator itself, e.g. when
+//! hunting fragmentation. The statistics are only meaningful if the allocator is actually installed as
+//! `#[global_allocator]` (`tikv-jemallocator` or `mimalloc`).
+//!
+//! Enabled with the `jemalloc` and `mimalloc` features.
+
+use std::fmt::Write;
+
+/// Summary of the jemalloc `stats.*` counters, in bytes.
+#[cfg(feature = "jemalloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct JemallocStats {
+    /// Bytes allocated by the application.
+    pub allocated: usize,
+    /// Bytes in active pages, a multiple of the page size and at least `allocated`.
+    pub active: usize,
+    /// Bytes dedicated to jemalloc metadata.
+    pub metadata: usize,
+    /// Bytes in physically resident data pages.
+    pub resident: usize,
+    /// Bytes in active extents mapped by the allocator.
+    pub mapped: usize,
+    /// Bytes in virtual memory mappings retained for future reuse.
+    pub retained: usize,
+}
+
+/// Returns the current jemalloc statistics, `None` if they can't be read.
+#[cfg(feature = "jemalloc")]
+pub fn jemalloc_stats() -> Option<JemallocStats> {
+    use tikv_jemalloc_ctl::{epoch, stats};
+
+    // The statistics are cached, advancing the epoch refreshes them.
+    epoch::advance().ok()?;
+    Some(JemallocStats {
+        allocated: stats::allocated::read().ok()?,
+        active: stats::active::read().ok()?,
+        metadata: stats::metadata::read().ok()?,
+        resident: stats::resident::read().ok()?,
+        mapped: stats::mapped::read().ok()?,
+        retained: stats::retained::read().ok()?,
+    })
+}
+
+/// Prints the summary of the jemalloc statistics.
+#[cfg(feature = "jemalloc")]
+pub fn print_jemalloc_stats() {
+    crate::render::emit(|out| {
+        match jemalloc_stats() {
+            Some(stats) => {
+                writeln!(out, "jemalloc allocated: {} bytes", stats.allocated)?;
+                writeln!(out, "jemalloc active   : {} bytes", stats.active)?;
+                writeln!(out, "jemalloc metadata : {} bytes", stats.metadata)?;
+                writeln!(out, "jemalloc resident : {} bytes", stats.resident)?;
+                writeln!(out, "jemalloc mapped   : {} bytes", stats.mapped)?;
+                writeln!(out, "jemalloc retained : {} bytes", stats.retained)?;
+            }
+            None => writeln!(out, "jemalloc statistics unavailable")?,
+        }
+        writeln!(out)
+    });
+}
+
+/// Process level memory information reported by mimalloc.
+#[cfg(feature = "mimalloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MimallocProcessInfo {
+    /// Current working set size in bytes.
+    pub current_rss: usize,
+    /// Peak working set size in bytes.
+    pub peak_rss: usize,
+    /// Current committed memory in bytes.
+    pub current_commit: usize,
+    /// Peak committed memory in bytes.
+    pub peak_commit: usize,
+    /// Count of hard page faults.
+    pub page_faults: usize,
+}
+
+/// Returns the process memory information of mimalloc.
+#[cfg(feature = "mimalloc")]
+pub fn mimalloc_process_info() -> MimallocProcessInfo {
+    let mut info = MimallocProcessInfo::default();
+    let null = std::ptr::null_mut();
+    unsafe {
+        libmimalloc_sys::mi_process_info(
+            null,
+            null,
+            null,
+            &mut info.current_rss,
+            &mut info.peak_rss,
+            &mut info.current_commit,
+            &mut info.peak_commit,
+            &mut info.page_faults,
+        );
+    }
+    info
+}
+
+/// Returns the statistics report of mimalloc (`mi_stats_print_out`) as text.
+#[cfg(feature = "mimalloc")]
+pub fn mimalloc_stats() -> String {
+    use std::ffi::{c_char, c_void, CStr};
+
+    unsafe extern "C" fn append(msg: *const c_char, arg: *mut c_void) {
+        let report = &mut *(arg as *mut String);
+        report.push_str(&CStr::from_ptr(msg).to_string_lossy());
+    }
+
+    let mut report = String::new();
+    unsafe {
+        libmimalloc_sys::mi_stats_print_out(Some(append), &mut report as *mut String as *mut c_void);
+    }
+    report
+}
+
+/// Prints the summary of the mimalloc statistics.
+#[cfg(feature = "mimalloc")]
+pub fn print_mimalloc_stats() {
+    let info = mimalloc_process_info();
+    let report = mimalloc_stats();
+    crate::render::emit(|out| {
+        writeln!(out, "mimalloc current rss   : {} bytes", info.current_rss)?;
+        writeln!(out, "mimalloc peak rss      : {} bytes", info.peak_rss)?;
+        writeln!(out, "mimalloc current commit: {} bytes", info.current_commit)?;
+        writeln!(out, "mimalloc peak commit   : {} bytes", info.peak_commit)?;
+        writeln!(out, "mimalloc page faults   : {}", info.page_faults)?;
+        out.push_str(&report);
+        writeln!(out)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "jemalloc")]
+    #[test]
+    fn jemalloc_summary() {
+        assert!(jemalloc_stats().is_some());
+        print_jemalloc_stats();
+    }
+
+    #[cfg(feature = "mimalloc")]
+    #[test]
+    fn mimalloc_summary() {
+        assert!(!mimalloc_stats().is_empty());
+        print_mimalloc_stats();
+    }
+}
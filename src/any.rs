@@ -0,0 +1,128 @@
+//! Dumps of type-erased values.
+//!
+//! In plugin systems the concrete type of a value is often unknown where it gets dumped. An [`AnyViewer`] holds a
+//! set of registered types, recognizes a `&dyn Any` as one of them and annotates its dump with the registered
+//! layout. Unknown types fall back to a raw dump.
+
+use std::any::{Any, TypeId};
+
+use crate::layout::{self, StructLayout};
+use crate::render::{self, Table, MEMORY_CONTENT};
+use crate::write::write_header;
+
+/// A registered type.
+struct Entry {
+    type_id: TypeId,
+    type_name: &'static str,
+    layout: Option<StructLayout>,
+}
+
+/// Dumps `&dyn Any` values, annotated with the layout of the registered types.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+/// use std::any::Any;
+///
+/// struct Packet {
+///     id: u16,
+///     len: u32,
+/// }
+///
+/// let mut viewer = AnyViewer::new();
+/// viewer.register_with_layout::<Packet>(layout_of!(Packet { id, len }));
+/// viewer.register::<u64>();
+///
+/// let my_packet: Box<dyn Any> = Box::new(Packet { id: 7, len: 69 });
+/// viewer.view("my_packet", &*my_packet);
+/// ```
+#[derive(Default)]
+pub struct AnyViewer {
+    entries: Vec<Entry>,
+}
+
+impl AnyViewer {
+    /// Creates a viewer without any registered type.
+    pub fn new() -> Self {
+        AnyViewer::default()
+    }
+
+    /// Registers a type, its dumps are labeled with its type name.
+    pub fn register<T: Any>(&mut self) -> &mut Self {
+        self.insert::<T>(None)
+    }
+
+    /// Registers a type with its layout, its dumps are labeled with its type name and annotated with its fields.
+    ///
+    /// # Argument
+    ///
+    /// * `layout` - The layout of `T`, usually from layout_of! macro.
+    pub fn register_with_layout<T: Any>(&mut self, layout: StructLayout) -> &mut Self {
+        self.insert::<T>(Some(layout))
+    }
+
+    fn insert<T: Any>(&mut self, layout: Option<StructLayout>) -> &mut Self {
+        let type_id = TypeId::of::<T>();
+        self.entries.retain(|entry| entry.type_id != type_id);
+        self.entries.push(Entry {
+            type_id,
+            type_name: std::any::type_name::<T>(),
+            layout,
+        });
+        self
+    }
+
+    /// Returns the type name of `value` if its type is registered.
+    pub fn type_name_of(&self, value: &dyn Any) -> Option<&'static str> {
+        self.entry_of(value).map(|entry| entry.type_name)
+    }
+
+    fn entry_of(&self, value: &dyn Any) -> Option<&Entry> {
+        let type_id = value.type_id();
+        self.entries.iter().find(|entry| entry.type_id == type_id)
+    }
+
+    /// Dumps a type-erased value.
+    ///
+    /// The bytes are annotated with the fields of the matching registered layout, if any. Values of unregistered
+    /// types are dumped as raw bytes, their size is still known from the vtable.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name displayed in the header.
+    /// * `value` - The value to dump.
+    pub fn view(&self, name: &str, value: &dyn Any) {
+        let ptr = value as *const dyn Any as *const u8;
+        let addr = ptr.addr();
+        let size = std::mem::size_of_val(value);
+        let entry = self.entry_of(value);
+        let header = |out: &mut String| match entry {
+            Some(entry) => write_header(out, name, entry.type_name, addr, size),
+            None => write_header(out, name, &format!("unregistered ({:?})", value.type_id()), addr, size),
+        };
+        if crate::config().miri_friendly {
+            render::emit(|out| {
+                header(out)?;
+                render::write_miri_note(out)
+            });
+            return;
+        }
+
+        let bytes = unsafe { std::slice::from_raw_parts(ptr, size) };
+
+        let layout = entry.and_then(|entry| entry.layout.as_ref());
+        let annotate = |offset: usize, out: &mut dyn core::fmt::Write| match layout {
+            Some(layout) => layout::annotate(layout, offset, out),
+            None => Ok(()),
+        };
+        let mut table = Table { label: name, ..Table::new(MEMORY_CONTENT, addr, bytes) };
+        if layout.is_some() {
+            table.annotate = Some(&annotate);
+        }
+        render::emit(|out| {
+            header(out)?;
+            table.write(out)
+        });
+    }
+}
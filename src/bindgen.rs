@@ -0,0 +1,293 @@
+//! Struct layouts read from bindgen output.
+//!
+//! bindgen generates the Rust definition of every C struct with layout assertions (its size, alignment and the
+//! offset of every field), both checked by the compiler. [`parse_bindgen_layouts`] reads them back from the generated
+//! source, so C structs get field-annotated dumps without hand-written descriptions.
+
+use alloc::vec::Vec;
+
+use crate::layout::{FieldLayout, StructLayout};
+
+/// Returns the layouts of the structs and unions of a bindgen-generated source.
+///
+/// Both the `const _: () = { ["Size of point_t"][...]; ... };` assertions of recent bindgen versions and the older
+/// `#[test] fn bindgen_test_layout_point_t()` functions are understood, so the source must be generated with layout
+/// tests (the default). Structs without them are skipped. The size of a field comes from its type when it is a
+/// primitive, a C type alias, a pointer, an array or another struct of the source, and from the distance to the next
+/// field otherwise (e.g. bitfield units). The names are borrowed from the source, which is usually included with
+/// `include_str!`.
+///
+/// # Argument
+///
+/// * `source` - The Rust source generated by bindgen.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use mem_viewer::*;
+///
+/// static BINDINGS: &str = include_str!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+///
+/// let layouts = parse_bindgen_layouts(BINDINGS);
+/// let point = layouts.iter().find(|layout| layout.name == "point_t").unwrap();
+/// unsafe { view_c_struct!(ptr, point.size, point) };
+/// ```
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let layouts = parse_bindgen_layouts(r#"
+///     #[repr(C)]
+///     pub struct point_t {
+///         pub id: ::std::os::raw::c_ushort,
+///         pub x: f32,
+///     }
+///     const _: () = {
+///         ["Size of point_t"][::std::mem::size_of::<point_t>() - 8usize];
+///         ["Alignment of point_t"][::std::mem::align_of::<point_t>() - 4usize];
+///         ["Offset of field: point_t::id"][::std::mem::offset_of!(point_t, id) - 0usize];
+///         ["Offset of field: point_t::x"][::std::mem::offset_of!(point_t, x) - 4usize];
+///     };
+/// "#);
+/// assert_eq!(layouts[0].padding(), vec![2..4]);
+/// ```
+pub fn parse_bindgen_layouts(source: &'static str) -> Vec<StructLayout> {
+    let definitions = definitions(source);
+
+    // Sizes first, fields may be of the type of another struct of the source
+    let sizes: Vec<(&str, usize, usize)> = definitions
+        .iter()
+        .filter_map(|definition| {
+            let size = asserted(source, &["size_of::<", definition.name, ">()"])?;
+            let align = asserted(source, &["align_of::<", definition.name, ">()"])?;
+            Some((definition.name, size, align))
+        })
+        .collect();
+
+    let mut layouts = Vec::new();
+    for definition in &definitions {
+        let Some(&(_, size, align)) = sizes.iter().find(|(name, _, _)| *name == definition.name) else {
+            continue;
+        };
+        let offsets: Option<Vec<usize>> = definition.fields.iter().map(|(field, _)| field_offset(source, definition.name, field)).collect();
+        let Some(offsets) = offsets else {
+            continue;
+        };
+
+        let fields = definition
+            .fields
+            .iter()
+            .zip(&offsets)
+            .map(|(&(name, type_name), &offset)| {
+                let (field_size, field_align) = type_layout(type_name, &sizes).unwrap_or_else(|| {
+                    let next = offsets.iter().copied().filter(|&other| other > offset).min().unwrap_or(size);
+                    (next - offset, 1)
+                });
+                FieldLayout {
+                    name,
+                    type_name: short_type_name(type_name),
+                    offset,
+                    size: field_size,
+                    align: field_align,
+                }
+            })
+            .collect();
+        layouts.push(StructLayout {
+            name: definition.name,
+            size,
+            align,
+            fields,
+        });
+    }
+    layouts
+}
+
+/// A struct or union definition: its name and its `(name, type)` fields.
+struct Definition {
+    name: &'static str,
+    fields: Vec<(&'static str, &'static str)>,
+}
+
+/// Returns the struct and union definitions with named fields of `source`, in source order.
+fn definitions(source: &'static str) -> Vec<Definition> {
+    let mut found = Vec::new();
+    for keyword in ["pub struct ", "pub union "] {
+        for (start, _) in source.match_indices(keyword) {
+            let rest = &source[start + keyword.len()..];
+            let name_len = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            let after_name = rest[name_len..].trim_start();
+            // Tuple, unit and generic structs have no layout assertions
+            if name_len == 0 || !after_name.starts_with('{') {
+                continue;
+            }
+            let Some(body) = block(&after_name[1..]) else {
+                continue;
+            };
+            let fields = split_top_level(body, ',').into_iter().filter_map(|item| field(strip_attributes(item))).collect();
+            found.push((start, Definition { name: &rest[..name_len], fields }));
+        }
+    }
+    found.sort_by_key(|(start, _)| *start);
+    found.into_iter().map(|(_, definition)| definition).collect()
+}
+
+/// Returns the text before the `}` closing a block whose `{` precedes `text`.
+fn block(text: &str) -> Option<&str> {
+    let mut end = None;
+    scan(text, |index, c, depth| {
+        if c == '}' && depth < 0 {
+            end = Some(index);
+        }
+        end.is_none()
+    });
+    Some(&text[..end?])
+}
+
+/// Splits `text` at the `separator`s outside of brackets and string literals.
+fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    scan(text, |index, c, depth| {
+        if c == separator && depth == 0 {
+            items.push(&text[start..index]);
+            start = index + c.len_utf8();
+        }
+        true
+    });
+    items.push(&text[start..]);
+    items
+}
+
+/// Calls `f` with the index, the character and the bracket depth after it of every character of `text` outside of
+/// string literals, as long as `f` returns `true`.
+fn scan(text: &str, mut f: impl FnMut(usize, char, i32) -> bool) {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut previous = ' ';
+    for (index, c) in text.char_indices() {
+        if in_string {
+            in_string = !(c == '"' && previous != '\\');
+            previous = if previous == '\\' { ' ' } else { c };
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '(' | '[' | '{' | '<' => depth += 1,
+            // `->` of function pointer types isn't a bracket
+            '>' if previous == '-' => {}
+            ')' | ']' | '}' | '>' => depth -= 1,
+            _ => {}
+        }
+        previous = c;
+        if !in_string && !f(index, c, depth) {
+            return;
+        }
+    }
+}
+
+/// Removes the attributes (e.g. `#[doc = "..."]`) and comments preceding a field.
+fn strip_attributes(mut item: &str) -> &str {
+    loop {
+        item = item.trim_start();
+        if let Some(rest) = item.strip_prefix("#[") {
+            let mut end = rest.len();
+            scan(rest, |index, c, depth| {
+                if c == ']' && depth < 0 {
+                    end = index + 1;
+                }
+                end == rest.len()
+            });
+            item = &rest[end..];
+        } else if item.starts_with("//") {
+            item = item.find('\n').map_or("", |end| &item[end..]);
+        } else {
+            return item;
+        }
+    }
+}
+
+/// Parses a `pub name: Type` field.
+fn field(item: &'static str) -> Option<(&'static str, &'static str)> {
+    let item = item.trim();
+    let item = item.strip_prefix("pub").map_or(item, |rest| {
+        let rest = rest.trim_start();
+        match rest.strip_prefix('(') {
+            Some(visibility) => visibility.split_once(')').map_or(rest, |(_, rest)| rest),
+            None => rest,
+        }
+    });
+    let (name, type_name) = item.split_once(':')?;
+    let (name, type_name) = (name.trim(), type_name.trim());
+    if name.is_empty() || type_name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+    Some((name, type_name))
+}
+
+/// Returns the offset of a field asserted in `source`.
+fn field_offset(source: &str, struct_name: &str, field_name: &str) -> Option<usize> {
+    asserted(source, &["offset_of!(", struct_name, ", ", field_name, ")"]).or_else(|| {
+        // Older bindgen: `addr_of!((*ptr).field) as usize - ptr as usize }, 4usize,` in a test function
+        let test = ["fn bindgen_test_layout_", struct_name, "()"].concat();
+        let start = source.find(&test)? + test.len();
+        let body = block(source[start..].split_once('{')?.1)?;
+        asserted(body, &["(*ptr).", field_name, ")"])
+    })
+}
+
+/// Returns the number of the first `<number>usize` literal following the concatenation of `pattern` in `source`.
+fn asserted(source: &str, pattern: &[&str]) -> Option<usize> {
+    let pattern = pattern.concat();
+    let start = source.find(&pattern)? + pattern.len();
+    let rest = &source[start..];
+    rest.match_indices("usize").find_map(|(end, _)| {
+        let digits = rest[..end].len() - rest[..end].trim_end_matches(|c: char| c.is_ascii_digit()).len();
+        rest[end - digits..end].parse().ok()
+    })
+}
+
+/// Returns the size and alignment of a type written by bindgen, if it is known.
+fn type_layout(type_name: &str, structs: &[(&str, usize, usize)]) -> Option<(usize, usize)> {
+    use core::mem::{align_of, size_of};
+
+    let pointer = (size_of::<usize>(), align_of::<usize>());
+    if type_name.starts_with(['*', '&']) || type_name.starts_with("::std::option::Option<") || type_name.starts_with("Option<") {
+        return Some(pointer);
+    }
+    if let Some(array) = type_name.strip_prefix('[').and_then(|array| array.strip_suffix(']')) {
+        let parts = split_top_level(array, ';');
+        let [element, len] = parts[..] else {
+            return None;
+        };
+        let (size, align) = type_layout(element.trim(), structs)?;
+        let len: usize = len.trim().trim_end_matches("usize").parse().ok()?;
+        return Some((size * len, align));
+    }
+
+    macro_rules! layouts {
+        ($($name: literal => $ty: ty),*) => {
+            match type_name.rsplit("::").next().unwrap_or(type_name) {
+                $($name => Some((size_of::<$ty>(), align_of::<$ty>())),)*
+                name => structs.iter().find(|(struct_name, _, _)| *struct_name == name).map(|&(_, size, align)| (size, align)),
+            }
+        };
+    }
+    layouts! {
+        "u8" => u8, "i8" => i8, "bool" => bool, "u16" => u16, "i16" => i16, "u32" => u32, "i32" => i32, "char" => char,
+        "u64" => u64, "i64" => i64, "u128" => u128, "i128" => i128, "usize" => usize, "isize" => isize,
+        "f32" => f32, "f64" => f64,
+        "c_char" => core::ffi::c_char, "c_schar" => core::ffi::c_schar, "c_uchar" => core::ffi::c_uchar,
+        "c_short" => core::ffi::c_short, "c_ushort" => core::ffi::c_ushort, "c_int" => core::ffi::c_int,
+        "c_uint" => core::ffi::c_uint, "c_long" => core::ffi::c_long, "c_ulong" => core::ffi::c_ulong,
+        "c_longlong" => core::ffi::c_longlong, "c_ulonglong" => core::ffi::c_ulonglong,
+        "c_float" => core::ffi::c_float, "c_double" => core::ffi::c_double
+    }
+}
+
+/// Removes the path of the C type aliases, e.g. `::std::os::raw::c_int` becomes `c_int`.
+fn short_type_name(type_name: &'static str) -> &'static str {
+    ["::std::os::raw::", "std::os::raw::", "::core::ffi::", "core::ffi::", "::std::ffi::"]
+        .iter()
+        .find_map(|path| type_name.strip_prefix(path))
+        .unwrap_or(type_name)
+}
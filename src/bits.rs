@@ -0,0 +1,90 @@
+//! The memory content tables as a matrix of bits.
+//!
+//! Selected with [`Preset::Bits`](crate::Preset::Bits), every row shows a byte, or a word of
+//! [`Config::words`](crate::Config::words), as its individual bits from the most significant one, set bits as `1` and
+//! clear bits as `.`, followed by the value and the numbers of the set bits. This is the view of bitflags, bitmaps and
+//! packed bitfields.
+
+use core::fmt;
+
+use crate::render::{separator, Table, ADDR_TITLE};
+
+/// Writes the title of the bits of the byte `byte` of a word, e.g. `15.....8`.
+fn write_byte_title<W: fmt::Write + ?Sized>(out: &mut W, byte: usize) -> fmt::Result {
+    let (high, low) = (byte * 8 + 7, byte * 8);
+    let digits = |bit: usize| if bit >= 10 { 2 } else { 1 };
+    write!(out, "{}", high)?;
+    for _ in digits(high) + digits(low)..8 {
+        out.write_char('.')?;
+    }
+    write!(out, "{}", low)
+}
+
+/// Writes the row of the word of `size` bytes at `index`.
+fn write_row<W: fmt::Write + ?Sized>(out: &mut W, table: &Table<'_>, index: usize, size: usize) -> fmt::Result {
+    let word = &table.bytes[index..index + size];
+    // The value of the word in the byte order of the machine
+    let value = if cfg!(target_endian = "little") {
+        word.iter().rev().fold(0u64, |value, byte| value << 8 | *byte as u64)
+    } else {
+        word.iter().fold(0u64, |value, byte| value << 8 | *byte as u64)
+    };
+    write!(out, " {} |", crate::_Addr(table.addr + index))?;
+    for bit in (0..size * 8).rev() {
+        if bit % 8 == 7 {
+            out.write_char(' ')?;
+        }
+        match (value >> bit & 1 == 1, table.color) {
+            (true, true) => write!(out, "{}1{}", BOLD, RESET)?,
+            (true, false) => out.write_char('1')?,
+            (false, _) => out.write_char('.')?,
+        }
+    }
+    write!(out, " | {:#0width$x} |", value, width = 2 + 2 * size)?;
+    let mut first = true;
+    for bit in (0..size * 8).filter(|bit| value >> bit & 1 == 1) {
+        write!(out, "{}{}", if first { " " } else { ", " }, bit)?;
+        first = false;
+    }
+    writeln!(out)
+}
+
+/// Writes the rows of the bytes in `range`, the bytes of a last incomplete word one per row.
+fn write_rows<W: fmt::Write + ?Sized>(out: &mut W, table: &Table<'_>, range: core::ops::Range<usize>, size: usize) -> fmt::Result {
+    let mut index = range.start;
+    while index < range.end {
+        let size = if index + size <= range.end { size } else { 1 };
+        write_row(out, table, index, size)?;
+        index += size;
+    }
+    Ok(())
+}
+
+/// Formats a table as a matrix of bits, the bytes omitted by `max_bytes` being replaced by a line.
+pub(crate) fn write_table<W: fmt::Write + ?Sized>(out: &mut W, table: &Table<'_>) -> fmt::Result {
+    let bytes = table.bytes;
+    let size = table.words.map_or(1, |words| words.size());
+    write!(out, "{}|", ADDR_TITLE)?;
+    for byte in (0..size).rev() {
+        out.write_char(' ')?;
+        write_byte_title(out, byte)?;
+    }
+    writeln!(out, " | {:<width$} | Set bits", "Hex", width = 2 + 2 * size)?;
+    if table.color {
+        writeln!(out, "{}{}{}", BOLD, separator(table.separator, 1), RESET)?;
+    } else {
+        writeln!(out, "{}", separator(table.separator, 1))?;
+    }
+    match crate::_omitted_range(bytes.len(), table.max_bytes) {
+        Some(omitted) => {
+            write_rows(out, table, 0..omitted.start, size)?;
+            writeln!(out, " … {} bytes omitted …", crate::Thousands(omitted.len()))?;
+            write_rows(out, table, omitted.end..bytes.len(), size)
+        }
+        None => write_rows(out, table, 0..bytes.len(), size),
+    }
+}
+
+/// ANSI escape codes of the set bits.
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
@@ -0,0 +1,313 @@
+//! Dumps of the interior mutability cells, with the borrow state of a `RefCell` decoded.
+//!
+//! A `RefCell` stores a borrow flag next to its value: `0` when unborrowed, the number of `Ref`s when shared, and a
+//! negative number while a `RefMut` exists. The [`view_cell!`](crate::view_cell) macro finds the flag in the memory of
+//! the cell, decodes it, and annotates the bytes of the flag and of the value. A `Cell` has no flag, its value is
+//! copied in and out. An `UnsafeCell` is read through `UnsafeCell::get`, its bytes labelled interior mutable. The
+//! atomic integers and `AtomicBool` are read with a single atomic load, so another thread storing a value meanwhile
+//! can't tear the dump.
+
+use std::cell::{Cell, RefCell, UnsafeCell};
+use std::fmt::{self, Write};
+use std::sync::atomic::{self, Ordering};
+
+use crate::render::{self, Table, MEMORY_CONTENT};
+
+/// A borrow state of a `RefCell`, decoded from its borrow flag.
+fn borrow_state(flag: isize) -> String {
+    match flag {
+        0 => "unborrowed".into(),
+        1 => "1 shared borrow (Ref)".into(),
+        flag if flag > 0 => format!("{} shared borrows (Ref)", flag),
+        -1 => "exclusively borrowed (RefMut)".into(),
+        flag => format!("exclusively borrowed by {} disjoint RefMuts", -flag),
+    }
+}
+
+/// Writes the `Name`, `Type`, `Addr` and `Size` lines of a cell.
+fn write_header(out: &mut String, name: &str, type_name: &str, addr: usize, size: usize) -> fmt::Result {
+    writeln!(out, "Name: {}", name)?;
+    writeln!(out, "Type: {}", type_name)?;
+    writeln!(out, "Addr: {}", crate::_HeaderAddr(addr))?;
+    writeln!(out, "Size: {} bytes", size)
+}
+
+/// Returns the offset of the borrow flag of a `RefCell` of `size` bytes whose value occupies `value`: the first
+/// aligned word outside of the value, as the layout of `RefCell` isn't specified.
+fn flag_offset(size: usize, value: core::ops::Range<usize>) -> Option<usize> {
+    const WORD: usize = core::mem::size_of::<isize>();
+    (0..size.saturating_sub(WORD - 1)).step_by(core::mem::align_of::<isize>()).find(|offset| offset + WORD <= value.start || *offset >= value.end || value.is_empty())
+}
+
+/// Writes the annotation of the byte at `offset` of a cell holding its value at `value` and its borrow flag, if any, at
+/// `flag`.
+fn annotate(value: &core::ops::Range<usize>, flag: Option<(usize, isize)>, offset: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+    const WORD: usize = core::mem::size_of::<isize>();
+    match flag {
+        Some((start, flag)) if offset == start => return write!(out, "borrow flag = {}", flag),
+        Some((start, _)) if (start..start + WORD).contains(&offset) => return write!(out, "borrow flag +{}", offset - start),
+        _ => {}
+    }
+    if offset == value.start {
+        out.write_str("value")
+    } else if value.contains(&offset) {
+        write!(out, "value +{}", offset - value.start)
+    } else {
+        out.write_str("(padding)")
+    }
+}
+
+/// Formats the borrow state and the memory content of a `RefCell`.
+pub(crate) fn write_ref_cell<T>(out: &mut String, name: &str, cell: &RefCell<T>) -> fmt::Result {
+    const WORD: usize = core::mem::size_of::<isize>();
+    let base = cell as *const RefCell<T> as *const u8;
+    let size = core::mem::size_of::<RefCell<T>>();
+    let start = (cell.as_ptr() as *const u8).addr() - base.addr();
+    let value = start..start + core::mem::size_of::<T>();
+    write_header(out, name, core::any::type_name::<RefCell<T>>(), base.addr(), size)?;
+    let Some(offset) = flag_offset(size, value.clone()) else {
+        return writeln!(out, "Brrw: the borrow flag wasn't found next to the value\n");
+    };
+    // The flag is a `Cell<isize>`, it can be read through a shared reference to the cell
+    let flag = unsafe { (base.add(offset) as *const isize).read() };
+    writeln!(out, "Brrw: {}, flag {} at +{:#x}..+{:#x}, value at +{:#x}..+{:#x}", borrow_state(flag), flag, offset, offset + WORD, value.start, value.end)?;
+    if crate::config().miri_friendly {
+        return render::write_miri_note(out);
+    }
+
+    let annotate = |index: usize, out: &mut dyn fmt::Write| annotate(&value, Some((offset, flag)), index, out);
+    if flag < 0 {
+        // A `RefMut` may be writing the value right now, only the flag is shown
+        writeln!(out, "Note: the value isn't read while it is exclusively borrowed, only the borrow flag is shown")?;
+        let bytes = unsafe { core::slice::from_raw_parts(base.add(offset), WORD) };
+        let annotate = |index: usize, out: &mut dyn fmt::Write| annotate(index + offset, out);
+        return Table { label: name, annotate: Some(&annotate), ..Table::new(MEMORY_CONTENT, base.addr() + offset, bytes) }.write(out);
+    }
+    let bytes = unsafe { core::slice::from_raw_parts(base, size) };
+    Table { label: name, annotate: Some(&annotate), ..Table::new(MEMORY_CONTENT, base.addr(), bytes) }.write(out)
+}
+
+/// Formats the memory content of a `Cell`.
+pub(crate) fn write_cell<T>(out: &mut String, name: &str, cell: &Cell<T>) -> fmt::Result {
+    let base = cell as *const Cell<T> as *const u8;
+    let size = core::mem::size_of::<Cell<T>>();
+    write_header(out, name, core::any::type_name::<Cell<T>>(), base.addr(), size)?;
+    writeln!(out, "Brrw: none, a Cell has no borrow flag, its value is copied in and out")?;
+    if crate::config().miri_friendly {
+        return render::write_miri_note(out);
+    }
+
+    let value = 0..size;
+    let annotate = |index: usize, out: &mut dyn fmt::Write| annotate(&value, None, index, out);
+    let bytes = unsafe { core::slice::from_raw_parts(base, size) };
+    Table { label: name, annotate: Some(&annotate), ..Table::new(MEMORY_CONTENT, base.addr(), bytes) }.write(out)
+}
+
+/// Formats the memory content of an `UnsafeCell`, read through `UnsafeCell::get`.
+pub(crate) fn write_unsafe_cell<T>(out: &mut String, name: &str, cell: &UnsafeCell<T>) -> fmt::Result {
+    let value = cell.get() as *const u8;
+    let size = core::mem::size_of::<T>();
+    write_header(out, name, core::any::type_name::<UnsafeCell<T>>(), value.addr(), size)?;
+    writeln!(out, "Intr: interior mutable, the bytes can change behind a shared reference, read through UnsafeCell::get")?;
+    if crate::config().miri_friendly {
+        return render::write_miri_note(out);
+    }
+    if size == 0 {
+        return render::write_zst_note(out, value.addr());
+    }
+
+    let annotate = |index: usize, out: &mut dyn fmt::Write| match index {
+        0 => out.write_str("value (interior mutable)"),
+        index => write!(out, "value +{}", index),
+    };
+    let bytes = unsafe { core::slice::from_raw_parts(value, size) };
+    Table { label: name, annotate: Some(&annotate), ..Table::new(MEMORY_CONTENT, value.addr(), bytes) }.write(out)
+}
+
+/// A cell shown by [`view_cell!`](crate::view_cell): `RefCell`, `Cell`, `UnsafeCell`, an atomic integer or
+/// `AtomicBool`.
+///
+/// (This is supposed to be private usage of view_cell! macro usage.)
+pub trait _InteriorCell {
+    /// Formats the borrow state and the memory content of the cell.
+    fn write_cell(&self, out: &mut String, name: &str) -> fmt::Result;
+}
+
+impl<T> _InteriorCell for RefCell<T> {
+    fn write_cell(&self, out: &mut String, name: &str) -> fmt::Result {
+        write_ref_cell(out, name, self)
+    }
+}
+
+impl<T> _InteriorCell for Cell<T> {
+    fn write_cell(&self, out: &mut String, name: &str) -> fmt::Result {
+        write_cell(out, name, self)
+    }
+}
+
+impl<T> _InteriorCell for UnsafeCell<T> {
+    fn write_cell(&self, out: &mut String, name: &str) -> fmt::Result {
+        write_unsafe_cell(out, name, self)
+    }
+}
+
+/// The value of an atomic, loaded once with a relaxed atomic load, with its bytes.
+///
+/// (This is supposed to be private usage of view_mem! macro usage.)
+pub struct _AtomicLoad {
+    value: String,
+    bytes: Vec<u8>,
+}
+
+/// Loads the value of an atomic integer or `AtomicBool` dumped by `view_mem!` with a single relaxed atomic load.
+///
+/// (This is supposed to be private usage of view_mem! macro usage.)
+pub trait _AtomicValue {
+    /// Returns the loaded value.
+    fn atomic_load(&self) -> Option<_AtomicLoad>;
+}
+
+/// Loads nothing for the values which aren't atomics, their bytes are read as they are.
+///
+/// (This is supposed to be private usage of view_mem! macro usage.)
+pub trait _NoAtomicValue {
+    /// Returns `None`.
+    fn atomic_load(&self) -> Option<_AtomicLoad>;
+}
+
+impl<T: ?Sized> _NoAtomicValue for &crate::_ValueProbe<'_, T> {
+    fn atomic_load(&self) -> Option<_AtomicLoad> {
+        None
+    }
+}
+
+/// Formats the value of an atomic at `addr`, loaded once, and its bytes.
+pub(crate) fn write_atomic_content(out: &mut String, name: &str, addr: usize, load: &_AtomicLoad) -> fmt::Result {
+    match crate::config().redact {
+        true => writeln!(out, "Atom: read with a single relaxed atomic load, the bytes below are those of the loaded value")?,
+        false => writeln!(out, "Atom: {}, read with a single relaxed atomic load, the bytes below are those of this value", load.value)?,
+    }
+    Table { label: name, ..Table::new(MEMORY_CONTENT, addr, &load.bytes) }.write(out)
+}
+
+/// Formats the header of an atomic, its value loaded once and its bytes.
+fn write_atomic<A>(out: &mut String, name: &str, atomic: &A, load: &_AtomicLoad) -> fmt::Result {
+    let addr = (atomic as *const A as *const u8).addr();
+    write_header(out, name, core::any::type_name::<A>(), addr, load.bytes.len())?;
+    write_atomic_content(out, name, addr, load)
+}
+
+macro_rules! atomic_cells {
+    ($($atomic: ident, $width: literal;)*) => {$(
+        #[cfg(target_has_atomic = $width)]
+        impl _AtomicValue for crate::_ValueProbe<'_, atomic::$atomic> {
+            fn atomic_load(&self) -> Option<_AtomicLoad> {
+                let value = self.0.load(Ordering::Relaxed);
+                Some(_AtomicLoad { value: value.to_string(), bytes: value.to_ne_bytes().to_vec() })
+            }
+        }
+
+        #[cfg(target_has_atomic = $width)]
+        impl _InteriorCell for atomic::$atomic {
+            fn write_cell(&self, out: &mut String, name: &str) -> fmt::Result {
+                crate::_ValueProbe(self).atomic_load().map_or(Ok(()), |load| write_atomic(out, name, self, &load))
+            }
+        }
+    )*};
+}
+
+atomic_cells! {
+    AtomicU8, "8";
+    AtomicI8, "8";
+    AtomicU16, "16";
+    AtomicI16, "16";
+    AtomicU32, "32";
+    AtomicI32, "32";
+    AtomicU64, "64";
+    AtomicI64, "64";
+    AtomicUsize, "ptr";
+    AtomicIsize, "ptr";
+}
+
+#[cfg(target_has_atomic = "8")]
+impl _AtomicValue for crate::_ValueProbe<'_, atomic::AtomicBool> {
+    fn atomic_load(&self) -> Option<_AtomicLoad> {
+        let value = self.0.load(Ordering::Relaxed);
+        Some(_AtomicLoad { value: value.to_string(), bytes: alloc::vec![value as u8] })
+    }
+}
+
+#[cfg(target_has_atomic = "8")]
+impl _InteriorCell for atomic::AtomicBool {
+    fn write_cell(&self, out: &mut String, name: &str) -> fmt::Result {
+        crate::_ValueProbe(self).atomic_load().map_or(Ok(()), |load| write_atomic(out, name, self, &load))
+    }
+}
+
+#[macro_export]
+/// Macro to view a `RefCell` with its borrow flag decoded, a `Cell`, an `UnsafeCell`, or an atomic.
+///
+/// The borrow flag of a `RefCell` is found next to the value and decoded on a `Brrw` line: unborrowed, the number of
+/// shared borrows, or exclusively borrowed. The bytes of the flag and of the value are annotated in the table. While
+/// the cell is exclusively borrowed, the value may be written through the `RefMut`: it isn't read, only the flag is
+/// shown. A `Cell` has no flag, its bytes are those of its value. An `UnsafeCell` is read through `UnsafeCell::get`,
+/// and an `Intr` line tells that its bytes can change behind a shared reference. In Miri-friendly mode the memory
+/// content of these cells isn't shown.
+///
+/// An atomic integer or `AtomicBool` is read with a single `Ordering::Relaxed` load instead of byte reads, which would
+/// be a data race if another thread stored a value meanwhile and could mix the bytes of two values: the loaded value
+/// is printed on an `Atom` line, and its bytes in the table. `view_mem!` reads the atomics the same way.
+///
+/// # Argument
+///
+/// * `var` - The `RefCell`, `Cell`, `UnsafeCell`, atomic integer or `AtomicBool` to dump.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+/// use std::cell::RefCell;
+///
+/// let my_cell = RefCell::new(0x2a_u32);
+/// let _first = my_cell.borrow();
+/// let _second = my_cell.borrow();
+/// view_cell!(my_cell);
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_cell
+/// Type: core::cell::RefCell<u32>
+/// Addr: 00007ffd4c3e2a30
+/// Size: 16 bytes
+/// Brrw: 2 shared borrows (Ref), flag 2 at +0x0..+0x8, value at +0x8..+0xc
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ----------------------Memory Content--------------------
+///  00007ffd4c3e2a30 | 02  | 002 | 00000010 |  STX  | ... | borrow flag = 2
+///  00007ffd4c3e2a31 | 00  | 000 | 00000000 |  NUL  | ... | borrow flag +1
+/// ...
+///  00007ffd4c3e2a38 | 2a  | 042 | 00101010 |   *   | ... | value
+///  00007ffd4c3e2a39 | 00  | 000 | 00000000 |  NUL  | ... | value +1
+///  00007ffd4c3e2a3a | 00  | 000 | 00000000 |  NUL  | ... | value +2
+///  00007ffd4c3e2a3b | 00  | 000 | 00000000 |  NUL  | ... | value +3
+///  00007ffd4c3e2a3c | 00  | 000 | 00000000 |  NUL  | ... | (padding)
+/// ...
+/// ```
+macro_rules! view_cell {
+    ($var: expr) => {
+        $crate::_show_cell(stringify!($var), &$var)
+    };
+}
+
+/// Displays the borrow state and the memory content of a cell.
+///
+/// (This is supposed to be private usage of view_cell! macro usage.)
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `cell` - The `RefCell`, `Cell`, `UnsafeCell`, atomic integer or `AtomicBool` to display.
+pub fn _show_cell<C: _InteriorCell>(name: &str, cell: &C) {
+    render::emit(|out| cell.write_cell(out, name));
+}
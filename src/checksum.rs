@@ -0,0 +1,169 @@
+//! Checksums of the dumped bytes, as used by wire protocols and storage formats.
+//!
+//! Select the algorithms with [`Config::checksums`](crate::Config::checksums) to print them below every memory content
+//! table, so the checksum of a packet or a record can be checked against the one it carries without a separate script.
+
+use core::fmt;
+
+/// A checksum algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Checksum {
+    /// CRC-16/CCITT-FALSE (polynomial `0x1021`, initial value `0xffff`), used by many framing protocols.
+    Crc16CcittFalse,
+    /// CRC-16/XMODEM (polynomial `0x1021`, initial value `0`), used by XMODEM and Bluetooth.
+    Crc16Xmodem,
+    /// CRC-16/KERMIT (reflected polynomial `0x1021`, initial value `0`), the CRC of HDLC and X.25 is its complement.
+    Crc16Kermit,
+    /// CRC-16/MODBUS (reflected polynomial `0x8005`, initial value `0xffff`), sent low byte first by Modbus RTU.
+    Crc16Modbus,
+    /// CRC-16/ARC (reflected polynomial `0x8005`, initial value `0`), also known as CRC-16/IBM.
+    Crc16Arc,
+    /// CRC-32 (reflected polynomial `0x04c11db7`), used by Ethernet, zlib and PNG.
+    Crc32,
+    /// Fletcher-16, over bytes.
+    Fletcher16,
+    /// Fletcher-32, over little-endian 16-bit words, an odd last byte being padded with a zero byte.
+    Fletcher32,
+    /// The Internet checksum of IP, UDP and TCP (RFC 1071): the ones' complement of the ones' complement sum of the
+    /// big-endian 16-bit words. It is `0` over a header including its valid checksum.
+    Internet,
+}
+
+impl Checksum {
+    /// Every algorithm, in the order of the declaration.
+    pub const ALL: [Checksum; 9] = [
+        Checksum::Crc16CcittFalse,
+        Checksum::Crc16Xmodem,
+        Checksum::Crc16Kermit,
+        Checksum::Crc16Modbus,
+        Checksum::Crc16Arc,
+        Checksum::Crc32,
+        Checksum::Fletcher16,
+        Checksum::Fletcher32,
+        Checksum::Internet,
+    ];
+
+    /// Returns the usual name of the algorithm, e.g. `CRC-16/MODBUS`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Checksum::Crc16CcittFalse => "CRC-16/CCITT-FALSE",
+            Checksum::Crc16Xmodem => "CRC-16/XMODEM",
+            Checksum::Crc16Kermit => "CRC-16/KERMIT",
+            Checksum::Crc16Modbus => "CRC-16/MODBUS",
+            Checksum::Crc16Arc => "CRC-16/ARC",
+            Checksum::Crc32 => "CRC-32",
+            Checksum::Fletcher16 => "Fletcher-16",
+            Checksum::Fletcher32 => "Fletcher-32",
+            Checksum::Internet => "Internet",
+        }
+    }
+
+    /// Returns the number of bits of the checksum, 16 or 32.
+    pub fn bits(&self) -> u32 {
+        match self {
+            Checksum::Crc32 | Checksum::Fletcher32 => 32,
+            _ => 16,
+        }
+    }
+
+    /// Computes the checksum of `bytes`.
+    ///
+    /// # Argument
+    ///
+    /// * `bytes` - The checksummed bytes, e.g. the payload of a packet.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// assert_eq!(Checksum::Crc16Modbus.compute(b"123456789"), 0x4b37);
+    /// assert_eq!(Checksum::Internet.compute(&[0x00, 0x01, 0xf2, 0x03, 0xf4, 0xf5, 0xf6, 0xf7]), 0x220d);
+    /// ```
+    pub fn compute(&self, bytes: &[u8]) -> u32 {
+        match self {
+            Checksum::Crc16CcittFalse => crc16(bytes, 0x1021, 0xffff, false) as u32,
+            Checksum::Crc16Xmodem => crc16(bytes, 0x1021, 0, false) as u32,
+            Checksum::Crc16Kermit => crc16(bytes, 0x1021, 0, true) as u32,
+            Checksum::Crc16Modbus => crc16(bytes, 0x8005, 0xffff, true) as u32,
+            Checksum::Crc16Arc => crc16(bytes, 0x8005, 0, true) as u32,
+            Checksum::Crc32 => crc32(bytes),
+            Checksum::Fletcher16 => fletcher16(bytes),
+            Checksum::Fletcher32 => fletcher32(bytes),
+            Checksum::Internet => internet(bytes),
+        }
+    }
+}
+
+/// Computes a CRC-16 without final XOR, bit by bit. Reflected CRCs shift the bits out least significant first, with
+/// the mirrored polynomial.
+fn crc16(bytes: &[u8], poly: u16, init: u16, reflected: bool) -> u16 {
+    let mut crc = init;
+    if reflected {
+        let poly = poly.reverse_bits();
+        for byte in bytes {
+            crc ^= *byte as u16;
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 { crc >> 1 ^ poly } else { crc >> 1 };
+            }
+        }
+    } else {
+        for byte in bytes {
+            crc ^= (*byte as u16) << 8;
+            for _ in 0..8 {
+                crc = if crc & 0x8000 != 0 { crc << 1 ^ poly } else { crc << 1 };
+            }
+        }
+    }
+    crc
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let poly = 0x04c1_1db7u32.reverse_bits();
+    let mut crc = u32::MAX;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { crc >> 1 ^ poly } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn fletcher16(bytes: &[u8]) -> u32 {
+    let (mut sum1, mut sum2) = (0u32, 0u32);
+    for byte in bytes {
+        sum1 = (sum1 + *byte as u32) % 255;
+        sum2 = (sum2 + sum1) % 255;
+    }
+    sum2 << 8 | sum1
+}
+
+fn fletcher32(bytes: &[u8]) -> u32 {
+    let (mut sum1, mut sum2) = (0u32, 0u32);
+    for word in bytes.chunks(2) {
+        let word = u16::from_le_bytes([word[0], word.get(1).copied().unwrap_or(0)]);
+        sum1 = (sum1 + word as u32) % 65535;
+        sum2 = (sum2 + sum1) % 65535;
+    }
+    sum2 << 16 | sum1
+}
+
+fn internet(bytes: &[u8]) -> u32 {
+    let mut sum = 0u32;
+    for word in bytes.chunks(2) {
+        sum += u16::from_be_bytes([word[0], word.get(1).copied().unwrap_or(0)]) as u32;
+        // Fold the carry back in before the sum can overflow
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !sum & 0xffff
+}
+
+/// Writes a `Csum` line for each algorithm, the footer of the memory content tables.
+pub(crate) fn write_checksums<W: fmt::Write + ?Sized>(out: &mut W, checksums: &[Checksum], bytes: &[u8]) -> fmt::Result {
+    for checksum in checksums {
+        let digits = checksum.bits() as usize / 4;
+        writeln!(out, "Csum: {} = {:#0width$x}", checksum.name(), checksum.compute(bytes), width = digits + 2)?;
+    }
+    Ok(())
+}
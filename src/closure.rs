@@ -0,0 +1,195 @@
+//! Dumps of closures, with their captured variables found among their bytes.
+//!
+//! A closure is a struct generated by the compiler with a field per captured variable: a reference to the variable,
+//! or its value when it is moved or copied in. Neither the fields nor their order can be named in Rust. The
+//! [`view_closure!`](crate::view_closure) macro dumps the bytes of a closure and looks for the variables it is given:
+//! a capture by reference is a word holding the address of the variable, or of one of its fields when the closure
+//! only uses that field, and a capture by value is a copy of the bytes of the variable.
+
+use std::fmt::{self, Write};
+
+use crate::render::{self, Table, MEMORY_CONTENT};
+
+const WORD: usize = core::mem::size_of::<usize>();
+
+/// A variable which may be captured by a closure, given to [`view_closure!`](crate::view_closure).
+///
+/// (This is supposed to be private usage of view_closure! macro usage.)
+pub struct _Capture<'a> {
+    name: &'static str,
+    type_name: &'static str,
+    addr: usize,
+    bytes: &'a [u8],
+    align: usize,
+}
+
+impl<'a> _Capture<'a> {
+    /// Describes the variable `value`, named `name`.
+    pub fn of<T: ?Sized>(name: &'static str, value: &'a T) -> Self {
+        let ptr = value as *const T as *const u8;
+        // Like `view_mem!`, the bytes are read whatever the type, padding included
+        let bytes = unsafe { core::slice::from_raw_parts(ptr, core::mem::size_of_val(value)) };
+        _Capture { name, type_name: core::any::type_name::<T>(), addr: ptr.addr(), bytes, align: core::mem::align_of_val(value) }
+    }
+}
+
+/// Where a variable was found in a closure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Found {
+    /// A word at this offset holds the address of the variable, plus `within` when it refers to one of its fields.
+    Reference { offset: usize, within: usize },
+    /// The bytes of the variable are copied at this offset.
+    Value { offset: usize },
+    /// Neither its address nor its bytes are in the closure.
+    Missing,
+}
+
+/// Finds the captures among the bytes of a closure: the references first, then the values, in bytes not taken yet.
+fn find(bytes: &[u8], captures: &[_Capture]) -> Vec<Found> {
+    let mut taken = vec![false; bytes.len()];
+    let mut found = vec![Found::Missing; captures.len()];
+    let words: Vec<(usize, usize)> = (0..bytes.len() / WORD).map(|index| (index * WORD, usize::from_ne_bytes(bytes[index * WORD..(index + 1) * WORD].try_into().unwrap()))).collect();
+    for (capture, found) in captures.iter().zip(found.iter_mut()) {
+        let refers = |word: usize| word == capture.addr || (capture.addr..capture.addr + capture.bytes.len()).contains(&word);
+        if let Some(&(offset, word)) = words.iter().find(|(offset, word)| !taken[*offset] && refers(*word)) {
+            taken[offset..offset + WORD].fill(true);
+            *found = Found::Reference { offset, within: word - capture.addr };
+        }
+    }
+    for (capture, found) in captures.iter().zip(found.iter_mut()) {
+        let size = capture.bytes.len();
+        if *found != Found::Missing || size == 0 || size > bytes.len() {
+            continue;
+        }
+        let free = |offset: usize| !taken[offset..offset + size].contains(&true);
+        if let Some(offset) = (0..=bytes.len() - size).step_by(capture.align).find(|offset| free(*offset) && bytes[*offset..*offset + size] == *capture.bytes) {
+            taken[offset..offset + size].fill(true);
+            *found = Found::Value { offset };
+        }
+    }
+    found
+}
+
+/// Writes the annotation of the byte at `offset` of a closure: the capture it belongs to, if any.
+fn annotate(captures: &[_Capture], found: &[Found], offset: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+    for (capture, found) in captures.iter().zip(found) {
+        match *found {
+            Found::Reference { offset: start, within } if offset == start && within > 0 => return write!(out, "&{} +{:#x}", capture.name, within),
+            Found::Reference { offset: start, .. } if offset == start => return write!(out, "&{}", capture.name),
+            Found::Reference { offset: start, .. } if (start..start + WORD).contains(&offset) => return write!(out, "&{} +{}", capture.name, offset - start),
+            Found::Value { offset: start } if offset == start => return write!(out, "{}: {}", capture.name, capture.type_name),
+            Found::Value { offset: start } if (start..start + capture.bytes.len()).contains(&offset) => return write!(out, "{} +{}", capture.name, offset - start),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Formats a closure: its header, where the captures are found in it, then its bytes annotated with them.
+pub(crate) fn write_closure<F: ?Sized>(out: &mut String, name: &str, closure: &F, captures: &[_Capture]) -> fmt::Result {
+    let ptr = closure as *const F as *const u8;
+    let size = core::mem::size_of_val(closure);
+    crate::write::write_header(out, name, core::any::type_name::<F>(), ptr.addr(), size)?;
+    if size == 0 {
+        writeln!(out, "Capt: nothing stored, the closure is zero-sized: it captures no variable, or only zero-sized ones")?;
+        return writeln!(out);
+    }
+    if crate::config().miri_friendly {
+        return render::write_miri_note(out);
+    }
+
+    let bytes = unsafe { core::slice::from_raw_parts(ptr, size) };
+    let found = find(bytes, captures);
+    for (index, (capture, found)) in captures.iter().zip(&found).enumerate() {
+        let label = if index == 0 { "Capt: " } else { "      " };
+        write!(out, "{}{}: {}, ", label, capture.name, capture.type_name)?;
+        match *found {
+            Found::Reference { offset, within: 0 } => writeln!(out, "by reference at +{:#x}", offset)?,
+            Found::Reference { offset, within } => writeln!(out, "by reference at +{:#x}, to its bytes at +{:#x}: a field captured alone", offset, within)?,
+            Found::Value { offset } => writeln!(out, "by value at +{:#x}, {} bytes", offset, capture.bytes.len())?,
+            Found::Missing if capture.bytes.is_empty() => writeln!(out, "zero-sized, nothing stored even if captured")?,
+            Found::Missing => writeln!(out, "not found: not captured, or changed since")?,
+        }
+    }
+    let matched: usize = found.iter().zip(captures).map(|(found, capture)| match found {
+        Found::Reference { .. } => WORD,
+        Found::Value { .. } => capture.bytes.len(),
+        Found::Missing => 0,
+    }).sum();
+    if matched < size {
+        writeln!(out, "Rest: {} of the {} bytes not matched: captures of other variables, values moved in, or padding", size - matched, size)?;
+    }
+
+    let annotate = |offset: usize, out: &mut dyn fmt::Write| annotate(captures, &found, offset, out);
+    Table { label: name, annotate: Some(&annotate), ..Table::new(MEMORY_CONTENT, ptr.addr(), bytes) }.write(out)
+}
+
+#[macro_export]
+/// Macro to view the memory content of a closure, with the variables it captures found among its bytes.
+///
+/// The closure is a struct generated by the compiler, whose fields are its captures: the macro shows its size and its
+/// bytes, and looks for the variables given after it. A variable captured by reference is found by its address, held
+/// in a word of the closure, or the address of one of its fields when only that field is used; a variable captured by
+/// value, moved or copied in, is found by its bytes. A `Capt` line tells where each variable is, and a `Rest` line
+/// counts the bytes matched to none, such as values moved into the closure, which can't be named anymore. A variable
+/// captured by mutable reference can't be named while the closure lives, its word is among the rest. A closure
+/// capturing nothing is zero-sized. In Miri-friendly mode the memory content isn't shown.
+///
+/// # Arguments
+///
+/// * `closure` - The closure to dump.
+/// * `captures` - The variables which may be captured, if any, separated by commas.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_limit: u32 = 10;
+/// let my_scale: u16 = 3;
+/// let my_name = String::from("mem");
+/// let my_closure = move |value: u32| value.min(my_limit) * my_scale as u32 + my_name.len() as u32;
+/// view_closure!(my_closure, my_limit, my_scale);
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_closure
+/// Type: rust_out::main::{{closure}}
+/// Addr: 00007ffe3b2c1a10
+/// Size: 32 bytes
+/// Capt: my_limit: u32, by value at +0x18, 4 bytes
+///       my_scale: u16, by value at +0x1c, 2 bytes
+/// Rest: 26 of the 32 bytes not matched: captures of other variables, values moved in, or padding
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ----------------------Memory Content--------------------
+///  00007ffe3b2c1a10 | 03  | 003 | 00000011 |  ETX  | ... |
+/// ...
+///  00007ffe3b2c1a28 | 0a  | 010 | 00001010 |  LF   | ... | my_limit: u32
+///  00007ffe3b2c1a29 | 00  | 000 | 00000000 |  NUL  | ... | my_limit +1
+///  00007ffe3b2c1a2a | 00  | 000 | 00000000 |  NUL  | ... | my_limit +2
+///  00007ffe3b2c1a2b | 00  | 000 | 00000000 |  NUL  | ... | my_limit +3
+///  00007ffe3b2c1a2c | 03  | 003 | 00000011 |  ETX  | XXX | my_scale: u16
+///  00007ffe3b2c1a2d | 00  | 000 | 00000000 |  NUL  | XXX | my_scale +1
+///  00007ffe3b2c1a2e | 00  | 000 | 00000000 |  NUL  | XXX |
+///  00007ffe3b2c1a2f | 00  | 000 | 00000000 |  NUL  | XXX |
+/// ```
+macro_rules! view_closure {
+    ($closure: expr $(, $capture: expr)* $(,)?) => {
+        $crate::_show_closure(stringify!($closure), &$closure, &[$($crate::_Capture::of(stringify!($capture), &$capture)),*])
+    };
+}
+
+/// Displays the memory content of a closure, with the captures found in it.
+///
+/// (This is supposed to be private usage of view_closure! macro usage.)
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `closure` - The closure to display.
+/// * `captures` - The variables looked for among its bytes.
+pub fn _show_closure<F: ?Sized>(name: &str, closure: &F, captures: &[_Capture]) {
+    render::emit(|out| write_closure(out, name, closure, captures));
+}
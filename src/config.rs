@@ -0,0 +1,217 @@
+//! Global configuration of the dump output.
+
+#[cfg(feature = "std")]
+use std::sync::{OnceLock, RwLock};
+
+use crate::checksum::Checksum;
+use crate::transform::Transform;
+use crate::elements::ElementType;
+use crate::words::Word;
+
+/// Options controlling how `view_mem!` and `safe_view_mem!` render their output.
+///
+/// The configuration is global to the process, change it with [`set_config`]. Without the `std` feature there is no
+/// global state, every dump uses the default configuration.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// set_config(Config { max_bytes: 64, ..Config::default() });
+///
+/// let my_vec: Vec<u8> = vec![0; 1024];
+/// view_mem!(*my_vec); // Prints the first and last 32 bytes only.
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Maximum number of bytes printed by a single dump, `0` means unlimited.
+    ///
+    /// Larger regions print the first and the last `max_bytes / 2` bytes with a marker line telling how many bytes
+    /// were omitted in between.
+    pub max_bytes: usize,
+    /// Print the current thread (id and name), process id and a UTC timestamp in the dump header, so dumps collected
+    /// from multi-threaded runs can be attributed. Disabled by default.
+    pub header_context: bool,
+    /// Number of frames of the call stack printed in the dump header as `Call` lines, innermost first, e.g.
+    /// `Call: my_crate::handler at src/handler.rs:42`, so the dumps scattered through a codebase can be traced back to
+    /// where they were taken. Requires the `symbols` feature, 0 (disabled) by default.
+    pub header_backtrace: usize,
+    /// Keep `view_mem!` and the other value dumps usable under Miri: the memory content of the value isn't read, since
+    /// its padding bytes may be uninitialized and reading them is undefined behavior, and the headers of `view_mem!`
+    /// and `sink_mem` hide the address, which changes from run to run. Enabled by default under Miri (`cfg(miri)`).
+    pub miri_friendly: bool,
+    /// Checksums of the dumped bytes printed below the memory content tables, e.g. `&[Checksum::Internet]` to check
+    /// the checksum of a dumped IP header. They cover every byte of the dump, including the bytes omitted by
+    /// `max_bytes`. None by default.
+    pub checksums: &'static [Checksum],
+    /// Color the tables printed to stdout: `Some(true)` always, `Some(false)` never, `None` (default) only on a
+    /// terminal without `NO_COLOR`, see [`detect_terminal`](crate::detect_terminal).
+    pub color: Option<bool>,
+    /// Write a `Stat` line below the memory content tables with the number of zero, printable ASCII, `0xff` and
+    /// distinct bytes, a quick check that a buffer was initialized. Like checksums, it covers every byte of the dump.
+    /// False by default.
+    pub statistics: bool,
+    /// Number of most frequent byte values listed below the memory content tables on `Hist` lines, with their count,
+    /// share and a bar, to spot fill patterns (`00`, `ff`, the `cd` of the MSVC debug heap…) and uninitialized memory.
+    /// Like checksums, they cover every byte of the dump. 0 (none) by default.
+    pub histogram: usize,
+    /// Write the Shannon entropy of the dumped bytes below the memory content tables, for the whole region and every
+    /// 16-byte row, to tell compressed or encrypted blobs (close to 8 bits per byte) from structured data. The rows of
+    /// the bytes omitted by `max_bytes` are omitted too. Requires the `std` feature, disabled by default.
+    pub entropy: bool,
+    /// Adds the bytes transformed by a byte swap or a bit reversal next to the original ones in the memory content
+    /// tables, to spot endianness bugs. None by default.
+    pub transform: Option<Transform>,
+    /// Transformation applied to the bytes before the memory content tables are rendered, e.g.
+    /// `Some(Transform::Xor(&[0x5a]))` to read a payload obfuscated with a XOR key: every column, and the checksums
+    /// and statistics below the table, show the transformed bytes, and a `Deco` line above the table tells which
+    /// transformation was applied. None by default.
+    pub decode: Option<Transform>,
+    /// Adds the high and low nibbles of every byte to the memory content tables, in hex and binary, with the two
+    /// decimal digits of the byte read as packed BCD (`--` if a nibble is above 9). False by default.
+    pub nibbles: bool,
+    /// Adds a `Glyph` column to the memory content tables with the UTF-8 characters decoded from the bytes: every
+    /// character is shown on the row of its first byte, `┌` marking it when it takes several bytes, and `│` and `└`
+    /// span the rows of its other bytes. ASCII controls are shown as their pictures (`␊`), the other invisible
+    /// characters as `⍰` and the bytes of no valid character as `�`. False by default.
+    pub glyphs: bool,
+    /// Adds the little- and big-endian values of every 2, 4 or 8-byte word to the memory content tables, on the row
+    /// of the first byte of the word. None by default.
+    pub words: Option<Word>,
+    /// Shows the bytes of every 2, 4 or 8-byte word of the memory content tables in reverse order, so that the values
+    /// stored in the other byte order read from top to bottom, e.g. the big-endian fields of a network capture on a
+    /// little-endian machine. A line above the table tells the byte order of the machine. The rows keep their address
+    /// and annotation, they are only reordered. None by default.
+    pub swap: Option<Word>,
+    /// Adds a column per type to the memory content tables, with the value of the bytes read as this type in native
+    /// byte order, on the rows of the offsets aligned to its size. None by default.
+    pub interpretations: &'static [ElementType],
+    /// Replace the values of the bytes of the memory content tables by `*`, keeping their addresses, the annotations,
+    /// the size and the FNV-1a hash of the content on an `Rdct` line, so the layout of memory holding keys or tokens
+    /// can be shown in logs. The extra columns, the presets and the footers (checksums, statistics…) are left out, as
+    /// they would tell the values, and so are the decoded values and texts of the headers and annotations (128-bit
+    /// integers, `NonZero` values, floats, SIMD lanes, chars, strings, C strings, UTF-16 text, the rows of
+    /// `grep_mem!`, the values and fields of the registers of a `RegisterMap` and the CPU registers of `view_regs!`).
+    /// The hash tells whether two contents are identical, it doesn't hide low-entropy secrets such as PINs, which can
+    /// be found by trying them all. The exports of `MemDump` (`to_csv`, `to_html`…) aren't redacted. False by default.
+    pub redact: bool,
+    /// Replace the runs of at least 16 zero bytes of the memory content tables by a `… N zero bytes skipped …` line,
+    /// so dumps of mostly empty arenas and zero-initialized buffers stay short. Shorter runs, such as padding, are
+    /// shown. False by default.
+    pub sparse: bool,
+    /// Write a `Fill` line above the memory content tables for every run of at least 4 bytes of a debug fill value,
+    /// e.g. `Fill: +0x10..+0x30, 32 bytes of dd, MSVC freed heap`, so dumps of freed, uninitialized or guard memory
+    /// tell so at once. The values are those of the MSVC debug CRT and heap (`cc`, `cd`, `dd`, `fd`, `fe`, `ab`),
+    /// of the pattern initialization of Clang and GCC (`aa`) and of jemalloc (`a5`, `5a`). Like checksums, the runs
+    /// cover every byte of the dump. False by default.
+    pub fill_patterns: bool,
+    /// Write a `Symb` line above the memory content tables for every aligned word holding the address of a symbol of a
+    /// loaded module, a function pointer, a vtable entry or a return address, e.g.
+    /// `Symb: +0x8 = 000055d1c3a3fb10 <my_crate::handler+0x1a>`, see [`resolve_symbol`](crate::resolve_symbol).
+    /// Requires the `symbols` feature, disabled by default.
+    pub symbols: bool,
+    /// How the addresses are printed: absolute, or relative to the base of the loaded module containing them, e.g.
+    /// `my_app+0x4a1a0`, so dumps of statics from different runs compare equal despite address space layout
+    /// randomization. Absolute by default.
+    pub address_style: AddressStyle,
+    /// Layout of the memory content tables.
+    pub preset: Preset,
+    /// Print the table of a content only once per run: the following dumps of the same bytes, e.g. from every
+    /// iteration of a loop, print a one-line reference to the first table instead, see
+    /// [`reset_dedup`](crate::reset_dedup). Tables formatted with `Display` or `write_mem` are never replaced.
+    /// Requires the `std` feature, disabled by default.
+    pub dedup: bool,
+}
+
+/// A layout of the memory content tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Preset {
+    /// One byte per row, with its hex, decimal, binary, ASCII and UTF-8 representations (default).
+    #[default]
+    Classic,
+    /// 16 bytes per row between box-drawing borders, with a character panel and colors, like the `hexyl` hex viewer.
+    /// Annotations, transformed bytes, nibbles, glyphs, words and interpretations aren't shown in this layout, nor are
+    /// bytes swapped, zero bytes skipped or elements separated.
+    Hexyl,
+    /// The output of `od -A x -t x1z`, offsets from the start of the region included, to compare dumps with `od` in
+    /// scripts. Annotations, transformed bytes, nibbles, glyphs, words and interpretations aren't shown in this layout,
+    /// nor are bytes swapped, zero bytes skipped or elements separated.
+    Od,
+    /// One byte per row as a matrix of its bits, set bits as `1` (bold in color) and clear bits as `.`, with its value
+    /// and the numbers of its set bits, to inspect bitflags and bitmaps. With `words`, a row is a word of that size
+    /// read in the byte order of the machine. Annotations, transformed bytes, nibbles, glyphs and interpretations aren't
+    /// shown in this layout, nor are bytes swapped, zero bytes skipped or elements separated.
+    Bits,
+}
+
+/// How the addresses of the dumps are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AddressStyle {
+    /// The address in the process, e.g. `000055d1c3a3fb10` (default).
+    #[default]
+    Absolute,
+    /// The offset from the base of the loaded module containing the address, e.g. `my_app+0x4a1a0` in the headers,
+    /// the tables of such addresses showing the offsets below a `Base` line naming the module. The addresses outside of
+    /// the modules (stack, heap...) stay absolute. Requires the `std` feature and a system whose modules are listed by
+    /// [`loaded_modules`](crate::loaded_modules).
+    ModuleRelative,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            max_bytes: 4096,
+            header_context: false,
+            header_backtrace: 0,
+            miri_friendly: cfg!(miri),
+            checksums: &[],
+            color: None,
+            statistics: false,
+            histogram: 0,
+            entropy: false,
+            transform: None,
+            decode: None,
+            nibbles: false,
+            glyphs: false,
+            words: None,
+            swap: None,
+            interpretations: &[],
+            redact: false,
+            sparse: false,
+            fill_patterns: false,
+            symbols: false,
+            address_style: AddressStyle::Absolute,
+            preset: Preset::Classic,
+            dedup: false,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn global() -> &'static RwLock<Config> {
+    static CONFIG: OnceLock<RwLock<Config>> = OnceLock::new();
+    CONFIG.get_or_init(|| RwLock::new(Config::default()))
+}
+
+/// Returns a copy of the current global configuration.
+#[cfg(feature = "std")]
+pub fn config() -> Config {
+    global().read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Returns the default configuration, which can't be changed without the `std` feature.
+#[cfg(not(feature = "std"))]
+pub fn config() -> Config {
+    Config::default()
+}
+
+/// Replaces the global configuration.
+///
+/// # Argument
+///
+/// * `config` - The new configuration, used by every dump from now on.
+#[cfg(feature = "std")]
+pub fn set_config(config: Config) {
+    *global().write().unwrap_or_else(|e| e.into_inner()) = config;
+}
@@ -0,0 +1,641 @@
+//! Dumps of the standard containers, split between their handle and their heap allocation.
+//!
+//! `view_mem!(my_vec)` dumps the pointer, capacity and length of a `Vec`, whose order isn't even specified, and
+//! `view_mem!(*my_vec)` its elements but not its spare capacity. The [`view_vec!`](crate::view_vec) macro dumps the
+//! handle with its words named, the initialized elements, then the spare capacity, clearly marked as uninitialized.
+//! [`view_string!`](crate::view_string) does the same for a `String` or a `&str`, or an OS string or path, with the
+//! decoded text, and
+//! [`view_heap!`](crate::view_heap) for any `Box`, `Vec`, `String`, `Rc` or `Arc`.
+
+use std::ffi::{OsStr, OsString};
+use std::fmt::{self, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::render::{self, Table};
+
+/// Separator line of the tables of the handles of the containers.
+pub(crate) const HANDLE: &str = "-------------------------Handle-------------------------";
+
+/// Separator line of the tables of the initialized elements of the containers.
+const INITIALIZED_ELEMENTS: &str = "------------------Initialized Elements------------------";
+
+/// Separator line of the tables of the bytes of the strings.
+const TEXT_BYTES: &str = "-----------------------Text Bytes-----------------------";
+
+/// Separator line of the tables of the values of the boxes and reference-counted pointers.
+const HEAP_ALLOCATION: &str = "--------------------Heap Allocation---------------------";
+
+/// Separator line of the tables of the spare capacity of the containers.
+const SPARE_CAPACITY: &str = "-------------Spare Capacity (uninitialized)-------------";
+
+/// A word of a container handle: its name, its value and whether the value is an address.
+pub(crate) type Field = (&'static str, usize, bool);
+
+/// Writes the annotation of the byte at `offset` of a handle made of `bytes`: the names of the fields whose value is
+/// the word containing the byte, several ones when they are equal (e.g. `len/cap` for a full `Vec`).
+pub(crate) fn annotate_handle(bytes: &[u8], fields: &[Field], offset: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+    const WORD: usize = core::mem::size_of::<usize>();
+    let start = offset - offset % WORD;
+    let Some(word) = bytes.get(start..start + WORD) else {
+        return Ok(());
+    };
+    let value = usize::from_ne_bytes(word.try_into().unwrap());
+    let mut matching = fields.iter().filter(|field| field.1 == value).peekable();
+    let Some(&(_, _, addr)) = matching.peek().copied() else {
+        return Ok(());
+    };
+    for (index, (name, _, _)) in matching.enumerate() {
+        if index > 0 {
+            out.write_char('/')?;
+        }
+        out.write_str(name)?;
+    }
+    if offset != start {
+        write!(out, " +{}", offset - start)
+    } else if addr {
+        write!(out, " = {}", crate::_Addr(value))
+    } else {
+        write!(out, " = {}", value)
+    }
+}
+
+/// Writes the annotation of the byte at `offset` of elements of `size` bytes, the first one at `first`.
+fn annotate_element(size: usize, first: usize, offset: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+    let index = first + offset / size;
+    match offset % size {
+        0 => write!(out, "[{}]", index),
+        within => write!(out, "[{}] +{}", index, within),
+    }
+}
+
+/// The handle of a container and the heap allocation it points to.
+struct Parts<'a> {
+    /// The bytes of the handle.
+    handle: &'a [u8],
+    /// The words of the handle.
+    fields: &'a [Field],
+    /// The first element.
+    ptr: *const u8,
+    /// The number of initialized elements.
+    len: usize,
+    /// The number of elements the allocation can hold.
+    capacity: usize,
+    /// The size of an element.
+    size: usize,
+    /// The separator line of the table of the elements.
+    separator: &'static str,
+    /// Annotate the bytes of the elements with their index, else they are the bytes of a text, marked as UTF-8.
+    indexed: bool,
+    /// Replace the values of the bytes by `*` in the tables.
+    redact: bool,
+}
+
+/// Formats the handle, the initialized elements and the spare capacity of a container, below the header lines.
+fn write_parts(out: &mut String, name: &str, parts: &Parts) -> fmt::Result {
+    if crate::config().miri_friendly {
+        return render::write_miri_note(out);
+    }
+
+    let Parts { handle, fields, ptr, len, capacity, size, separator, indexed, redact } = *parts;
+    let annotate = |offset: usize, out: &mut dyn fmt::Write| annotate_handle(handle, fields, offset, out);
+    let table = Table { label: name, annotate: Some(&annotate), redact, ..Table::new(HANDLE, handle.as_ptr().addr(), handle) };
+    table.write(out)?;
+    if size == 0 || capacity == 0 {
+        return Ok(());
+    }
+
+    let initialized = unsafe { core::slice::from_raw_parts(ptr, len * size) };
+    let annotate = |offset: usize, out: &mut dyn fmt::Write| annotate_element(size, 0, offset, out);
+    let mut table = Table { label: name, text: !indexed, element: size, redact, ..Table::new(separator, ptr.addr(), initialized) };
+    if indexed {
+        table.annotate = Some(&annotate);
+    }
+    table.write(out)?;
+    if capacity == len {
+        return Ok(());
+    }
+    // The spare capacity is uninitialized: its bytes are whatever the allocator left there
+    let spare = unsafe { crate::dump::read_volatile_bytes(ptr.add(len * size), (capacity - len) * size) };
+    let annotate = |offset: usize, out: &mut dyn fmt::Write| {
+        out.write_str("uninit")?;
+        if indexed {
+            out.write_char(' ')?;
+            annotate_element(size, len, offset, out)?;
+        }
+        Ok(())
+    };
+    let table = Table { label: name, annotate: Some(&annotate), element: size, redact, ..Table::new(SPARE_CAPACITY, ptr.addr() + len * size, &spare) };
+    table.write(out)
+}
+
+/// Writes the `Name`, `Type`, `Addr` and `Size` lines of a container whose handle is made of `handle`.
+pub(crate) fn write_header(out: &mut String, name: &str, type_name: &str, handle: &[u8]) -> fmt::Result {
+    writeln!(out, "Name: {}", name)?;
+    writeln!(out, "Type: {}", type_name)?;
+    writeln!(out, "Addr: {}", crate::_HeaderAddr(handle.as_ptr().addr()))?;
+    writeln!(out, "Size: {} bytes", handle.len())?;
+    if crate::_likely_stack(handle.as_ptr().addr()) {
+        writeln!(out, "Aloc: Likely Stack (handle)")
+    } else {
+        writeln!(out, "Aloc: Likely Heap (handle)")
+    }
+}
+
+/// Returns the bytes of a handle.
+pub(crate) fn handle_bytes<T>(handle: &T) -> &[u8] {
+    unsafe { core::slice::from_raw_parts(handle as *const T as *const u8, core::mem::size_of::<T>()) }
+}
+
+/// Formats the handle, the initialized elements and the spare capacity of a `Vec`.
+pub(crate) fn write_vec<T>(out: &mut String, name: &str, vec: &Vec<T>) -> fmt::Result {
+    let handle = handle_bytes(vec);
+    let size = core::mem::size_of::<T>();
+    let ptr = vec.as_ptr() as *const u8;
+    let (len, capacity) = (vec.len(), vec.capacity());
+    write_header(out, name, core::any::type_name::<Vec<T>>(), handle)?;
+    if size == 0 {
+        writeln!(out, "Heap: none, the {} elements are zero-sized", len)?;
+    } else if capacity == 0 {
+        writeln!(out, "Heap: none, the capacity is 0")?;
+    } else {
+        writeln!(out, "Heap: {}, {} of {} elements of {} bytes initialized", crate::_HeaderAddr(ptr.addr()), len, capacity, size)?;
+    }
+    let fields: [Field; 3] = [("ptr", ptr.addr(), true), ("len", len, false), ("cap", capacity, false)];
+    write_parts(out, name, &Parts { handle, fields: &fields, ptr, len, capacity, size, separator: INITIALIZED_ELEMENTS, indexed: true, redact: crate::config().redact })
+}
+
+/// Maximal number of characters of the `Text` line of a string.
+const MAX_TEXT_CHARS: usize = 256;
+
+/// A string shown by [`view_string!`](crate::view_string): `String`, `&str`, `OsString`, `&OsStr`, `PathBuf` or
+/// `&Path`.
+///
+/// (This is supposed to be private usage of view_string! macro usage.)
+pub trait _Text {
+    /// Returns the bytes of the text, in the encoding of the type.
+    fn text_bytes(&self) -> &[u8];
+
+    /// Returns the capacity of the allocation of an owned string, `None` for a borrowed one.
+    fn capacity(&self) -> Option<usize>;
+
+    /// Returns the encoding of the bytes of the OS strings and paths, `None` for the UTF-8 ones of `str`.
+    fn encoding(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// The encoding of the bytes of `OsStr` on this platform.
+#[cfg(windows)]
+const OS_ENCODING: &str = "WTF-8, UTF-8 extended with the unpaired surrogates of UTF-16, converted to UTF-16 for the system calls";
+#[cfg(not(windows))]
+const OS_ENCODING: &str = "the bytes given to the system calls as is, usually UTF-8 but never checked";
+
+impl _Text for String {
+    fn text_bytes(&self) -> &[u8] {
+        self.as_bytes()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(String::capacity(self))
+    }
+}
+
+impl _Text for &str {
+    fn text_bytes(&self) -> &[u8] {
+        self.as_bytes()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl _Text for OsString {
+    fn text_bytes(&self) -> &[u8] {
+        self.as_encoded_bytes()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(OsString::capacity(self))
+    }
+
+    fn encoding(&self) -> Option<&'static str> {
+        Some(OS_ENCODING)
+    }
+}
+
+impl _Text for &OsStr {
+    fn text_bytes(&self) -> &[u8] {
+        self.as_encoded_bytes()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+
+    fn encoding(&self) -> Option<&'static str> {
+        Some(OS_ENCODING)
+    }
+}
+
+impl _Text for PathBuf {
+    fn text_bytes(&self) -> &[u8] {
+        self.as_os_str().as_encoded_bytes()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(PathBuf::capacity(self))
+    }
+
+    fn encoding(&self) -> Option<&'static str> {
+        Some(OS_ENCODING)
+    }
+}
+
+impl _Text for &Path {
+    fn text_bytes(&self) -> &[u8] {
+        self.as_os_str().as_encoded_bytes()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+
+    fn encoding(&self) -> Option<&'static str> {
+        Some(OS_ENCODING)
+    }
+}
+
+/// Formats the handle, the bytes and the spare capacity of a string, with its text, or only its length when redacting.
+pub(crate) fn write_text<S: _Text>(out: &mut String, name: &str, value: &S, redact: bool) -> fmt::Result {
+    let handle = handle_bytes(value);
+    let bytes = value.text_bytes();
+    let ptr = bytes.as_ptr();
+    let len = bytes.len();
+    write_header(out, name, core::any::type_name::<S>(), handle)?;
+    match value.capacity() {
+        Some(0) => writeln!(out, "Heap: none, the capacity is 0")?,
+        Some(capacity) => writeln!(out, "Heap: {}, {} of {} bytes used", crate::_HeaderAddr(ptr.addr()), len, capacity)?,
+        None => writeln!(out, "Data: {}, {} bytes borrowed", crate::_HeaderAddr(ptr.addr()), len)?,
+    }
+    if let Some(encoding) = value.encoding() {
+        writeln!(out, "Encd: {}", encoding)?;
+        if let Err(error) = core::str::from_utf8(bytes) {
+            writeln!(out, "Vald: not valid UTF-8 from +{:#x}, the invalid bytes are shown as U+FFFD in the text", error.valid_up_to())?;
+        }
+    }
+    let text = String::from_utf8_lossy(bytes);
+    let chars = text.chars().count();
+    let plural = if chars == 1 { "" } else { "s" };
+    if redact {
+        writeln!(out, "Text: redacted, {} char{}", chars, plural)?;
+    } else {
+        out.write_str("Text: \"")?;
+        for c in text.chars().take(MAX_TEXT_CHARS) {
+            write!(out, "{}", c.escape_debug())?;
+        }
+        if chars > MAX_TEXT_CHARS {
+            writeln!(out, "\" … {} more chars", chars - MAX_TEXT_CHARS)?;
+        } else {
+            writeln!(out, "\" ({} char{})", chars, plural)?;
+        }
+    }
+
+    let capacity = value.capacity();
+    let fields: [Field; 3] = [("ptr", ptr.addr(), true), ("len", len, false), ("cap", capacity.unwrap_or(usize::MAX), false)];
+    let fields = if capacity.is_some() { &fields[..] } else { &fields[..2] };
+    write_parts(out, name, &Parts { handle, fields, ptr, len, capacity: capacity.unwrap_or(len), size: 1, separator: TEXT_BYTES, indexed: false, redact })
+}
+
+#[macro_export]
+/// Macro to view the handle, the initialized elements and the spare capacity of a `Vec`.
+///
+/// The words of the handle are named after the pointer, length and capacity of the `Vec` they hold. The elements
+/// follow, then the spare capacity, the memory allocated past the length: it is uninitialized, its bytes are leftovers
+/// of the allocator or of removed elements and mean nothing. Elements larger than a byte are separated by a rule line.
+/// In Miri-friendly mode the memory content isn't shown.
+///
+/// # Argument
+///
+/// * `var` - The `Vec` to dump.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let mut my_vec: Vec<u16> = Vec::with_capacity(3);
+/// my_vec.push(0x4142);
+/// view_vec!(my_vec);
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_vec
+/// Type: alloc::vec::Vec<u16>
+/// Addr: 00007ffc8f1b54a0
+/// Size: 24 bytes
+/// Aloc: Likely Stack (handle)
+/// Heap: 000055c1f04a6ba0, 1 of 3 elements of 2 bytes initialized
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// -------------------------Handle-------------------------
+///  00007ffc8f1b54a0 | 03  | 003 | 00000011 |  ETX  | ... | cap = 3
+///  00007ffc8f1b54a1 | 00  | 000 | 00000000 |  NUL  | ... | cap +1
+/// ...
+///  00007ffc8f1b54a8 | a0  | 160 | 10100000 |  ...  | ... | ptr = 000055c1f04a6ba0
+/// ...
+///  00007ffc8f1b54b0 | 01  | 001 | 00000001 |  SOH  | ... | len = 1
+/// ...
+///
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ------------------Initialized Elements------------------
+///  000055c1f04a6ba0 | 42  | 066 | 01000010 |   B   | XXX | [0]
+///  000055c1f04a6ba1 | 41  | 065 | 01000001 |   A   | XXX | [0] +1
+///
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// -------------Spare Capacity (uninitialized)-------------
+///  000055c1f04a6ba2 | 00  | 000 | 00000000 |  NUL  | ... | uninit [1]
+///  000055c1f04a6ba3 | 00  | 000 | 00000000 |  NUL  | XXX | uninit [1] +1
+///  - - - - - - - - - - - - - - - - - - - - - - - - - - - -
+///  000055c1f04a6ba4 | 00  | 000 | 00000000 |  NUL  | XXX | uninit [2]
+///  000055c1f04a6ba5 | 00  | 000 | 00000000 |  NUL  | XXX | uninit [2] +1
+/// ```
+macro_rules! view_vec {
+    ($var: expr) => {
+        $crate::_show_vec(stringify!($var), &$var)
+    };
+}
+
+/// Displays the handle, the initialized elements and the spare capacity of a `Vec`.
+///
+/// (This is supposed to be private usage of view_vec! macro usage.)
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `vec` - The `Vec` to display.
+pub fn _show_vec<T>(name: &str, vec: &Vec<T>) {
+    render::emit(|out| write_vec(out, name, vec));
+}
+
+#[macro_export]
+/// Macro to view the handle, the bytes and the text of a `String` or a `&str`, or of an `OsString`, a `&OsStr`, a
+/// `PathBuf` or a `&Path`.
+///
+/// The words of the handle are named after the pointer, length and capacity (`String` only) they hold, the text is
+/// decoded on a `Text` line, then come the UTF-8 bytes of the text, the position of each byte in its multi-byte
+/// character in the ASCII column, and, for a `String`, its spare capacity, marked as uninitialized. One call replaces
+/// `view_mem!(my_string)` followed by `view_mem!(*my_string)`. With the `redact` configuration the `Text` line only
+/// tells the number of characters. In Miri-friendly mode the memory content isn't shown.
+///
+/// The OS strings and paths are dumped in the encoding of the platform, told on an `Encd` line: WTF-8 on Windows, the
+/// bytes given to the system calls elsewhere. A `Vald` line tells where their bytes stop being valid UTF-8, if they
+/// do: the invalid bytes, e.g. the unpaired surrogates of a Windows file name, are shown as U+FFFD in the text and
+/// marked `BAD` in the ASCII column.
+///
+/// # Argument
+///
+/// * `var` - The `String`, `&str`, `OsString`, `&OsStr`, `PathBuf` or `&Path` to dump.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let mut my_string = String::with_capacity(8);
+/// my_string.push_str("Hé!");
+/// view_string!(my_string);
+///
+/// let my_str: &str = "🦀";
+/// view_string!(my_str);
+///
+/// let my_path = std::path::Path::new("/tmp/mem");
+/// view_string!(my_path);
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_string
+/// Type: alloc::string::String
+/// Addr: 00007ffe0b5c6b10
+/// Size: 24 bytes
+/// Aloc: Likely Stack (handle)
+/// Heap: 000055f3a4bd0ba0, 4 of 8 bytes used
+/// Text: "Hé!" (3 chars)
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// -------------------------Handle-------------------------
+///  00007ffe0b5c6b10 | 08  | 008 | 00001000 |  BS   | ... | cap = 8
+/// ...
+///
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8
+/// -----------------------Text Bytes-----------------------
+///  000055f3a4bd0ba0 | 48  | 072 | 01001000 |   H   | Hé!
+///  000055f3a4bd0ba1 | c3  | 195 | 11000011 |  1/2  | XXX
+///  000055f3a4bd0ba2 | a9  | 169 | 10101001 |  2/2  | XXX
+///  000055f3a4bd0ba3 | 21  | 033 | 00100001 |   !   | XXX
+///
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// -------------Spare Capacity (uninitialized)-------------
+///  000055f3a4bd0ba4 | 00  | 000 | 00000000 |  NUL  | ... | uninit
+/// ...
+/// ```
+macro_rules! view_string {
+    ($var: expr) => {
+        $crate::_show_text(stringify!($var), &$var)
+    };
+}
+
+/// Displays the handle, the bytes and the text of a string.
+///
+/// (This is supposed to be private usage of view_string! macro usage.)
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `value` - The `String` or `&str` to display.
+pub fn _show_text<S: _Text>(name: &str, value: &S) {
+    render::emit(|out| write_text(out, name, value, crate::config().redact));
+}
+
+/// Formats the handle of a `Box` and the value it points to.
+#[allow(clippy::borrowed_box)]
+pub(crate) fn write_box<T>(out: &mut String, name: &str, boxed: &Box<T>) -> fmt::Result {
+    let handle = handle_bytes(boxed);
+    let value: &T = boxed;
+    let ptr = value as *const T as *const u8;
+    let size = core::mem::size_of::<T>();
+    write_header(out, name, core::any::type_name::<Box<T>>(), handle)?;
+    if size == 0 {
+        writeln!(out, "Heap: none, the value is zero-sized")?;
+    } else {
+        writeln!(out, "Heap: {}, {} bytes", crate::_HeaderAddr(ptr.addr()), size)?;
+    }
+    if crate::config().miri_friendly {
+        return render::write_miri_note(out);
+    }
+
+    let fields: [Field; 1] = [("ptr", ptr.addr(), true)];
+    let annotate = |offset: usize, out: &mut dyn fmt::Write| annotate_handle(handle, &fields, offset, out);
+    Table { label: name, annotate: Some(&annotate), ..Table::new(HANDLE, handle.as_ptr().addr(), handle) }.write(out)?;
+    if size == 0 {
+        return Ok(());
+    }
+    let bytes = unsafe { core::slice::from_raw_parts(ptr, size) };
+    Table { label: name, ..Table::new(HEAP_ALLOCATION, ptr.addr(), bytes) }.write(out)
+}
+
+/// Formats the handle of a reference-counted pointer and its allocation: the strong and weak counts, then the value.
+///
+/// `Rc` and `Arc` allocate their counts and value as a `#[repr(C)]` struct, the pointer of the handle is the address of
+/// the strong count. The counts are decoded from the allocation and checked against `strong` and `weak`, the counts
+/// returned by `strong_count()` and `weak_count()`, in case a version of the standard library changes this layout.
+fn write_counted<T>(out: &mut String, name: &str, type_name: &str, handle: &[u8], value: *const T, strong: usize, weak: usize) -> fmt::Result {
+    const WORD: usize = core::mem::size_of::<usize>();
+    let size = core::mem::size_of::<T>();
+    // The value follows the two counts, aligned
+    let offset = (2 * WORD).next_multiple_of(core::mem::align_of::<T>());
+    let start = (value as *const u8).wrapping_sub(offset);
+    write_header(out, name, type_name, handle)?;
+    writeln!(out, "Heap: {}, {} bytes: 2 counts of {} bytes, then the value of {} bytes", crate::_HeaderAddr(start.addr()), offset + size, WORD, size)?;
+    if crate::config().miri_friendly {
+        writeln!(out, "Cnts: strong {}, weak {} (+1 held by the strong references)", strong, weak)?;
+        return render::write_miri_note(out);
+    }
+
+    // The counts of an `Arc` may change under our feet, they are read with atomic loads
+    let (stored_strong, stored_weak) = unsafe {
+        let counts = start as *const core::sync::atomic::AtomicUsize;
+        ((*counts).load(core::sync::atomic::Ordering::Relaxed), (*counts.add(1)).load(core::sync::atomic::Ordering::Relaxed))
+    };
+    writeln!(out, "Cnts: strong {} at +0x0..+{:#x}, weak {} at +{:#x}..+{:#x} ({} Weak + 1 held by the strong references), value at +{:#x}..+{:#x}", stored_strong, WORD, stored_weak, WORD, 2 * WORD, stored_weak.saturating_sub(1), offset, offset + size)?;
+    if stored_strong != strong || stored_weak != weak + 1 {
+        writeln!(out, "Note: strong_count() is {} and weak_count() is {}, the counts aren't where they used to be in the allocation", strong, weak)?;
+    }
+
+    let fields: [Field; 1] = [("ptr", start.addr(), true)];
+    let annotate = |offset: usize, out: &mut dyn fmt::Write| annotate_handle(handle, &fields, offset, out);
+    Table { label: name, annotate: Some(&annotate), ..Table::new(HANDLE, handle.as_ptr().addr(), handle) }.write(out)?;
+    let bytes = unsafe { core::slice::from_raw_parts(start, offset + size) };
+    let annotate = |index: usize, out: &mut dyn fmt::Write| match index {
+        0 => write!(out, "strong = {}", stored_strong),
+        index if index < WORD => write!(out, "strong +{}", index),
+        index if index == WORD => write!(out, "weak = {}", stored_weak),
+        index if index < 2 * WORD => write!(out, "weak +{}", index - WORD),
+        index if index < offset => out.write_str("(padding)"),
+        index if index == offset => out.write_str("value"),
+        index => write!(out, "value +{}", index - offset),
+    };
+    Table { label: name, annotate: Some(&annotate), ..Table::new(HEAP_ALLOCATION, start.addr(), bytes) }.write(out)
+}
+
+/// A pointer-backed value shown by [`view_heap!`](crate::view_heap): `Box`, `Vec`, `String`, `Rc` or `Arc`.
+///
+/// (This is supposed to be private usage of view_heap! macro usage.)
+pub trait _HeapBacked {
+    /// Formats the handle and the heap allocation it points to.
+    fn write_sections(&self, out: &mut String, name: &str) -> fmt::Result;
+}
+
+impl<T> _HeapBacked for Box<T> {
+    fn write_sections(&self, out: &mut String, name: &str) -> fmt::Result {
+        write_box(out, name, self)
+    }
+}
+
+impl<T> _HeapBacked for Vec<T> {
+    fn write_sections(&self, out: &mut String, name: &str) -> fmt::Result {
+        write_vec(out, name, self)
+    }
+}
+
+impl _HeapBacked for String {
+    fn write_sections(&self, out: &mut String, name: &str) -> fmt::Result {
+        write_text(out, name, self, crate::config().redact)
+    }
+}
+
+impl<T> _HeapBacked for Rc<T> {
+    fn write_sections(&self, out: &mut String, name: &str) -> fmt::Result {
+        write_counted(out, name, core::any::type_name::<Rc<T>>(), handle_bytes(self), Rc::as_ptr(self), Rc::strong_count(self), Rc::weak_count(self))
+    }
+}
+
+impl<T> _HeapBacked for Arc<T> {
+    fn write_sections(&self, out: &mut String, name: &str) -> fmt::Result {
+        write_counted(out, name, core::any::type_name::<Arc<T>>(), handle_bytes(self), Arc::as_ptr(self), Arc::strong_count(self), Arc::weak_count(self))
+    }
+}
+
+#[macro_export]
+/// Macro to view a pointer-backed value in a single call: its handle, then the heap allocation it points to.
+///
+/// Dumping a `Box` with `view_mem!` shows the bytes of a pointer, not the value: this macro shows both, in a `Handle`
+/// table with the words named, then the heap tables. A `Vec` or a `String` is shown as by
+/// [`view_vec!`](crate::view_vec) or [`view_string!`](crate::view_string). The allocation of an `Rc` or an `Arc`
+/// starts with the strong and weak counts, decoded on a `Cnts` line with the bytes they occupy and annotated, before
+/// the value; the weak count stored there is one more than `weak_count()`, as the strong references share a weak one.
+/// In Miri-friendly mode the memory content isn't shown.
+///
+/// # Argument
+///
+/// * `var` - The `Box`, `Vec`, `String`, `Rc` or `Arc` to dump.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+/// use std::rc::Rc;
+///
+/// let my_box: Box<u32> = Box::new(0xdead_beef);
+/// view_heap!(my_box);
+///
+/// let my_rc: Rc<u16> = Rc::new(69);
+/// let _my_clone = Rc::clone(&my_rc);
+/// view_heap!(my_rc);
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_rc
+/// Type: alloc::rc::Rc<u16>
+/// Addr: 00007ffd9b1e4c28
+/// Size: 8 bytes
+/// Aloc: Likely Stack (handle)
+/// Heap: 000055e0c86a6ba0, 18 bytes: 2 counts of 8 bytes, then the value of 2 bytes
+/// Cnts: strong 2 at +0x0..+0x8, weak 1 at +0x8..+0x10 (0 Weak + 1 held by the strong references), value at +0x10..+0x12
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// -------------------------Handle-------------------------
+///  00007ffd9b1e4c28 | a0  | 160 | 10100000 |  ...  | ... | ptr = 000055e0c86a6ba0
+/// ...
+///
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// --------------------Heap Allocation---------------------
+///  000055e0c86a6ba0 | 02  | 002 | 00000010 |  STX  | ... | strong = 2
+/// ...
+///  000055e0c86a6ba8 | 01  | 001 | 00000001 |  SOH  | ... | weak = 1
+/// ...
+///  000055e0c86a6bb0 | 45  | 069 | 01000101 |   E   | ... | value
+///  000055e0c86a6bb1 | 00  | 000 | 00000000 |  NUL  | XXX | value +1
+/// ```
+macro_rules! view_heap {
+    ($var: expr) => {
+        $crate::_show_heap(stringify!($var), &$var)
+    };
+}
+
+/// Displays the handle of a pointer-backed value and its heap allocation.
+///
+/// (This is supposed to be private usage of view_heap! macro usage.)
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `value` - The `Box`, `Vec`, `String`, `Rc` or `Arc` to display.
+pub fn _show_heap<H: _HeapBacked>(name: &str, value: &H) {
+    render::emit(|out| value.write_sections(out, name));
+}
@@ -0,0 +1,66 @@
+//! Thread and process context printed in the dump header.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Returns the context lines of the dump header (thread, process and time, then the call-site backtrace) as
+/// `(label, value)` pairs, or nothing if `Config::header_context` and `Config::header_backtrace` are disabled.
+///
+/// (This is supposed to be private usage of view_mem! and safe_view_mem! macro usage.)
+pub fn _header_context() -> Vec<(&'static str, String)> {
+    let config = crate::config();
+    let lines = if config.header_context { context_lines() } else { Vec::new() };
+    #[cfg(feature = "symbols")]
+    let lines = {
+        let mut lines = lines;
+        lines.extend(crate::symbols::call_site_frames(config.header_backtrace).into_iter().map(|frame| ("Call", frame)));
+        lines
+    };
+    lines
+}
+
+/// Returns the thread, process and time lines of the dump header.
+fn context_lines() -> Vec<(&'static str, String)> {
+    let thread = std::thread::current();
+    let thread = match thread.name() {
+        Some(name) => format!("{:?} \"{}\"", thread.id(), name),
+        None => format!("{:?}", thread.id()),
+    };
+    vec![
+        ("Thrd", thread),
+        ("Proc", std::process::id().to_string()),
+        ("Time", format_timestamp(SystemTime::now())),
+    ]
+}
+
+/// Formats a time as an RFC 3339 UTC timestamp with milliseconds, e.g. `2024-06-01T12:34:56.789Z`.
+pub(crate) fn format_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86400) as i64);
+    let secs_of_day = secs % 86400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Converts a number of days since 1970-01-01 to a (year, month, day) date, see
+/// <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
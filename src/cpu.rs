@@ -0,0 +1,224 @@
+//! Snapshots of the general-purpose registers of the CPU.
+//!
+//! [`CpuRegisters::capture`] stores the registers with inline assembly, where it is inlined: the values are those the
+//! calling function holds at this point, so the addresses they hold can be compared with the addresses of the dumps.
+//! Each value is located in the stack of the thread or in a section of a loaded module, and named after its symbol with
+//! the `symbols` feature. Only x86-64 and AArch64 are supported.
+
+use std::fmt::{self, Write};
+
+/// Names of the captured registers, in the order of [`CpuRegisters::values`].
+#[cfg(target_arch = "x86_64")]
+pub const REGISTER_NAMES: [&str; 18] =
+    ["rax", "rbx", "rcx", "rdx", "rsi", "rdi", "rbp", "rsp", "r8", "r9", "r10", "r11", "r12", "r13", "r14", "r15", "rip", "rflags"];
+
+/// Names of the captured registers, in the order of [`CpuRegisters::values`].
+#[cfg(target_arch = "aarch64")]
+pub const REGISTER_NAMES: [&str; 34] = [
+    "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12", "x13", "x14", "x15", "x16", "x17",
+    "x18", "x19", "x20", "x21", "x22", "x23", "x24", "x25", "x26", "x27", "x28", "fp", "lr", "sp", "pc", "nzcv",
+];
+
+/// Indices of the stack pointer and of the program counter in [`REGISTER_NAMES`].
+#[cfg(target_arch = "x86_64")]
+const SP_PC: (usize, usize) = (7, 16);
+/// Indices of the stack pointer and of the program counter in [`REGISTER_NAMES`].
+#[cfg(target_arch = "aarch64")]
+const SP_PC: (usize, usize) = (31, 32);
+
+/// A snapshot of the general-purpose registers, with the stack pointer, the program counter and the flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuRegisters {
+    /// Values of the registers, named by [`REGISTER_NAMES`].
+    pub values: [usize; REGISTER_NAMES.len()],
+}
+
+impl CpuRegisters {
+    /// Captures the registers of the calling function.
+    ///
+    /// The function is always inlined, the program counter is the address of the capture in the caller. A register
+    /// the compiler picks to hold the address of the snapshot shows this address instead of the value of the caller.
+    /// Under Miri, which can't run inline assembly, the values are zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let my_regs = CpuRegisters::capture();
+    /// println!("sp = {:x}", my_regs.stack_pointer());
+    /// ```
+    #[inline(always)]
+    #[cfg_attr(miri, allow(unused_mut))]
+    pub fn capture() -> Self {
+        let mut values = [0usize; REGISTER_NAMES.len()];
+        // SAFETY: the registers are stored into `values`, the scratch register is declared, the flags are preserved
+        #[cfg(all(target_arch = "x86_64", not(miri)))]
+        unsafe {
+            core::arch::asm!(
+                "mov [{p}], rax",
+                "mov [{p} + 8], rbx",
+                "mov [{p} + 16], rcx",
+                "mov [{p} + 24], rdx",
+                "mov [{p} + 32], rsi",
+                "mov [{p} + 40], rdi",
+                "mov [{p} + 48], rbp",
+                "mov [{p} + 56], rsp",
+                "mov [{p} + 64], r8",
+                "mov [{p} + 72], r9",
+                "mov [{p} + 80], r10",
+                "mov [{p} + 88], r11",
+                "mov [{p} + 96], r12",
+                "mov [{p} + 104], r13",
+                "mov [{p} + 112], r14",
+                "mov [{p} + 120], r15",
+                "lea {t}, [rip]",
+                "mov [{p} + 128], {t}",
+                "pushfq",
+                "pop {t}",
+                "mov [{p} + 136], {t}",
+                p = in(reg) values.as_mut_ptr(),
+                t = out(reg) _,
+                options(preserves_flags),
+            );
+        }
+        // SAFETY: as above
+        #[cfg(all(target_arch = "aarch64", not(miri)))]
+        unsafe {
+            core::arch::asm!(
+                "stp x0, x1, [{p}]",
+                "stp x2, x3, [{p}, #16]",
+                "stp x4, x5, [{p}, #32]",
+                "stp x6, x7, [{p}, #48]",
+                "stp x8, x9, [{p}, #64]",
+                "stp x10, x11, [{p}, #80]",
+                "stp x12, x13, [{p}, #96]",
+                "stp x14, x15, [{p}, #112]",
+                "stp x16, x17, [{p}, #128]",
+                "stp x18, x19, [{p}, #144]",
+                "stp x20, x21, [{p}, #160]",
+                "stp x22, x23, [{p}, #176]",
+                "stp x24, x25, [{p}, #192]",
+                "stp x26, x27, [{p}, #208]",
+                "stp x28, x29, [{p}, #224]",
+                "str x30, [{p}, #240]",
+                "mov {t}, sp",
+                "str {t}, [{p}, #248]",
+                "adr {t}, .",
+                "str {t}, [{p}, #256]",
+                "mrs {t}, nzcv",
+                "str {t}, [{p}, #264]",
+                p = in(reg) values.as_mut_ptr(),
+                t = out(reg) _,
+                options(nostack, preserves_flags),
+            );
+        }
+        CpuRegisters { values }
+    }
+
+    /// Returns the value of the register named `name`, e.g. `"rsp"`, if it is captured on this architecture.
+    pub fn get(&self, name: &str) -> Option<usize> {
+        REGISTER_NAMES.iter().position(|other| *other == name).map(|index| self.values[index])
+    }
+
+    /// Returns the registers with their names.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, usize)> + '_ {
+        REGISTER_NAMES.iter().copied().zip(self.values.iter().copied())
+    }
+
+    /// Returns the stack pointer.
+    pub fn stack_pointer(&self) -> usize {
+        self.values[SP_PC.0]
+    }
+
+    /// Returns the program counter, the address of the capture.
+    pub fn program_counter(&self) -> usize {
+        self.values[SP_PC.1]
+    }
+}
+
+/// Formats a snapshot: a line per register with its value in hex and where it points, if anywhere known, or with `*`
+/// in place of the value if `redact` is set.
+pub(crate) fn write_regs(out: &mut String, regs: &CpuRegisters, redact: bool) -> fmt::Result {
+    let thread = std::thread::current();
+    match thread.name() {
+        Some(name) => writeln!(out, "Name: registers of thread \"{}\"", name)?,
+        None => writeln!(out, "Name: registers of thread {:?}", thread.id())?,
+    }
+    writeln!(out, "Arch: {}, {} registers", std::env::consts::ARCH, REGISTER_NAMES.len())?;
+    let stack = crate::stack_bounds();
+    let modules = crate::loaded_modules();
+    let flags = REGISTER_NAMES.len() - 1;
+    for (index, (name, value)) in regs.iter().enumerate() {
+        // Where a value points tells it too
+        if redact {
+            writeln!(out, "{:>6} = {:*<width$}", name, "", width = 2 * core::mem::size_of::<usize>())?;
+            continue;
+        }
+        write!(out, "{:>6} = {}", name, crate::_Addr(value))?;
+        if index == flags {
+            writeln!(out)?;
+            continue;
+        }
+        if let Some(stack) = stack.as_ref().filter(|stack| stack.contains(&value)) {
+            write!(out, "  stack, {:#x} below the base", stack.end - value)?;
+        } else if let Some((module, section)) = modules.iter().find_map(|module| Some((module, module.section_at(value)?))) {
+            write!(out, "  {} {} +{:#x}", module.name(), section.name, value - section.addr)?;
+            #[cfg(feature = "symbols")]
+            if let Some(symbol) = crate::resolve_symbol(value) {
+                write!(out, " {}", symbol)?;
+            }
+        }
+        writeln!(out)?;
+    }
+    writeln!(out)
+}
+
+#[macro_export]
+/// Macro to view the general-purpose registers of the CPU at the point of the call, with where their values point.
+///
+/// The registers are captured by [`CpuRegisters::capture`](crate::CpuRegisters::capture), inlined in the calling
+/// function, and printed in hex like the addresses of the dumps, each one with a note when it points into the stack of
+/// the thread (its distance from the base) or into a section of a loaded module (the module, the section and the
+/// offset, with the symbol when the `symbols` feature is enabled). When [`Config::redact`](crate::Config::redact) is
+/// set, the values are replaced by `*` and their notes left out. Requires the `regs` feature, on x86-64 and AArch64.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_value: u64 = 42;
+/// view_mem!(my_value);
+/// view_regs!();
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: registers of thread "main"
+/// Arch: x86_64, 18 registers
+///    rax = 000000000000002a
+///    rbx = 00007ffc8d1e2f68  stack, 0x30d8 below the base
+/// ...
+///    rsp = 00007ffc8d1e2b40  stack, 0x34c0 below the base
+/// ...
+///    rip = 000055d1c3a3fb10  my_app .text +0x3b10
+/// rflags = 0000000000000246
+/// ```
+macro_rules! view_regs {
+    () => {
+        $crate::_show_regs(&$crate::CpuRegisters::capture())
+    };
+}
+
+/// Displays a snapshot of the registers.
+///
+/// (This is supposed to be private usage of view_regs! macro usage.)
+///
+/// # Argument
+///
+/// * `regs` - The registers captured in the caller of the macro.
+pub fn _show_regs(regs: &CpuRegisters) {
+    crate::render::emit(|out| write_regs(out, regs, crate::config().redact));
+}
@@ -0,0 +1,263 @@
+//! Dumps of C strings, with their NUL terminator found and checked.
+//!
+//! A C string ends at its first NUL byte: a NUL too early cuts the text short for every C function, and a missing one
+//! sends `strlen` reading past the buffer. The [`view_cstr!`](crate::view_cstr) macro dumps a `CStr`, a `CString`,
+//! a byte buffer or the string behind a raw pointer up to and including its terminator, and flags both mistakes.
+
+use std::ffi::{CStr, CString};
+use std::fmt::{self, Write};
+
+use crate::render::{self, Table, MEMORY_CONTENT};
+
+/// Where the bytes of a C string come from, which tells how far its terminator may be looked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum _CBound {
+    /// A `CStr` or a `CString`, terminated by construction.
+    Terminated,
+    /// A buffer of this number of bytes, the string in it.
+    Buffer(usize),
+    /// A raw pointer, the terminator looked for in at most this number of bytes.
+    Pointer(usize),
+}
+
+/// The NUL bytes of a C string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Nuls {
+    /// The offset of the terminator: the NUL after the last other byte.
+    terminator: Option<usize>,
+    /// The offsets of the NULs before the terminator, where C functions stop too early.
+    premature: Vec<usize>,
+}
+
+impl Nuls {
+    /// Finds the terminator and the premature NULs of the string in `bytes`.
+    pub(crate) fn find(bytes: &[u8]) -> Self {
+        let end = bytes.iter().rposition(|byte| *byte != 0).map_or(0, |last| last + 1);
+        let terminator = (end < bytes.len()).then_some(end);
+        let premature = (0..end).filter(|offset| bytes[*offset] == 0).collect();
+        Nuls { terminator, premature }
+    }
+}
+
+/// Writes the annotation of the byte at `offset` of a C string: the terminator, a premature NUL or the last byte read
+/// without finding a terminator.
+fn annotate(nuls: &Nuls, len: usize, offset: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+    if nuls.terminator == Some(offset) {
+        out.write_str("NUL terminator")
+    } else if nuls.premature.first() == Some(&offset) {
+        out.write_str("PREMATURE NUL, C functions stop here")
+    } else if nuls.premature.contains(&offset) {
+        out.write_str("PREMATURE NUL")
+    } else if nuls.terminator.is_none() && offset + 1 == len {
+        out.write_str("no NUL up to here")
+    } else {
+        Ok(())
+    }
+}
+
+/// Formats the C string at `addr` made of `bytes`, the whole region where its terminator was looked for, with only the
+/// length of its text when redacting.
+pub(crate) fn write_c_str(out: &mut String, name: &str, type_name: &str, addr: usize, bytes: &[u8], bound: _CBound, redact: bool) -> fmt::Result {
+    let nuls = Nuls::find(bytes);
+    let shown = nuls.terminator.map_or(bytes.len(), |terminator| terminator + 1);
+    crate::write::write_header(out, name, type_name, addr, shown)?;
+    match (nuls.terminator, bound) {
+        (Some(terminator), _CBound::Buffer(len)) if len > shown => writeln!(out, "Term: NUL at +{:#x}, {} more bytes of the buffer after it not shown", terminator, len - shown)?,
+        (Some(terminator), _) => writeln!(out, "Term: NUL at +{:#x}", terminator)?,
+        (None, _CBound::Pointer(max)) => writeln!(out, "Term: NONE within the first {} bytes, strlen would read further", max)?,
+        (None, _) => writeln!(out, "Term: NONE in the {} bytes of the buffer, strlen would read past its end", bytes.len())?,
+    }
+    let text = &bytes[..nuls.terminator.unwrap_or(bytes.len())];
+    if redact {
+        writeln!(out, "Text: redacted ({} bytes)", text.len())?;
+    } else {
+        writeln!(out, "Text: \"{}\" ({} bytes)", String::from_utf8_lossy(text).escape_debug(), text.len())?;
+    }
+    if let Some(&first) = nuls.premature.first() {
+        let count = nuls.premature.len();
+        let offsets: Vec<String> = nuls.premature.iter().map(|offset| format!("+{:#x}", offset)).collect();
+        let plural = if count == 1 { "" } else { "s" };
+        writeln!(out, "Nuls: {} PREMATURE NUL{} at {}, C functions see only the first {} bytes", count, plural, offsets.join(", "), first)?;
+    }
+    if crate::config().miri_friendly {
+        return render::write_miri_note(out);
+    }
+
+    let annotate = |offset: usize, out: &mut dyn fmt::Write| annotate(&nuls, shown, offset, out);
+    Table { label: name, annotate: Some(&annotate), redact, ..Table::new(MEMORY_CONTENT, addr, &bytes[..shown]) }.write(out)
+}
+
+/// A C string shown by [`view_cstr!`](crate::view_cstr): a `CStr`, a `CString`, or a buffer of `u8` or `c_char`.
+///
+/// (This is supposed to be private usage of view_cstr! macro usage.)
+pub trait _CText {
+    /// Returns the bytes where the terminator is looked for, and how they are bounded.
+    fn c_bytes(&self) -> (&[u8], _CBound);
+}
+
+impl _CText for CStr {
+    fn c_bytes(&self) -> (&[u8], _CBound) {
+        (self.to_bytes_with_nul(), _CBound::Terminated)
+    }
+}
+
+impl _CText for CString {
+    fn c_bytes(&self) -> (&[u8], _CBound) {
+        (self.as_bytes_with_nul(), _CBound::Terminated)
+    }
+}
+
+impl<C: _CText + ?Sized> _CText for &C {
+    fn c_bytes(&self) -> (&[u8], _CBound) {
+        (**self).c_bytes()
+    }
+}
+
+impl _CText for [u8] {
+    fn c_bytes(&self) -> (&[u8], _CBound) {
+        (self, _CBound::Buffer(self.len()))
+    }
+}
+
+impl<const N: usize> _CText for [u8; N] {
+    fn c_bytes(&self) -> (&[u8], _CBound) {
+        (self, _CBound::Buffer(N))
+    }
+}
+
+impl _CText for Vec<u8> {
+    fn c_bytes(&self) -> (&[u8], _CBound) {
+        (self, _CBound::Buffer(self.len()))
+    }
+}
+
+// `c_char` is `i8` or `u8` depending on the target, its buffers are covered by both
+impl _CText for [i8] {
+    fn c_bytes(&self) -> (&[u8], _CBound) {
+        (unsafe { core::slice::from_raw_parts(self.as_ptr() as *const u8, self.len()) }, _CBound::Buffer(self.len()))
+    }
+}
+
+impl<const N: usize> _CText for [i8; N] {
+    fn c_bytes(&self) -> (&[u8], _CBound) {
+        self[..].c_bytes()
+    }
+}
+
+#[macro_export]
+/// Macro to view a C string up to and including its NUL terminator, with the premature and missing terminators
+/// flagged.
+///
+/// Works with a `CStr`, a `CString`, a buffer of `u8` or `c_char` (`[c_char; 32]`, `Vec<u8>`, ...) and, given the
+/// number of bytes where the terminator may be looked for, a raw pointer received from C. A `Term` line gives the
+/// offset of the terminator, the NUL after the last other byte, and a `Text` line the text before it. The NULs before
+/// the terminator are listed on a `Nuls` line: C functions stop at the first one and miss the rest of the text. When
+/// the buffer or the bound holds no NUL, the `Term` line warns that `strlen` would read further. The bytes follow up to
+/// the terminator, which is annotated, as are the premature NULs. With the `redact` configuration the `Text` line only
+/// tells the number of bytes. In Miri-friendly mode the memory content isn't shown.
+///
+/// With a raw pointer the macro reads the memory behind it, so it must be called in an `unsafe` block: unless the
+/// pointer is null, its bytes must be readable up to the terminator, or `max` bytes without one.
+///
+/// # Arguments
+///
+/// * `var` - The C string or buffer to dump, or a raw pointer to it.
+/// * `max` - With a raw pointer, the number of bytes read at most while looking for the terminator.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+/// use std::ffi::{c_char, CString};
+///
+/// let my_name = CString::new("mem").unwrap();
+/// view_cstr!(my_name);
+///
+/// let my_buffer: [u8; 8] = *b"ab\0cd\0\0\0";
+/// view_cstr!(my_buffer);
+///
+/// let my_ptr: *const c_char = my_name.as_ptr();
+/// unsafe { view_cstr!(my_ptr, 64) };
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_name
+/// Type: alloc::ffi::c_str::CString
+/// Addr: 000055b3c1a4eba0
+/// Size: 4 bytes
+/// Term: NUL at +0x3
+/// Text: "mem" (3 bytes)
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ----------------------Memory Content--------------------
+///  000055b3c1a4eba0 | 6d  | 109 | 01101101 |   m   | mem  |
+///  000055b3c1a4eba1 | 65  | 101 | 01100101 |   e   | XXX |
+///  000055b3c1a4eba2 | 6d  | 109 | 01101101 |   m   | XXX |
+///  000055b3c1a4eba3 | 00  | 000 | 00000000 |  NUL  | XXX | NUL terminator
+///
+/// Name: my_buffer
+/// Type: [u8; 8]
+/// Addr: 00007ffd1a2b3c40
+/// Size: 6 bytes
+/// Term: NUL at +0x5, 2 more bytes of the buffer after it not shown
+/// Text: "ab\0cd" (5 bytes)
+/// Nuls: 1 PREMATURE NUL at +0x2, C functions see only the first 2 bytes
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ----------------------Memory Content--------------------
+///  00007ffd1a2b3c40 | 61  | 097 | 01100001 |   a   | ab c |
+///  00007ffd1a2b3c41 | 62  | 098 | 01100010 |   b   | b cd |
+///  00007ffd1a2b3c42 | 00  | 000 | 00000000 |  NUL  |  cd  | PREMATURE NUL, C functions stop here
+///  00007ffd1a2b3c43 | 63  | 099 | 01100011 |   c   | XXX |
+///  00007ffd1a2b3c44 | 64  | 100 | 01100100 |   d   | XXX |
+///  00007ffd1a2b3c45 | 00  | 000 | 00000000 |  NUL  | XXX | NUL terminator
+/// ...
+/// ```
+macro_rules! view_cstr {
+    ($var: expr) => {
+        $crate::_show_cstr(stringify!($var), &$var)
+    };
+    ($ptr: expr, $max: expr) => {
+        $crate::_show_cstr_ptr(stringify!($ptr), $ptr as *const u8, $max)
+    };
+}
+
+/// Displays a C string or a buffer holding one.
+///
+/// (This is supposed to be private usage of view_cstr! macro usage.)
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `text` - The C string or buffer to display.
+pub fn _show_cstr<C: _CText + ?Sized>(name: &str, text: &C) {
+    let (bytes, bound) = text.c_bytes();
+    render::emit(|out| write_c_str(out, name, core::any::type_name::<C>(), bytes.as_ptr().addr(), bytes, bound, crate::config().redact));
+}
+
+/// Displays the C string behind a raw pointer, looking for its terminator in at most `max` bytes.
+///
+/// (This is supposed to be private usage of view_cstr! macro usage.)
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `ptr` - The address of the string.
+/// * `max` - The number of bytes read at most.
+///
+/// # Safety
+///
+/// Unless `ptr` is null, the bytes at `ptr` must be readable up to its NUL terminator, or `max` bytes without one.
+pub unsafe fn _show_cstr_ptr(name: &str, ptr: *const u8, max: usize) {
+    if ptr.is_null() {
+        render::emit(|out| {
+            crate::write::write_header(out, name, "*const c_char", 0, 0)?;
+            writeln!(out, "Null: the pointer is null, there is no memory content to show.\n")
+        });
+        return;
+    }
+    // The bytes after the terminator may not be readable: they aren't read
+    let len = (0..max).find(|offset| ptr.add(*offset).read() == 0).map_or(max, |terminator| terminator + 1);
+    let bytes = core::slice::from_raw_parts(ptr, len);
+    render::emit(|out| write_c_str(out, name, "*const c_char", ptr.addr(), bytes, _CBound::Pointer(max), crate::config().redact));
+}
@@ -0,0 +1,45 @@
+//! Dumps exported as CSV, to analyze large regions in spreadsheets or pandas.
+
+use core::fmt::Write as _;
+
+use alloc::string::String;
+
+use crate::dump::MemDump;
+use crate::render::{ASCII, BIN, HEX};
+
+impl MemDump {
+    /// Returns the bytes of the dump as CSV rows, one per byte, below a header row: `address,offset,hex,dec,bin,ascii`.
+    ///
+    /// The address is written in hex with a `0x` prefix, the offset and the decimal value in decimal, the hex and
+    /// binary values with their leading zeros, and the ASCII column holds the character or the name of the control
+    /// character (`NUL`, `LF`…), `...` for non-ASCII bytes. Every byte is written, regardless of `max_bytes`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let dump = MemDump::new("my_u16", "u16", 0x1000, vec![0x45, 0x00]);
+    /// assert_eq!(
+    ///     dump.to_csv().lines().skip(1).collect::<Vec<_>>(),
+    ///     [
+    ///         format!("0x{},0,45,69,01000101,E", _Addr(0x1000)),
+    ///         format!("0x{},1,00,0,00000000,NUL", _Addr(0x1001)),
+    ///     ]
+    /// );
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut out = String::with_capacity((self.bytes.len() + 1) * (crate::render::ADDR_WIDTH + 28));
+        out.push_str("address,offset,hex,dec,bin,ascii\n");
+        for (offset, byte) in self.bytes.iter().enumerate() {
+            let cell = *byte as usize;
+            let ascii = match ASCII[cell].trim() {
+                "," => "\",\"",
+                "\"" => "\"\"\"\"",
+                ascii => ascii,
+            };
+            let _ = writeln!(out, "0x{},{},{},{},{},{}", crate::_Addr(self.addr + offset), offset, HEX[cell], byte, BIN[cell], ascii);
+        }
+        out
+    }
+}
@@ -0,0 +1,60 @@
+//! Deduplication of the memory content tables printed during a run.
+//!
+//! With [`Config::dedup`](crate::Config::dedup), every table printed to stdout or sent to a sink is identified by the
+//! FNV-1a hash and the length of its bytes. The first table of a content is printed in full below a `Hash` line
+//! numbering it, the following tables of the same content are replaced by a single line referring to it.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::render::content_hash;
+
+/// Whether a content was already printed during the session, with the number of its first table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Seen {
+    /// The content is printed for the first time, as the table with this number.
+    First(usize),
+    /// The content was printed by the table with this number.
+    Again(usize),
+}
+
+/// The numbers of the tables printed during the session, by hash and length of their content.
+fn session() -> &'static Mutex<HashMap<(u64, usize), usize>> {
+    static SESSION: OnceLock<Mutex<HashMap<(u64, usize), usize>>> = OnceLock::new();
+    SESSION.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Records that a table of `bytes` is printed, returning the hash of the content and whether it was already printed.
+pub(crate) fn record(bytes: &[u8]) -> (u64, Seen) {
+    let hash = content_hash(bytes);
+    let mut tables = session().lock().unwrap_or_else(|e| e.into_inner());
+    let next = tables.len() + 1;
+    match tables.get(&(hash, bytes.len())) {
+        Some(number) => (hash, Seen::Again(*number)),
+        None => {
+            tables.insert((hash, bytes.len()), next);
+            (hash, Seen::First(next))
+        }
+    }
+}
+
+/// Forgets the tables printed so far, so the next table of every content is printed in full again.
+///
+/// The tables are numbered from 1 again, e.g. to start every test case of a run with its own references.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// set_config(Config { dedup: true, ..Config::default() });
+///
+/// let my_u32: u32 = 42;
+/// view_mem!(my_u32); // Prints the table as table #1
+/// view_mem!(my_u32); // Prints a reference to table #1
+/// reset_dedup();
+/// view_mem!(my_u32); // Prints the table again, as table #1
+/// ```
+pub fn reset_dedup() {
+    session().lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
@@ -0,0 +1,102 @@
+//! Dumps emitted as defmt frames.
+//!
+//! On microcontrollers logging usually goes through defmt (e.g. over RTT with probe-rs), which sends the arguments
+//! in binary and formats them on the host. The dumps of this module are emitted as one defmt frame per line, so they
+//! show up next to the other logs with the existing tooling, and only the raw byte of each row is transferred.
+
+use crate::render::{separator, utf8_repr, ADDR_TITLE, ASCII, MEMORY_CONTENT};
+
+#[macro_export]
+/// Macro to view the memory content of an arbitrary variable over defmt.
+///
+/// Same as `view_mem!`, but every line is emitted with `defmt::println!` instead of printed to stdout.
+///
+/// # Argument
+///
+/// * `var` - The variable whose memory content needs to be viewed.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use mem_viewer::*;
+///
+/// let my_u16: u16 = 69;
+/// defmt_view_mem!(my_u16);
+/// ```
+macro_rules! defmt_view_mem {
+    ($var: expr) => {
+        $crate::defmt_mem(stringify!($var), &$var)
+    };
+}
+
+/// Emits the dump of `value` as defmt frames.
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `value` - The value to dump, its `size_of_val` bytes are displayed.
+pub fn defmt_mem<T: ?Sized>(name: &str, value: &T) {
+    let ptr = value as *const T as *const u8;
+    let size = core::mem::size_of_val(value);
+    let bytes = unsafe { core::slice::from_raw_parts(ptr, size) };
+    emit(name, core::any::type_name::<T>(), ptr.addr(), bytes, size == 0);
+}
+
+/// Emits the dump of `len` bytes starting at `ptr` as defmt frames.
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `ptr` - The address of the first byte.
+/// * `len` - The number of bytes to display.
+///
+/// # Safety
+///
+/// The whole region must be readable.
+pub unsafe fn defmt_mem_raw(name: &str, ptr: *const u8, len: usize) {
+    emit(name, "[u8]", ptr.addr(), core::slice::from_raw_parts(ptr, len), false);
+}
+
+fn emit(name: &str, type_name: &str, addr: usize, bytes: &[u8], zero_sized: bool) {
+    defmt::println!("Name: {=str}", name);
+    defmt::println!("Type: {=str}", type_name);
+    // defmt hints are literals, hence one line per address width
+    #[cfg(target_pointer_width = "64")]
+    defmt::println!("Addr: {=usize:016x}", addr);
+    #[cfg(target_pointer_width = "32")]
+    defmt::println!("Addr: {=usize:08x}", addr);
+    #[cfg(target_pointer_width = "16")]
+    defmt::println!("Addr: {=usize:04x}", addr);
+    defmt::println!("Size: {=usize} bytes", bytes.len());
+    if zero_sized {
+        defmt::println!("Zero-sized: the value occupies 0 bytes, there is no memory content to show.");
+        return;
+    }
+    if bytes.is_empty() {
+        defmt::println!("Empty: there is no memory content to show.");
+        return;
+    }
+
+    defmt::println!("{=str}| Hex | Dec |    Bin   | ASCII | UTF-8", ADDR_TITLE);
+    defmt::println!("{=str}", separator(MEMORY_CONTENT, 1));
+    match crate::_omitted_range(bytes.len(), crate::config().max_bytes) {
+        Some(omitted) => {
+            emit_rows(addr, bytes, 0..omitted.start);
+            defmt::println!(" … {=usize} bytes omitted …", omitted.len());
+            emit_rows(addr, bytes, omitted.end..bytes.len());
+        }
+        None => emit_rows(addr, bytes, 0..bytes.len()),
+    }
+}
+
+fn emit_rows(addr: usize, bytes: &[u8], range: core::ops::Range<usize>) {
+    for index in range {
+        let (addr, byte, ascii, utf8) = (addr + index, bytes[index], ASCII[bytes[index] as usize], utf8_repr(bytes, index));
+        #[cfg(target_pointer_width = "64")]
+        defmt::println!(" {0=usize:016x} | {1=u8:02x}  | {1=u8:03} | {1=u8:08b} |  {2=str}  | {3=str} ", addr, byte, ascii, utf8);
+        #[cfg(target_pointer_width = "32")]
+        defmt::println!(" {0=usize:08x} | {1=u8:02x}  | {1=u8:03} | {1=u8:08b} |  {2=str}  | {3=str} ", addr, byte, ascii, utf8);
+        #[cfg(target_pointer_width = "16")]
+        defmt::println!(" {0=usize:04x} | {1=u8:02x}  | {1=u8:03} | {1=u8:08b} |  {2=str}  | {3=str} ", addr, byte, ascii, utf8);
+    }
+}
@@ -0,0 +1,463 @@
+//! Byte-level comparison of two memory regions.
+//!
+//! A [`MemDiff`] compares two [`MemDump`]s offset by offset: the same value before and after some code ran, or two
+//! firmware or EEPROM images loaded with [`MemDump::from_image`](crate::MemDump::from_image). The report lists the
+//! differing bytes page by page, collapses the runs of identical pages, and can label every byte with the field or
+//! register containing it.
+
+use core::fmt;
+use core::ops::Range;
+
+use alloc::vec::Vec;
+
+use crate::dump::MemDump;
+use crate::layout::{self, StructLayout};
+use crate::registers::RegisterMap;
+use crate::render::{separator, ADDR_TITLE};
+
+/// Number of bytes per page used by [`MemDiff::new`], the page size of many flash memories.
+pub const DEFAULT_PAGE_SIZE: usize = 256;
+
+/// Separator line of the diff tables.
+const DIFFERENCES: &str = "-------------------Differences-------------------";
+
+/// A byte differing between the two regions of a [`MemDiff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteChange {
+    /// Offset of the byte from the start of the regions.
+    pub offset: usize,
+    /// Address of the byte in the new region.
+    pub addr: usize,
+    /// The byte of the old region, `None` past its end.
+    pub old: Option<u8>,
+    /// The byte of the new region, `None` past its end.
+    pub new: Option<u8>,
+}
+
+/// A pointer differing between the two regions of a [`MemDiff`], both values looking like valid heap addresses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointerChange {
+    /// Offset of the pointer from the start of the regions.
+    pub offset: usize,
+    /// Address of the pointer in the new region.
+    pub addr: usize,
+    /// The pointer of the old region.
+    pub old: usize,
+    /// The pointer of the new region.
+    pub new: usize,
+}
+
+/// Size of the pointers of the target.
+const POINTER_SIZE: usize = core::mem::size_of::<usize>();
+
+/// Returns `true` if `addr` looks like the address of a heap allocation: aligned, above the first 64 KiB (never
+/// mapped on mainstream systems) and, on 64-bit targets, in the lower half of the canonical address space used by
+/// user space.
+pub fn looks_like_heap_pointer(addr: usize) -> bool {
+    let in_user_space = usize::BITS < 64 || (addr as u64) < 0x0000_8000_0000_0000;
+    addr >= 0x1_0000 && addr.is_multiple_of(POINTER_SIZE) && in_user_space
+}
+
+/// What labels the bytes of a diff.
+#[derive(Clone, Copy)]
+enum Overlay<'a> {
+    None,
+    /// Fields, by offset from the start of the regions.
+    Layout(&'a StructLayout),
+    /// Registers, by address in the new region.
+    Registers(&'a RegisterMap),
+}
+
+/// The comparison of two memory regions, offset by offset.
+///
+/// Bytes expected to change every time, such as a timestamp or a pointer, can be ignored with [`MemDiff::ignore`] and
+/// [`MemDiff::ignore_field`] so they don't drown the real changes.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let mut my_config: [u16; 4] = [1, 2, 3, 4];
+/// let before = MemDump::of("my_config", &my_config);
+/// my_config[2] = 0x0103;
+/// let after = MemDump::of("my_config", &my_config);
+///
+/// let diff = MemDiff::new(&before, &after);
+/// assert_eq!(diff.changes().count(), 1);
+/// print!("{}", diff);
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Old : my_config (8 bytes at 00007ffd3c1ee2f0)
+/// New : my_config (8 bytes at 00007ffd3c1ee2f0)
+/// Diff: 1 bytes differ, in 1 of 1 pages of 256 bytes
+///      Address      | Old | New | Old Bin  | New Bin
+/// -------------------Differences-------------------
+///  00007ffd3c1ee2f5 | 00  | 01  | 00000000 | 00000001
+///
+/// ```
+#[derive(Clone)]
+pub struct MemDiff<'a> {
+    old: &'a MemDump,
+    new: &'a MemDump,
+    page_size: usize,
+    overlay: Overlay<'a>,
+    /// Offsets of the ignored bytes, sorted and merged.
+    ignored: Vec<Range<usize>>,
+    /// Tells the pointers apart, if pointer normalization is enabled.
+    is_pointer: Option<fn(usize) -> bool>,
+    /// The captured targets of the changed pointers, old and new.
+    pointees: Vec<(MemDump, MemDump)>,
+}
+
+impl<'a> MemDiff<'a> {
+    /// Compares `old` with `new`, with pages of [`DEFAULT_PAGE_SIZE`] bytes.
+    ///
+    /// The regions are compared offset by offset, so they may have been captured at different addresses. The
+    /// addresses displayed are those of `new`.
+    ///
+    /// # Arguments
+    ///
+    /// * `old` - The region before the change, e.g. the flash image of the previous firmware.
+    /// * `new` - The region after the change.
+    pub fn new(old: &'a MemDump, new: &'a MemDump) -> Self {
+        MemDiff {
+            old,
+            new,
+            page_size: DEFAULT_PAGE_SIZE,
+            overlay: Overlay::None,
+            ignored: Vec::new(),
+            is_pointer: None,
+            pointees: Vec::new(),
+        }
+    }
+
+    /// Changes the number of bytes per page, e.g. the erase size of a flash memory.
+    ///
+    /// # Argument
+    ///
+    /// * `page_size` - The number of bytes per page, at least 1.
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Labels every differing byte with the field containing it, by offset from the start of the regions.
+    ///
+    /// # Argument
+    ///
+    /// * `layout` - The layout of the regions, e.g. of the configuration struct stored in an EEPROM.
+    pub fn with_layout(mut self, layout: &'a StructLayout) -> Self {
+        self.overlay = Overlay::Layout(layout);
+        self
+    }
+
+    /// Labels every differing byte with the register containing it, by address in the new region.
+    ///
+    /// # Argument
+    ///
+    /// * `registers` - The registers of the regions, e.g. of a peripheral dumped before and after its setup.
+    pub fn with_registers(mut self, registers: &'a RegisterMap) -> Self {
+        self.overlay = Overlay::Registers(registers);
+        self
+    }
+
+    /// Ignores the bytes of a range of offsets, e.g. a field expected to change.
+    ///
+    /// The ignored bytes are never reported as changes and don't make their page differ.
+    ///
+    /// # Argument
+    ///
+    /// * `range` - The offsets of the ignored bytes from the start of the regions.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let before = MemDump::new("packet", "image", 0, vec![1, 2, 3, 4]);
+    /// let after = MemDump::new("packet", "image", 0, vec![1, 9, 9, 4]);
+    /// assert_eq!(MemDiff::new(&before, &after).ignore(1..3).changes().count(), 0);
+    /// ```
+    pub fn ignore(mut self, range: Range<usize>) -> Self {
+        if range.is_empty() {
+            return self;
+        }
+        let index = self.ignored.partition_point(|ignored| ignored.start < range.start);
+        self.ignored.insert(index, range);
+        // Merge the overlapping and adjacent ranges
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(self.ignored.len());
+        for range in self.ignored.drain(..) {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        self.ignored = merged;
+        self
+    }
+
+    /// Ignores the bytes of a field of the regions, e.g. a timestamp or a pointer. Unknown fields are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `layout` - The layout of the regions.
+    /// * `name` - The name of the ignored field.
+    pub fn ignore_field(self, layout: &StructLayout, name: &str) -> Self {
+        match layout.fields.iter().find(|field| field.name == name) {
+            Some(field) => self.ignore(field.offset..field.offset + field.size),
+            None => self,
+        }
+    }
+
+    /// Reports the pointers whose both values look like heap addresses as reallocated, instead of byte changes.
+    ///
+    /// The candidates are the pointer-aligned words of the regions, or the pointer-sized fields of the layout given to
+    /// [`MemDiff::with_layout`]. They are tested with [`looks_like_heap_pointer`].
+    pub fn normalize_pointers(self) -> Self {
+        self.normalize_pointers_with(looks_like_heap_pointer)
+    }
+
+    /// Reports the pointers whose both values pass `is_pointer` as reallocated, instead of byte changes.
+    ///
+    /// # Argument
+    ///
+    /// * `is_pointer` - Tells whether a value is a valid heap address, e.g. by looking it up in the blocks of the heap.
+    pub fn normalize_pointers_with(mut self, is_pointer: fn(usize) -> bool) -> Self {
+        self.is_pointer = Some(is_pointer);
+        self
+    }
+
+    /// Captures `len` bytes at both values of every changed pointer, to report the diff of the pointees under each
+    /// reallocated pointer. Enables pointer normalization if needed.
+    ///
+    /// # Argument
+    ///
+    /// * `len` - The number of bytes captured at each pointer, e.g. the size of the pointed type.
+    ///
+    /// # Safety
+    ///
+    /// Both targets of every changed pointer must still be readable for `len` bytes, which the old one often isn't
+    /// anymore after a reallocation.
+    pub unsafe fn follow_pointers(mut self, len: usize) -> Self {
+        if self.is_pointer.is_none() {
+            self.is_pointer = Some(looks_like_heap_pointer);
+        }
+        let changes: Vec<PointerChange> = self.pointer_changes().collect();
+        self.pointees = changes
+            .into_iter()
+            .map(|change| {
+                let read = |addr: usize, name: &str| {
+                    let name = alloc::format!("*{}+{:#x} ({})", self.new.name, change.offset, name);
+                    MemDump::from_raw(name, core::ptr::with_exposed_provenance(addr), len)
+                };
+                (read(change.old, "old"), read(change.new, "new"))
+            })
+            .collect();
+        self
+    }
+
+    /// Returns the changed pointers, in ascending offset order. Empty unless pointer normalization is enabled.
+    pub fn pointer_changes(&self) -> impl Iterator<Item = PointerChange> + '_ {
+        (0..self.len()).step_by(POINTER_SIZE).filter_map(|offset| self.pointer_change_at(offset))
+    }
+
+    /// Returns the change of the pointer at `offset`, if it is a candidate whose both values look like pointers.
+    fn pointer_change_at(&self, offset: usize) -> Option<PointerChange> {
+        let is_pointer = self.is_pointer?;
+        if !offset.is_multiple_of(POINTER_SIZE) || self.is_ignored(offset) {
+            return None;
+        }
+        if let Overlay::Layout(layout) = self.overlay {
+            layout.fields.iter().find(|field| field.offset == offset && field.size == POINTER_SIZE)?;
+        }
+        let word = |bytes: &[u8]| bytes.get(offset..offset + POINTER_SIZE).map(|word| usize::from_ne_bytes(word.try_into().unwrap()));
+        let (old, new) = (word(&self.old.bytes)?, word(&self.new.bytes)?);
+        (old != new && is_pointer(old) && is_pointer(new)).then_some(PointerChange {
+            offset,
+            addr: self.new.addr + offset,
+            old,
+            new,
+        })
+    }
+
+    /// Returns `true` if the byte at `offset` belongs to a changed pointer.
+    fn in_pointer_change(&self, offset: usize) -> bool {
+        self.is_pointer.is_some() && self.pointer_change_at(offset - offset % POINTER_SIZE).is_some()
+    }
+
+    /// Returns `true` if the byte at `offset` is ignored.
+    fn is_ignored(&self, offset: usize) -> bool {
+        let index = self.ignored.partition_point(|ignored| ignored.end <= offset);
+        self.ignored.get(index).is_some_and(|ignored| ignored.contains(&offset))
+    }
+
+    /// Returns the number of compared bytes, the size of the larger region.
+    pub fn len(&self) -> usize {
+        self.old.bytes.len().max(self.new.bytes.len())
+    }
+
+    /// Returns `true` if both regions are empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the bytes differing between the regions, in ascending offset order.
+    ///
+    /// Past the end of the shorter region, every byte of the longer one is a change. Ignored bytes and the bytes of
+    /// the reallocated pointers are skipped.
+    pub fn changes(&self) -> impl Iterator<Item = ByteChange> + '_ {
+        self.changes_in(0..self.len())
+    }
+
+    fn changes_in(&self, range: core::ops::Range<usize>) -> impl Iterator<Item = ByteChange> + '_ {
+        range.filter_map(|offset| {
+            let (old, new) = (self.old.bytes.get(offset).copied(), self.new.bytes.get(offset).copied());
+            (old != new && !self.is_ignored(offset) && !self.in_pointer_change(offset)).then_some(ByteChange {
+                offset,
+                addr: self.new.addr + offset,
+                old,
+                new,
+            })
+        })
+    }
+
+    /// Returns `true` if the page starting at `start` is the same in both regions, ignored bytes aside.
+    fn page_is_identical(&self, start: usize) -> bool {
+        let end = (start + self.page_size).min(self.len());
+        let page = |bytes: &'a [u8]| bytes.get(start..end.min(bytes.len())).unwrap_or_default();
+        if self.ignored.is_empty() && self.is_pointer.is_none() {
+            return end <= self.old.bytes.len().min(self.new.bytes.len()) && page(&self.old.bytes) == page(&self.new.bytes);
+        }
+        self.changes_in(start..end).next().is_none()
+    }
+
+    /// Formats the report into any writer.
+    pub fn write_to<W: fmt::Write + ?Sized>(&self, out: &mut W) -> fmt::Result {
+        for (label, dump) in [("Old ", self.old), ("New ", self.new)] {
+            writeln!(out, "{}: {} ({} bytes at {})", label, dump.name, dump.bytes.len(), crate::_Addr(dump.addr))?;
+        }
+        let pages = self.len().div_ceil(self.page_size);
+        let changed_pages = (0..pages).filter(|page| !self.page_is_identical(page * self.page_size)).count();
+        let changed_bytes = self.changes().count();
+        if !self.ignored.is_empty() {
+            let ignored: usize = self.ignored.iter().map(|range| range.end.min(self.len()).saturating_sub(range.start)).sum();
+            write!(out, "Mask: {} bytes ignored at offsets ", crate::Thousands(ignored))?;
+            for (index, range) in self.ignored.iter().enumerate() {
+                write!(out, "{}{:#x}..{:#x}", if index == 0 { "" } else { ", " }, range.start, range.end)?;
+            }
+            writeln!(out)?;
+        }
+        let pointers: Vec<PointerChange> = self.pointer_changes().collect();
+        if !pointers.is_empty() {
+            writeln!(out, "Ptrs: {} pointers changed (reallocated)", pointers.len())?;
+        }
+        if changed_bytes == 0 {
+            let except = match (self.ignored.is_empty(), pointers.is_empty()) {
+                (true, true) => "",
+                (false, true) => " outside of the ignored bytes",
+                (true, false) => " apart from the reallocated pointers",
+                (false, false) => " outside of the ignored bytes, apart from the reallocated pointers",
+            };
+            writeln!(out, "Same: the regions are identical{}.", except)?;
+        } else {
+            writeln!(out, "Diff: {} bytes differ, in {} of {} pages of {} bytes", crate::Thousands(changed_bytes), changed_pages, pages, self.page_size)?;
+            self.write_changes(out, pages)?;
+        }
+        for pointer in &pointers {
+            self.write_pointer(out, pointer)?;
+        }
+        writeln!(out)?;
+
+        // The diffs of the pointees follow the report
+        for (old, new) in &self.pointees {
+            MemDiff::new(old, new).write_to(out)?;
+        }
+        Ok(())
+    }
+
+    fn write_changes<W: fmt::Write + ?Sized>(&self, out: &mut W, pages: usize) -> fmt::Result {
+        let annotated = !matches!(self.overlay, Overlay::None);
+        if annotated {
+            writeln!(out, "{}| Old | New | Old Bin  | New Bin  | Annotation", ADDR_TITLE)?;
+        } else {
+            writeln!(out, "{}| Old | New | Old Bin  | New Bin", ADDR_TITLE)?;
+        }
+        writeln!(out, "{}", separator(DIFFERENCES, 1))?;
+
+        let mut page = 0;
+        while page < pages {
+            let identical = (page..pages).take_while(|page| self.page_is_identical(page * self.page_size)).count();
+            if identical > 0 {
+                let (start, end) = (page * self.page_size, ((page + identical) * self.page_size).min(self.len()));
+                let (first, last) = (crate::_Addr(self.new.addr + start), crate::_Addr(self.new.addr + end - 1));
+                writeln!(out, " … {} identical pages, {}..={} ({} bytes) …", identical, first, last, crate::Thousands(end - start))?;
+                page += identical;
+                continue;
+            }
+            let start = page * self.page_size;
+            for change in self.changes_in(start..(start + self.page_size).min(self.len())) {
+                self.write_change(out, &change, annotated)?;
+            }
+            page += 1;
+        }
+        Ok(())
+    }
+
+    /// Writes the line of a reallocated pointer.
+    fn write_pointer<W: fmt::Write + ?Sized>(&self, out: &mut W, pointer: &PointerChange) -> fmt::Result {
+        write!(out, " {} | ", crate::_Addr(pointer.addr))?;
+        {
+            let mut out = &mut *out;
+            match self.overlay {
+                Overlay::Layout(layout) => layout::annotate(layout, pointer.offset, &mut out)?,
+                Overlay::Registers(registers) => registers.write_label(pointer.addr, &mut out)?,
+                Overlay::None => write!(out, "+{:#x}", pointer.offset)?,
+            }
+        }
+        writeln!(out, " | pointer changed (reallocated): {} -> {}", crate::_Addr(pointer.old), crate::_Addr(pointer.new))
+    }
+
+    fn write_change<W: fmt::Write + ?Sized>(&self, out: &mut W, change: &ByteChange, annotated: bool) -> fmt::Result {
+        write!(out, " {} |", crate::_Addr(change.addr))?;
+        for byte in [change.old, change.new] {
+            match byte {
+                Some(byte) => write!(out, " {:02x}  |", byte)?,
+                None => out.write_str(" --  |")?,
+            }
+        }
+        for (index, byte) in [change.old, change.new].into_iter().enumerate() {
+            let end = if index == 0 || annotated { " |" } else { "" };
+            match byte {
+                Some(byte) => write!(out, " {:08b}{}", byte, end)?,
+                None => write!(out, " --------{}", end)?,
+            }
+        }
+        if annotated {
+            out.write_char(' ')?;
+            let mut out = &mut *out;
+            match self.overlay {
+                Overlay::None => {}
+                Overlay::Layout(layout) if change.offset >= layout.size => out.write_str("(beyond layout)")?,
+                Overlay::Layout(layout) => layout::annotate(layout, change.offset, &mut out)?,
+                Overlay::Registers(registers) => registers.write_label(change.addr, &mut out)?,
+            }
+        }
+        writeln!(out)
+    }
+
+    /// Displays the report.
+    #[cfg(feature = "std")]
+    pub fn view(&self) {
+        crate::render::emit(|out| self.write_to(out));
+    }
+}
+
+impl fmt::Display for MemDiff<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f)
+    }
+}
@@ -0,0 +1,383 @@
+//! Captured memory regions and their rows.
+
+use core::fmt;
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::render::{Table, MEMORY_CONTENT};
+use crate::space::AddressSpace;
+
+/// Number of bytes per row used by [`MemDump::rows`] and [`iter_mem_rows`].
+pub const DEFAULT_ROW_WIDTH: usize = 16;
+
+/// A copy of a memory region with the metadata printed in the dump header.
+///
+/// Unlike `view_mem!`, which prints right away, a `MemDump` can be inspected, filtered or printed later.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_u32: u32 = 0xdeadbeef;
+/// let dump = MemDump::of("my_u32", &my_u32);
+/// assert_eq!(dump.bytes.len(), 4);
+/// print!("{}", dump);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemDump {
+    /// Name of the dumped variable or region.
+    pub name: String,
+    /// Type name of the dumped value.
+    pub type_name: &'static str,
+    /// Address of the first byte when the region was captured.
+    pub addr: usize,
+    /// The captured bytes.
+    pub bytes: Vec<u8>,
+}
+
+impl MemDump {
+    /// Creates a dump from already captured bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the region.
+    /// * `type_name` - The type name of the region.
+    /// * `addr` - The address of the first byte.
+    /// * `bytes` - The content of the region.
+    pub fn new(name: impl Into<String>, type_name: &'static str, addr: usize, bytes: Vec<u8>) -> Self {
+        MemDump {
+            name: name.into(),
+            type_name,
+            addr,
+            bytes,
+        }
+    }
+
+    /// Captures the bytes of a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the value.
+    /// * `value` - The value to capture, its `size_of_val` bytes are copied.
+    pub fn of<T: ?Sized>(name: impl Into<String>, value: &T) -> Self {
+        let ptr = value as *const T as *const u8;
+        let len = core::mem::size_of_val(value);
+        let bytes = unsafe { core::slice::from_raw_parts(ptr, len) }.to_vec();
+        // Exposed, so that verify_zeroized! can read the memory again from the address
+        MemDump::new(name, core::any::type_name::<T>(), ptr.expose_provenance(), bytes)
+    }
+
+    /// Captures `len` bytes starting at `ptr`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the region.
+    /// * `ptr` - The address of the first byte.
+    /// * `len` - The number of bytes to capture.
+    ///
+    /// # Safety
+    ///
+    /// The whole region must be readable.
+    pub unsafe fn from_raw(name: impl Into<String>, ptr: *const u8, len: usize) -> Self {
+        let bytes = core::slice::from_raw_parts(ptr, len).to_vec();
+        MemDump::new(name, "[u8]", ptr.addr(), bytes)
+    }
+
+    /// Captures `len` bytes starting at `ptr` with volatile reads, for memory-mapped peripheral registers.
+    ///
+    /// Every byte is read exactly once, in ascending address order, with a byte-wide `read_volatile` which the
+    /// compiler can neither elide nor repeat. The dump is then formatted from the captured copy, so registers with
+    /// read side effects (e.g. a FIFO or a clear-on-read status) are not read again.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the region.
+    /// * `ptr` - The address of the first byte.
+    /// * `len` - The number of bytes to capture.
+    ///
+    /// # Safety
+    ///
+    /// The whole region must be readable with byte-wide accesses. Peripherals only accepting wider accesses must be
+    /// copied by the caller instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let my_register: u32 = 0x8000_0001;
+    /// let dump = unsafe { MemDump::from_raw_volatile("my_register", &my_register as *const u32 as *const u8, 4) };
+    /// assert_eq!(dump.bytes, my_register.to_ne_bytes());
+    /// ```
+    pub unsafe fn from_raw_volatile(name: impl Into<String>, ptr: *const u8, len: usize) -> Self {
+        MemDump::new(name, "[u8]", ptr.addr(), read_volatile_bytes(ptr, len))
+    }
+
+    /// Captures `len` bytes starting at `addr` of another address space, e.g. the linear memory of a wasm guest.
+    ///
+    /// The dump ends early if the region goes beyond the end of the address space.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the region.
+    /// * `space` - The address space to read.
+    /// * `addr` - The address of the first byte in `space`.
+    /// * `len` - The number of bytes to capture.
+    pub fn from_space<A: AddressSpace + ?Sized>(name: impl Into<String>, space: &A, addr: usize, len: usize) -> Self {
+        let mut bytes = vec![0; len];
+        let read = space.read(addr, &mut bytes);
+        bytes.truncate(read);
+        MemDump::new(name, "[u8]", addr, bytes)
+    }
+
+    /// Returns the rows of the dump, [`DEFAULT_ROW_WIDTH`] bytes each.
+    pub fn rows(&self) -> Rows<'_> {
+        Rows::new(self.addr, &self.bytes)
+    }
+
+    /// Writes the bytes as a Rust constant of a byte array, to paste captured memory into test fixtures.
+    ///
+    /// Up to [`DEFAULT_ROW_WIDTH`] bytes fit on one line, longer arrays have a line per row of bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - Where to write the constant.
+    /// * `const_name` - The name of the constant, e.g. `DATA`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let my_u32: u32 = 69;
+    /// let mut code = String::new();
+    /// MemDump::of("my_u32", &my_u32.to_be()).write_rust_array(&mut code, "DATA").unwrap();
+    /// assert_eq!(code, "const DATA: [u8; 4] = [0x00, 0x00, 0x00, 0x45];\n");
+    /// ```
+    pub fn write_rust_array<W: fmt::Write + ?Sized>(&self, out: &mut W, const_name: &str) -> fmt::Result {
+        write!(out, "const {}: [u8; {}] = [", const_name, self.bytes.len())?;
+        write_byte_list(out, &self.bytes)?;
+        writeln!(out, "];")
+    }
+
+    /// Writes the bytes as a C array with a define of its length, to embed captured memory into C test harnesses.
+    ///
+    /// The define is named after the array in upper case with a `_LEN` suffix. Up to [`DEFAULT_ROW_WIDTH`] bytes fit on
+    /// one line, longer arrays have a line per row of bytes. C has no empty arrays, an empty dump is written as an array
+    /// of a single zero byte with a length of 0.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - Where to write the array.
+    /// * `array_name` - The name of the array, e.g. `data`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let my_u32: u32 = 69;
+    /// let mut code = String::new();
+    /// MemDump::of("my_u32", &my_u32.to_be()).write_c_array(&mut code, "data").unwrap();
+    /// assert_eq!(code, "#define DATA_LEN 4\nunsigned char data[] = {0x00, 0x00, 0x00, 0x45};\n");
+    /// ```
+    pub fn write_c_array<W: fmt::Write + ?Sized>(&self, out: &mut W, array_name: &str) -> fmt::Result {
+        writeln!(out, "#define {}_LEN {}", array_name.to_ascii_uppercase(), self.bytes.len())?;
+        if self.bytes.is_empty() {
+            return writeln!(out, "unsigned char {}[1] = {{0x00}};", array_name);
+        }
+        write!(out, "unsigned char {}[] = {{", array_name)?;
+        write_byte_list(out, &self.bytes)?;
+        writeln!(out, "}};")
+    }
+
+    /// Returns the dump as two lines to paste into bug reports: the header metadata as JSON, then the bytes in
+    /// base64 (standard alphabet, padded).
+    ///
+    /// The address is a hex string, JSON numbers can't hold every 64-bit address. The bytes are decoded with any
+    /// base64 decoder, e.g. `tail -n 1 dump.txt | base64 -d > dump.bin`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let dump = MemDump::new("my_u32", "u32", 0x1000, vec![0x45, 0, 0, 0]);
+    /// assert_eq!(dump.to_base64(), "{\"name\":\"my_u32\",\"type\":\"u32\",\"addr\":\"0x1000\",\"size\":4}\nRQAAAA==\n");
+    /// ```
+    pub fn to_base64(&self) -> String {
+        let mut out = String::with_capacity(self.bytes.len().div_ceil(3) * 4 + 64);
+        out.push_str("{\"name\":");
+        push_json_string(&mut out, &self.name);
+        out.push_str(",\"type\":");
+        push_json_string(&mut out, self.type_name);
+        out.push_str(&alloc::format!(",\"addr\":\"{:#x}\",\"size\":{}}}\n", self.addr, self.bytes.len()));
+        for chunk in self.bytes.chunks(3) {
+            let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| group | (*byte as u32) << (16 - 8 * index));
+            for index in 0..4 {
+                let digit = if index <= chunk.len() { BASE64_DIGITS[(group >> (18 - 6 * index) & 0x3f) as usize] } else { b'=' };
+                out.push(digit as char);
+            }
+        }
+        out.push('\n');
+        out
+    }
+}
+
+/// Digits of the standard base64 alphabet.
+const BASE64_DIGITS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Appends `s` as a JSON string, quoted and escaped.
+fn push_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&alloc::format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Writes the bytes of an array literal: on the line of the opening bracket up to [`DEFAULT_ROW_WIDTH`] bytes, else a
+/// line per row of bytes followed by the line of the closing bracket.
+fn write_byte_list<W: fmt::Write + ?Sized>(out: &mut W, bytes: &[u8]) -> fmt::Result {
+    if bytes.len() <= DEFAULT_ROW_WIDTH {
+        for (index, byte) in bytes.iter().enumerate() {
+            let comma = if index == 0 { "" } else { ", " };
+            write!(out, "{}{:#04x}", comma, byte)?;
+        }
+        return Ok(());
+    }
+    writeln!(out)?;
+    for row in bytes.chunks(DEFAULT_ROW_WIDTH) {
+        out.write_str("   ")?;
+        for byte in row {
+            write!(out, " {:#04x},", byte)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Reads `len` bytes starting at `ptr`, each one exactly once with `read_volatile`, in ascending address order.
+///
+/// # Safety
+///
+/// The whole region must be readable with byte-wide accesses.
+pub(crate) unsafe fn read_volatile_bytes(ptr: *const u8, len: usize) -> Vec<u8> {
+    (0..len).map(|index| ptr.add(index).read_volatile()).collect()
+}
+
+impl fmt::Display for MemDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        crate::write::write_header(f, &self.name, self.type_name, self.addr, self.bytes.len())?;
+        Table::new(MEMORY_CONTENT, self.addr, &self.bytes).write_to(f)
+    }
+}
+
+/// A row of a memory region.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row<'a> {
+    /// Address of the first byte of the row.
+    pub addr: usize,
+    /// Offset of the first byte of the row from the start of the region.
+    pub offset: usize,
+    /// The bytes of the row, the last row may be shorter than the others.
+    pub bytes: &'a [u8],
+    /// One character per byte: printable ASCII as is, anything else as `.`.
+    pub ascii: String,
+}
+
+/// Iterator over the rows of a memory region, see [`MemDump::rows`] and [`iter_mem_rows`].
+///
+/// Rows are produced on demand, nothing is formatted for the rows you skip.
+#[derive(Debug, Clone)]
+pub struct Rows<'a> {
+    addr: usize,
+    bytes: &'a [u8],
+    width: usize,
+    offset: usize,
+}
+
+impl<'a> Rows<'a> {
+    fn new(addr: usize, bytes: &'a [u8]) -> Self {
+        Rows {
+            addr,
+            bytes,
+            width: DEFAULT_ROW_WIDTH,
+            offset: 0,
+        }
+    }
+
+    /// Changes the number of bytes per row.
+    ///
+    /// # Argument
+    ///
+    /// * `width` - The number of bytes per row, at least 1.
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = width.max(1);
+        self
+    }
+}
+
+impl<'a> Iterator for Rows<'a> {
+    type Item = Row<'a>;
+
+    fn next(&mut self) -> Option<Row<'a>> {
+        if self.offset >= self.bytes.len() {
+            return None;
+        }
+        let end = (self.offset + self.width).min(self.bytes.len());
+        let bytes = &self.bytes[self.offset..end];
+        let row = Row {
+            addr: self.addr + self.offset,
+            offset: self.offset,
+            bytes,
+            ascii: bytes.iter().map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' }).collect(),
+        };
+        self.offset = end;
+        Some(row)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.bytes.len() - self.offset.min(self.bytes.len())).div_ceil(self.width);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Rows<'_> {}
+
+/// Returns the rows of `len` bytes starting at `ptr`, [`DEFAULT_ROW_WIDTH`] bytes each, read lazily.
+///
+/// # Arguments
+///
+/// * `ptr` - The address of the first byte.
+/// * `len` - The number of bytes of the region.
+///
+/// # Safety
+///
+/// The whole region must be readable, and must not be mutated while the rows are alive.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_buf = [0u8, 1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 7];
+/// let non_zero = unsafe { iter_mem_rows(my_buf.as_ptr(), my_buf.len()) }
+///     .filter(|row| row.bytes.iter().any(|&byte| byte != 0))
+///     .count();
+/// assert_eq!(non_zero, 2);
+/// ```
+pub unsafe fn iter_mem_rows<'a>(ptr: *const u8, len: usize) -> Rows<'a> {
+    Rows::new(ptr.addr(), core::slice::from_raw_parts(ptr, len))
+}
@@ -0,0 +1,206 @@
+//! Tables of typed elements.
+//!
+//! Buffers of numbers (vertex data, uniforms, compute results) read better one element per row with its value than
+//! one byte per row. The same table is used for CPU slices and for GPU buffers read back with the `wgpu` feature.
+
+use core::fmt;
+
+use alloc::string::String;
+
+use crate::render::{separator, ADDR_TITLE};
+
+/// Type of the elements of a buffer, read in native byte order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ElementType {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    F32,
+    F64,
+}
+
+impl ElementType {
+    /// Returns the size of an element in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            ElementType::U8 | ElementType::I8 => 1,
+            ElementType::U16 | ElementType::I16 => 2,
+            ElementType::U32 | ElementType::I32 | ElementType::F32 => 4,
+            ElementType::U64 | ElementType::I64 | ElementType::F64 => 8,
+        }
+    }
+
+    /// Returns the Rust name of the type, e.g. `f32`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ElementType::U8 => "u8",
+            ElementType::I8 => "i8",
+            ElementType::U16 => "u16",
+            ElementType::I16 => "i16",
+            ElementType::U32 => "u32",
+            ElementType::I32 => "i32",
+            ElementType::U64 => "u64",
+            ElementType::I64 => "i64",
+            ElementType::F32 => "f32",
+            ElementType::F64 => "f64",
+        }
+    }
+
+    /// Writes the value of the element stored in `bytes`, which are exactly [`ElementType::size`] long.
+    pub(crate) fn write_value<W: fmt::Write + ?Sized>(self, out: &mut W, bytes: &[u8]) -> fmt::Result {
+        macro_rules! value {
+            ($ty: ty) => {
+                <$ty>::from_ne_bytes(bytes.try_into().map_err(|_| fmt::Error)?)
+            };
+        }
+        match self {
+            ElementType::U8 => write!(out, "{}", value!(u8)),
+            ElementType::I8 => write!(out, "{}", value!(i8)),
+            ElementType::U16 => write!(out, "{}", value!(u16)),
+            ElementType::I16 => write!(out, "{}", value!(i16)),
+            ElementType::U32 => write!(out, "{}", value!(u32)),
+            ElementType::I32 => write!(out, "{}", value!(i32)),
+            ElementType::U64 => write!(out, "{}", value!(u64)),
+            ElementType::I64 => write!(out, "{}", value!(i64)),
+            ElementType::F32 => write!(out, "{:?}", value!(f32)),
+            ElementType::F64 => write!(out, "{:?}", value!(f64)),
+        }
+    }
+
+    /// Width of the interpretation column: the longest value, or about as long for floats.
+    pub(crate) fn column_width(self) -> usize {
+        match self {
+            ElementType::U8 => 3,
+            ElementType::I8 => 4,
+            ElementType::U16 => 5,
+            ElementType::I16 => 6,
+            ElementType::U32 => 10,
+            ElementType::I32 => 11,
+            ElementType::F32 => 14,
+            ElementType::U64 | ElementType::I64 => 20,
+            ElementType::F64 => 24,
+        }
+    }
+
+    /// Width of the interpretation column in a row: `|   -1 `.
+    pub(crate) fn width(self) -> usize {
+        self.column_width() + 3
+    }
+
+    /// Writes the title of the interpretation column, e.g. ` | i16   `.
+    pub(crate) fn write_title<W: fmt::Write + ?Sized>(self, out: &mut W) -> fmt::Result {
+        write!(out, " | {:<width$}", self.name(), width = self.column_width())
+    }
+
+    /// Writes the interpretation column of the byte at `index` of `bytes`: the value of the element starting there if
+    /// `index` is aligned to the size of the element and the element fits, blanks otherwise.
+    pub(crate) fn write_column<W: fmt::Write + ?Sized>(self, out: &mut W, bytes: &[u8], index: usize) -> fmt::Result {
+        let size = self.size();
+        let mut value = String::new();
+        if index.is_multiple_of(size) && index + size <= bytes.len() {
+            self.write_value(&mut value, &bytes[index..index + size])?;
+        }
+        write!(out, "| {:>width$} ", value, width = self.column_width())
+    }
+}
+
+impl fmt::Display for ElementType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Separator line of the element tables.
+const ELEMENTS: &str = "------------------------Elements------------------------";
+
+/// Writes the table of the elements of `bytes`, one per row with its address, index, bytes and value.
+///
+/// The `max_bytes` cap of the configuration applies as for byte tables. Trailing bytes not forming a whole element
+/// are reported after the table.
+///
+/// # Arguments
+///
+/// * `out` - The writer receiving the table.
+/// * `addr` - The address displayed for the first byte.
+/// * `bytes` - The content of the buffer.
+/// * `element` - The type of the elements.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_floats: [f32; 2] = [1.0, -0.5];
+/// let bytes: Vec<u8> = my_floats.iter().flat_map(|value| value.to_ne_bytes()).collect();
+/// let mut out = String::new();
+/// write_elements(&mut out, 0, &bytes, ElementType::F32).unwrap();
+/// assert!(out.contains("|     1 | 00 00 00 bf | -0.5\n"));
+/// ```
+pub fn write_elements<W: fmt::Write + ?Sized>(out: &mut W, addr: usize, bytes: &[u8], element: ElementType) -> fmt::Result {
+    let size = element.size();
+    let count = bytes.len() / size;
+    if count == 0 {
+        writeln!(out, "Empty: there is no whole {} element to show.", element)?;
+    } else {
+        let hex_width = size * 3 - 1;
+        writeln!(out, "{}| Index | {:<hex_width$} | Value ({})", ADDR_TITLE, "Hex", element)?;
+        writeln!(out, "{}", separator(ELEMENTS, 1))?;
+        let max_elements = crate::config().max_bytes.div_ceil(size);
+        let write_rows = |out: &mut W, range: core::ops::Range<usize>| -> fmt::Result {
+            for index in range {
+                let element_bytes = &bytes[index * size..(index + 1) * size];
+                write!(out, " {} | {:>5} | ", crate::_Addr(addr + index * size), index)?;
+                for (position, byte) in element_bytes.iter().enumerate() {
+                    let space = if position == 0 { "" } else { " " };
+                    write!(out, "{}{:02x}", space, byte)?;
+                }
+                out.write_str(" | ")?;
+                element.write_value(out, element_bytes)?;
+                writeln!(out)?;
+            }
+            Ok(())
+        };
+        match crate::_omitted_range(count, max_elements) {
+            Some(omitted) => {
+                write_rows(out, 0..omitted.start)?;
+                writeln!(out, " … {} elements omitted …", crate::Thousands(omitted.len()))?;
+                write_rows(out, omitted.end..count)?;
+            }
+            None => write_rows(out, 0..count)?,
+        }
+    }
+    let rest = bytes.len() % size;
+    if rest != 0 {
+        writeln!(out, "Rest: {} trailing bytes don't form a whole {} element.", rest, element)?;
+    }
+    writeln!(out)
+}
+
+/// Displays the elements of a byte buffer, one per row with its value.
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `bytes` - The content of the buffer.
+/// * `element` - The type of the elements.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_vertices: Vec<u8> = [0.0f32, 0.5, 1.0].iter().flat_map(|value| value.to_ne_bytes()).collect();
+/// view_elements("my_vertices", &my_vertices, ElementType::F32);
+/// ```
+#[cfg(feature = "std")]
+pub fn view_elements(name: &str, bytes: &[u8], element: ElementType) {
+    crate::render::emit(|out| {
+        crate::write::write_header(out, name, &alloc::format!("[{}]", element), bytes.as_ptr().addr(), bytes.len())?;
+        write_elements(out, bytes.as_ptr().addr(), bytes, element)
+    });
+}
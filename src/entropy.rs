@@ -0,0 +1,89 @@
+//! Shannon entropy of the dumped bytes.
+//!
+//! Enabled with [`Config::entropy`](crate::Config::entropy), the entropy of the whole region and of every 16-byte row
+//! is written below the memory content tables: compressed or encrypted data is close to 8 bits per byte, text and
+//! structured data much lower, and fill patterns 0.
+
+use core::fmt;
+
+/// Number of bytes of the rows whose entropy is listed.
+const ROW_LEN: usize = 16;
+
+/// Length of the bar of 8 bits per byte.
+const BAR_WIDTH: usize = 32;
+
+/// Returns the Shannon entropy of `bytes` in bits per byte, from 0 (a single repeated value) to 8 (every value
+/// equally frequent). The entropy of no bytes is 0.
+///
+/// # Argument
+///
+/// * `bytes` - The bytes to measure.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// assert_eq!(shannon_entropy(&[0; 64]), 0.0);
+/// assert_eq!(shannon_entropy(b"abcd"), 2.0);
+/// assert_eq!(shannon_entropy(&(0..=255).collect::<Vec<u8>>()), 8.0);
+/// ```
+pub fn shannon_entropy(bytes: &[u8]) -> f64 {
+    let mut counts = [0usize; 256];
+    for byte in bytes {
+        counts[*byte as usize] += 1;
+    }
+    let len = bytes.len() as f64;
+    let entropy: f64 = counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let p = *count as f64 / len;
+            -p * p.log2()
+        })
+        .sum();
+    // A single value gives -0.0
+    if entropy > 0.0 {
+        entropy
+    } else {
+        0.0
+    }
+}
+
+/// Writes the entropy line of the row of `bytes` at `index`.
+fn write_row<W: fmt::Write + ?Sized>(out: &mut W, addr: usize, bytes: &[u8], index: usize) -> fmt::Result {
+    let row = &bytes[index * ROW_LEN..((index + 1) * ROW_LEN).min(bytes.len())];
+    let entropy = shannon_entropy(row);
+    write!(out, "  {} | {:.2} | ", crate::_Addr(addr + index * ROW_LEN), entropy)?;
+    for _ in 0..(entropy * BAR_WIDTH as f64 / 8.0).round() as usize {
+        out.write_char('#')?;
+    }
+    writeln!(out)
+}
+
+/// Writes the entropy of `bytes` and of each of its rows, the footer of the memory content tables. The rows of the
+/// bytes omitted by `max_bytes` are omitted too.
+pub(crate) fn write_entropy<W: fmt::Write + ?Sized>(out: &mut W, addr: usize, bytes: &[u8], max_bytes: usize) -> fmt::Result {
+    if bytes.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "Entr: {:.2} bits per byte over {} bytes, per {}-byte row:", shannon_entropy(bytes), crate::Thousands(bytes.len()), ROW_LEN)?;
+    let rows = bytes.len().div_ceil(ROW_LEN);
+    match crate::_omitted_range(rows, max_bytes.div_ceil(ROW_LEN)) {
+        Some(omitted) => {
+            for index in 0..omitted.start {
+                write_row(out, addr, bytes, index)?;
+            }
+            writeln!(out, "  … {} rows omitted …", crate::Thousands(omitted.len()))?;
+            for index in omitted.end..rows {
+                write_row(out, addr, bytes, index)?;
+            }
+        }
+        None => {
+            for index in 0..rows {
+                write_row(out, addr, bytes, index)?;
+            }
+        }
+    }
+    Ok(())
+}
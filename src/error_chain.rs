@@ -0,0 +1,118 @@
+//! Dumps of error chains.
+//!
+//! Walks an error and its `source()` chain, dumping the memory of every error object with its type name. Useful to
+//! debug suspicious error wrapping and to see how much memory the error path really carries around, e.g. inside a
+//! `Box<dyn Error>` or an `anyhow::Error` (pass `err.as_ref()`).
+
+use std::error::Error;
+use std::fmt::Write;
+
+use crate::render::{self, Table, MEMORY_CONTENT};
+
+/// Recognizes errors of one concrete type.
+type Matcher = fn(&(dyn Error + 'static)) -> bool;
+
+/// Dumps error chains, naming the errors whose type is known.
+///
+/// The concrete type of a `dyn Error` can't be named at runtime, it is found by trying the registered types. The
+/// common error types of `std` are registered by default.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+/// use std::error::Error;
+///
+/// let my_error: Box<dyn Error> = "x".parse::<u8>().unwrap_err().into();
+/// ErrorChainViewer::default().view("my_error", my_error.as_ref());
+/// ```
+pub struct ErrorChainViewer {
+    known: Vec<(&'static str, Matcher)>,
+}
+
+impl Default for ErrorChainViewer {
+    fn default() -> Self {
+        let mut viewer = ErrorChainViewer::new();
+        viewer
+            .register::<std::io::Error>()
+            .register::<std::fmt::Error>()
+            .register::<std::num::ParseIntError>()
+            .register::<std::num::ParseFloatError>()
+            .register::<std::num::TryFromIntError>()
+            .register::<std::str::Utf8Error>()
+            .register::<std::string::FromUtf8Error>()
+            .register::<std::str::ParseBoolError>()
+            .register::<std::char::ParseCharError>()
+            .register::<std::array::TryFromSliceError>()
+            .register::<std::ffi::NulError>()
+            .register::<std::time::SystemTimeError>()
+            .register::<std::env::VarError>();
+        viewer
+    }
+}
+
+impl ErrorChainViewer {
+    /// Creates a viewer without any known error type.
+    pub fn new() -> Self {
+        ErrorChainViewer { known: Vec::new() }
+    }
+
+    /// Registers an error type so it gets named in the dumps.
+    pub fn register<E: Error + 'static>(&mut self) -> &mut Self {
+        self.known.push((std::any::type_name::<E>(), |err| err.is::<E>()));
+        self
+    }
+
+    /// Returns the type name of `err` if its type is registered.
+    pub fn type_name_of(&self, err: &(dyn Error + 'static)) -> Option<&'static str> {
+        self.known.iter().find(|(_, matches)| matches(err)).map(|(name, _)| *name)
+    }
+
+    /// Dumps `err` and every error of its `source()` chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name displayed in the header.
+    /// * `err` - The outermost error.
+    pub fn view(&self, name: &str, err: &(dyn Error + 'static)) {
+        let mut total = 0;
+        let mut count = 0;
+        let mut next = Some(err);
+        while let Some(err) = next {
+            let ptr = err as *const dyn Error as *const u8;
+            let addr = ptr.addr();
+            let size = std::mem::size_of_val(err);
+            total += size;
+
+            render::emit(|out| {
+                writeln!(out, "Name: {} #{}", name, count)?;
+                writeln!(out, "Type: {}", self.type_name_of(err).unwrap_or("unknown (dyn Error)"))?;
+                writeln!(out, "Mesg: {}", err)?;
+                writeln!(out, "Addr: {}", crate::_HeaderAddr(addr))?;
+                writeln!(out, "Size: {} bytes", size)?;
+                if size == 0 {
+                    render::write_zst_note(out, addr)
+                } else if crate::config().miri_friendly {
+                    render::write_miri_note(out)
+                } else {
+                    let bytes = unsafe { std::slice::from_raw_parts(ptr, size) };
+                    Table { label: name, ..Table::new(MEMORY_CONTENT, addr, bytes) }.write(out)
+                }
+            });
+
+            count += 1;
+            next = err.source();
+        }
+        render::emit(|out| writeln!(out, "Chain: {} errors, {} bytes dumped (a source stored inline is also counted in its parent)\n", count, total));
+    }
+}
+
+/// Dumps `err` and every error of its `source()` chain, naming the common error types of `std`.
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `err` - The outermost error.
+pub fn view_error_chain(name: &str, err: &(dyn Error + 'static)) {
+    ErrorChainViewer::default().view(name, err);
+}
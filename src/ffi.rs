@@ -0,0 +1,126 @@
+//! Dumps of C structs received over FFI.
+//!
+//! A C library hands out raw pointers, often with the length of the pointed struct only known from its header. The
+//! [`view_c_struct!`](crate::view_c_struct) macro dumps such a region, annotated with the fields of a layout built
+//! with [`layout_of!`](crate::layout_of) (e.g. on a bindgen-generated struct) or parsed with
+//! [`StructLayout::parse`](crate::StructLayout::parse) from a short description.
+
+use std::fmt::{self, Write};
+
+use crate::layout::{self, StructLayout};
+use crate::render::{self, Table, MEMORY_CONTENT};
+
+#[macro_export]
+/// Macro to view the memory content of a C struct behind a raw pointer, annotated with its fields.
+///
+/// # Arguments
+///
+/// * `ptr` - The raw pointer received from C, of any pointee type.
+/// * `len` - The number of bytes to display.
+/// * `layout` (optional) - A `&StructLayout` describing the fields.
+///
+/// The macro reads `len` bytes at `ptr`, so it must be called in an `unsafe` block. A null pointer is reported instead
+/// of dumped.
+///
+/// # Safety
+///
+/// Unless `ptr` is null, the `len` bytes at `ptr` must be readable.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// #[repr(C)]
+/// struct point_t {
+///     id: u16,
+///     flags: u8,
+///     x: f32,
+/// }
+///
+/// let my_point = point_t { id: 69, flags: 0xff, x: 3.5 };
+/// let ptr: *const std::ffi::c_void = &my_point as *const point_t as *const _;
+///
+/// let layout = StructLayout::parse("point_t { id: uint16_t @ 0 + 2, flags: uint8_t @ 2 + 1, x: float @ 4 + 4 }").unwrap();
+/// unsafe { view_c_struct!(ptr, 8, &layout) };
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: ptr
+/// Type: point_t
+/// Addr: 00007ffd5d1ce4c0
+/// Size: 8 bytes
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ----------------------Memory Content--------------------
+///  00007ffd5d1ce4c0 | 45  | 069 | 01000101 |   E   | ... | id: uint16_t
+///  00007ffd5d1ce4c1 | 00  | 000 | 00000000 |  NUL  | ... | id +1
+///  00007ffd5d1ce4c2 | ff  | 255 | 11111111 |  ...  | ... | flags: uint8_t
+///  00007ffd5d1ce4c3 | 00  | 000 | 00000000 |  NUL  |    ` | (padding)
+///  00007ffd5d1ce4c4 | 00  | 000 | 00000000 |  NUL  |   `@ | x: float
+///  00007ffd5d1ce4c5 | 00  | 000 | 00000000 |  NUL  | XXX | x +1
+///  00007ffd5d1ce4c6 | 60  | 096 | 01100000 |   `   | XXX | x +2
+///  00007ffd5d1ce4c7 | 40  | 064 | 01000000 |   @   | XXX | x +3
+/// ```
+macro_rules! view_c_struct {
+    ($ptr: expr, $len: expr) => {
+        $crate::_show_c_struct(stringify!($ptr), $ptr as *const u8, $len, None)
+    };
+    ($ptr: expr, $len: expr, $layout: expr) => {
+        $crate::_show_c_struct(stringify!($ptr), $ptr as *const u8, $len, Some($layout))
+    };
+}
+
+/// Displays a C struct behind a raw pointer, annotated with its layout.
+///
+/// (This is supposed to be private usage of view_c_struct! macro usage.)
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `ptr` - The address of the struct.
+/// * `len` - The number of bytes to display.
+/// * `layout` - The layout of the struct, if known.
+///
+/// # Safety
+///
+/// Unless `ptr` is null, the `len` bytes at `ptr` must be readable.
+pub unsafe fn _show_c_struct(name: &str, ptr: *const u8, len: usize, layout: Option<&StructLayout>) {
+    let write_header = |out: &mut String| -> fmt::Result {
+        crate::write::write_header(out, name, layout.map_or("unknown (no layout)", |layout| layout.name), ptr.addr(), len)?;
+        match layout.filter(|layout| layout.size != len) {
+            Some(layout) => writeln!(out, "Note: the layout describes {} bytes", layout.size),
+            None => Ok(()),
+        }
+    };
+    if ptr.is_null() {
+        render::emit(|out| {
+            write_header(out)?;
+            writeln!(out, "Null: the pointer is null, there is no memory content to show.\n")
+        });
+        return;
+    }
+    if crate::config().miri_friendly {
+        render::emit(|out| {
+            write_header(out)?;
+            render::write_miri_note(out)
+        });
+        return;
+    }
+
+    let bytes = std::slice::from_raw_parts(ptr, len);
+    let annotate = |offset: usize, out: &mut dyn core::fmt::Write| match layout {
+        Some(layout) if offset >= layout.size => out.write_str("(beyond layout)"),
+        Some(layout) => layout::annotate(layout, offset, out),
+        None => Ok(()),
+    };
+    let mut table = Table { label: name, ..Table::new(MEMORY_CONTENT, ptr.addr(), bytes) };
+    if layout.is_some() {
+        table.annotate = Some(&annotate);
+    }
+    render::emit(|out| {
+        write_header(out)?;
+        table.write(out)
+    });
+}
@@ -0,0 +1,73 @@
+//! Debug fill patterns recognized in the dumped bytes.
+//!
+//! Debug allocators and compilers fill uninitialized, freed and guard memory with well-known byte values, e.g. `cd`
+//! for the uninitialized blocks of the MSVC debug heap. Enabled with
+//! [`Config::fill_patterns`](crate::Config::fill_patterns), the runs of these values are listed above the memory
+//! content tables, so a dump of freed or uninitialized memory tells so at once.
+
+use core::fmt;
+use core::ops::Range;
+
+use alloc::vec::Vec;
+
+use crate::render::HEX;
+
+/// Minimal length of a run of a debug fill value to be reported, shorter runs are likely data.
+pub(crate) const MIN_FILL_RUN: usize = 4;
+
+/// Maximal number of `Fill` lines above a table.
+const MAX_FILL_LINES: usize = 8;
+
+/// Returns what a byte value filling memory for debugging means, if anything.
+pub(crate) fn debug_fill_name(byte: u8) -> Option<&'static str> {
+    match byte {
+        0xcc => Some("MSVC uninitialized stack"),
+        0xcd => Some("MSVC uninitialized heap"),
+        0xdd => Some("MSVC freed heap"),
+        0xfd => Some("MSVC guard bytes"),
+        0xfe => Some("MSVC secure CRT unused buffer"),
+        0xab => Some("MSVC HeapAlloc guard after block"),
+        0xaa => Some("pattern-initialized (-ftrivial-auto-var-init=pattern)"),
+        0xa5 => Some("jemalloc junk"),
+        0x5a => Some("jemalloc freed"),
+        _ => None,
+    }
+}
+
+/// Returns what a byte value filling memory usually means, if anything: a debug fill value, zeroed memory or erased
+/// flash.
+pub(crate) fn fill_name(byte: u8) -> Option<&'static str> {
+    match byte {
+        0x00 => Some("zeroed"),
+        0xff => Some("erased flash"),
+        _ => debug_fill_name(byte),
+    }
+}
+
+/// Returns the runs of at least [`MIN_FILL_RUN`] bytes of a debug fill value in `bytes`, with the value.
+pub(crate) fn fill_runs(bytes: &[u8]) -> Vec<(Range<usize>, u8)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    while start < bytes.len() {
+        let value = bytes[start];
+        let len = bytes[start..].iter().take_while(|byte| **byte == value).count();
+        if len >= MIN_FILL_RUN && debug_fill_name(value).is_some() {
+            runs.push((start..start + len, value));
+        }
+        start += len;
+    }
+    runs
+}
+
+/// Writes a `Fill` line per run of a debug fill value in `bytes`, above the memory content tables.
+pub(crate) fn write_fill_runs<W: fmt::Write + ?Sized>(out: &mut W, bytes: &[u8]) -> fmt::Result {
+    let runs = fill_runs(bytes);
+    for (range, value) in runs.iter().take(MAX_FILL_LINES) {
+        let name = debug_fill_name(*value).unwrap_or_default();
+        writeln!(out, "Fill: +{:#x}..+{:#x}, {} bytes of {}, {}", range.start, range.end, crate::Thousands(range.len()), HEX[*value as usize], name)?;
+    }
+    if runs.len() > MAX_FILL_LINES {
+        writeln!(out, "Fill: … {} more runs", runs.len() - MAX_FILL_LINES)?;
+    }
+    Ok(())
+}
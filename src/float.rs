@@ -0,0 +1,213 @@
+//! Anatomy of IEEE 754 floats.
+//!
+//! `view_float!` splits the bits of an `f32` or `f64` into its sign, exponent and mantissa, with the value of each part
+//! and the class of the number, to see why `0.1 + 0.2 != 0.3` or where the payload of a NaN hides.
+
+use core::fmt;
+
+/// Class of a float, from its exponent and mantissa.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FloatClass {
+    /// Positive or negative zero: all the exponent and mantissa bits are clear.
+    Zero,
+    /// Below the smallest normal number: the exponent bits are clear, the mantissa has no implicit leading 1.
+    Subnormal,
+    /// A normal number, with an implicit leading 1 before the mantissa.
+    Normal,
+    /// Positive or negative infinity: all the exponent bits are set, the mantissa bits are clear.
+    Infinite,
+    /// Not a number: all the exponent bits are set. The first mantissa bit tells quiet NaNs from signaling ones, the
+    /// other bits are the payload.
+    Nan {
+        /// Whether the first mantissa bit is set.
+        quiet: bool,
+        /// The mantissa bits after the first one.
+        payload: u64,
+    },
+}
+
+/// The bits of an `f32` or `f64`, split into their IEEE 754 parts.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let anatomy = FloatAnatomy::from(-0.75f32);
+/// assert_eq!((anatomy.sign(), anatomy.biased_exponent(), anatomy.unbiased_exponent()), (true, 126, -1));
+/// assert_eq!(anatomy.mantissa(), 1 << 22);
+/// assert_eq!(anatomy.class(), FloatClass::Normal);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FloatAnatomy {
+    /// The bits of the float, in the low bits for an `f32`.
+    pub bits: u64,
+    /// The number of exponent bits: 8 for an `f32`, 11 for an `f64`.
+    pub exponent_bits: u32,
+    /// The number of mantissa bits: 23 for an `f32`, 52 for an `f64`.
+    pub mantissa_bits: u32,
+}
+
+impl From<f32> for FloatAnatomy {
+    fn from(value: f32) -> Self {
+        FloatAnatomy { bits: value.to_bits() as u64, exponent_bits: 8, mantissa_bits: 23 }
+    }
+}
+
+impl From<f64> for FloatAnatomy {
+    fn from(value: f64) -> Self {
+        FloatAnatomy { bits: value.to_bits(), exponent_bits: 11, mantissa_bits: 52 }
+    }
+}
+
+impl FloatAnatomy {
+    /// Returns the Rust name of the float type.
+    pub fn type_name(&self) -> &'static str {
+        if self.exponent_bits == 8 {
+            "f32"
+        } else {
+            "f64"
+        }
+    }
+
+    /// Returns whether the sign bit is set.
+    pub fn sign(&self) -> bool {
+        self.bits >> (self.exponent_bits + self.mantissa_bits) & 1 == 1
+    }
+
+    /// Returns the exponent bits as stored, biased by [`FloatAnatomy::bias`].
+    pub fn biased_exponent(&self) -> u64 {
+        self.bits >> self.mantissa_bits & ((1 << self.exponent_bits) - 1)
+    }
+
+    /// Returns the bias of the exponent: 127 for an `f32`, 1023 for an `f64`.
+    pub fn bias(&self) -> i32 {
+        (1 << (self.exponent_bits - 1)) - 1
+    }
+
+    /// Returns the power of 2 scaling the significand. Subnormal numbers and zeros use the exponent of the smallest
+    /// normal numbers, `1 - bias`.
+    pub fn unbiased_exponent(&self) -> i32 {
+        self.biased_exponent().max(1) as i32 - self.bias()
+    }
+
+    /// Returns the mantissa bits, without the implicit leading bit.
+    pub fn mantissa(&self) -> u64 {
+        self.bits & ((1 << self.mantissa_bits) - 1)
+    }
+
+    /// Returns the significand: the mantissa after the implicit leading 1 of normal numbers, or 0 of subnormal ones.
+    pub fn significand(&self) -> f64 {
+        let fraction = self.mantissa() as f64 / (1u64 << self.mantissa_bits) as f64;
+        if self.biased_exponent() == 0 {
+            fraction
+        } else {
+            1.0 + fraction
+        }
+    }
+
+    /// Returns the class of the float.
+    pub fn class(&self) -> FloatClass {
+        let all_set = (1 << self.exponent_bits) - 1;
+        match (self.biased_exponent(), self.mantissa()) {
+            (0, 0) => FloatClass::Zero,
+            (0, _) => FloatClass::Subnormal,
+            (exponent, 0) if exponent == all_set => FloatClass::Infinite,
+            (exponent, mantissa) if exponent == all_set => {
+                let quiet_bit = 1 << (self.mantissa_bits - 1);
+                FloatClass::Nan { quiet: mantissa & quiet_bit != 0, payload: mantissa & (quiet_bit - 1) }
+            }
+            _ => FloatClass::Normal,
+        }
+    }
+
+    /// Writes the value of the float as its own type.
+    fn write_value<W: fmt::Write + ?Sized>(&self, out: &mut W) -> fmt::Result {
+        if self.exponent_bits == 8 {
+            write!(out, "{:?}", f32::from_bits(self.bits as u32))
+        } else {
+            write!(out, "{:?}", f64::from_bits(self.bits))
+        }
+    }
+}
+
+/// Formats the anatomy of a float into `out`, only its name and type if `redact` is set.
+pub(crate) fn write_float<W: fmt::Write + ?Sized>(out: &mut W, name: &str, float: &FloatAnatomy, redact: bool) -> fmt::Result {
+    let exponent_width = float.exponent_bits as usize;
+    let mantissa_width = float.mantissa_bits as usize;
+    let sign = if float.sign() { '-' } else { '+' };
+    writeln!(out, "Name: {}", name)?;
+    writeln!(out, "Type: {}", float.type_name())?;
+    // The bits, the exponent, the mantissa and the class all tell the value
+    if redact {
+        writeln!(out, "Valu: redacted")?;
+        return writeln!(out);
+    }
+    out.write_str("Valu: ")?;
+    float.write_value(out)?;
+    writeln!(out)?;
+    writeln!(out, "Bits: {} {:0exponent_width$b} {:0mantissa_width$b}", float.sign() as u8, float.biased_exponent(), float.mantissa())?;
+    writeln!(out, "Sign: {} ({})", float.sign() as u8, sign)?;
+    writeln!(out, "Expo: {:0exponent_width$b} = {} biased, {} unbiased (bias {})", float.biased_exponent(), float.biased_exponent(), float.unbiased_exponent(), float.bias())?;
+    write!(out, "Mant: {:0mantissa_width$b} = {:#x}", float.mantissa(), float.mantissa())?;
+    // Infinities and NaNs have no significand
+    match float.class() {
+        FloatClass::Infinite | FloatClass::Nan { .. } => writeln!(out)?,
+        _ => writeln!(out, ", significand {:?} with the implicit {}", float.significand(), (float.biased_exponent() != 0) as u8)?,
+    }
+    match float.class() {
+        FloatClass::Zero => writeln!(out, "Clas: Zero ({}0)", sign)?,
+        FloatClass::Subnormal => {
+            writeln!(out, "Clas: Subnormal")?;
+            writeln!(out, "      {}{:?} x 2^{}", sign, float.significand(), float.unbiased_exponent())?;
+        }
+        FloatClass::Normal => {
+            writeln!(out, "Clas: Normal")?;
+            writeln!(out, "      {}{:?} x 2^{}", sign, float.significand(), float.unbiased_exponent())?;
+        }
+        FloatClass::Infinite => writeln!(out, "Clas: Infinite ({}inf)", sign)?,
+        FloatClass::Nan { quiet, payload } => {
+            let kind = if quiet { "quiet" } else { "signaling" };
+            writeln!(out, "Clas: NaN ({}, payload {:#x})", kind, payload)?;
+        }
+    }
+    writeln!(out)
+}
+
+#[cfg(feature = "std")]
+#[macro_export]
+/// Macro to print the anatomy of an `f32` or `f64`: its sign, exponent and mantissa bits, the biased and unbiased
+/// exponent, the significand and the class of the number (normal, subnormal, zero, infinite, or NaN with its payload).
+/// When [`Config::redact`](crate::Config::redact) is set, only the name and the type are shown, with `Valu: redacted`.
+///
+/// # Argument
+///
+/// * `x` - The float to explain.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_f32: f32 = 3.14;
+/// view_float!(my_f32);
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_f32
+/// Type: f32
+/// Valu: 3.14
+/// Bits: 0 10000000 10010001111010111000011
+/// Sign: 0 (+)
+/// Expo: 10000000 = 128 biased, 1 unbiased (bias 127)
+/// Mant: 10010001111010111000011 = 0x48f5c3, significand 1.5700000524520874 with the implicit 1
+/// Clas: Normal
+///       +1.5700000524520874 x 2^1
+/// ```
+macro_rules! view_float {
+    ($x: expr) => {
+        $crate::_show_float(stringify!($x), &$crate::FloatAnatomy::from($x));
+    };
+}
@@ -0,0 +1,234 @@
+//! Walks of the call stack, with the memory of every frame.
+//!
+//! The unwinder of the `backtrace` crate finds the frames of the call stack from the unwind tables of the modules:
+//! the address every function resumes at, and its stack pointer. The bytes of a frame lie between its stack pointer
+//! and the stack pointer of its caller, and hold the return address to its caller on most targets, so a frame whose
+//! return address is missing has likely been overwritten. [`view_frames!`](crate::view_frames) dumps every frame with its symbolized return address.
+
+use std::fmt::{self, Write};
+
+use crate::render::{self, Table, MEMORY_CONTENT};
+use crate::Symbol;
+
+const WORD: usize = core::mem::size_of::<usize>();
+
+/// A frame of the call stack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackFrame {
+    /// Address the function of the frame resumes at, the return address of the call to the next inner frame.
+    pub ip: usize,
+    /// Lowest address of the frame, its stack pointer when it called the next inner frame, 0 if the unwinder doesn't
+    /// tell it.
+    pub start: usize,
+    /// Address past the highest byte of the frame, the stack pointer of its caller, 0 if the unwinder doesn't tell it.
+    pub end: usize,
+    /// Function of the frame, with its offset and the source line of `ip`. Inlined functions share the frame of the
+    /// function they are inlined into, which names it.
+    pub symbol: Option<Symbol>,
+}
+
+impl StackFrame {
+    /// Returns the number of bytes of the frame, 0 if its bounds are unknown.
+    pub fn size(&self) -> usize {
+        if self.start == 0 || self.end == 0 {
+            return 0;
+        }
+        self.end.saturating_sub(self.start)
+    }
+}
+
+/// Returns the innermost `count` frames of the call stack, the frame of the function calling `stack_frames` first.
+///
+/// The frames of this crate and of the `backtrace` crate are skipped, and the walk stops at the frames of the runtime
+/// calling `main` or the closure of a thread. Without unwind tables, e.g. with `panic = "abort"` on some targets, the
+/// walk may stop early.
+///
+/// # Argument
+///
+/// * `count` - The maximal number of frames returned.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// for frame in stack_frames(4) {
+///     let name = frame.symbol.as_ref().map_or(String::from("?"), |symbol| symbol.to_string());
+///     println!("{:x} {} bytes {}", frame.ip, frame.size(), name);
+/// }
+/// ```
+#[inline(never)]
+pub fn stack_frames(count: usize) -> Vec<StackFrame> {
+    let mut frames: Vec<StackFrame> = Vec::new();
+    if count == 0 || crate::config().miri_friendly {
+        return frames;
+    }
+    backtrace::trace(|frame| {
+        let (ip, sp) = (frame.ip() as usize, frame.sp() as usize);
+        // The stack pointer of a frame ends the frame it called
+        if let Some(inner) = frames.last_mut().filter(|inner| inner.start != 0 && inner.start <= sp) {
+            inner.end = sp;
+        }
+        if frames.len() == count {
+            return false;
+        }
+        let symbol = resolve_return_address(ip);
+        let name = symbol.as_ref().map_or("", |symbol| symbol.name.as_str());
+        if name.contains("__rust_begin_short_backtrace") {
+            return false;
+        }
+        if !["backtrace::", "mem_viewer::frames::"].iter().any(|skipped| name.starts_with(skipped)) {
+            frames.push(StackFrame { ip, start: sp, end: 0, symbol });
+        }
+        true
+    });
+    frames
+}
+
+/// Resolves a return address to the call instruction before it, in the function of the frame, the return address
+/// being at the start of the next function after a call which doesn't return.
+pub(crate) fn resolve_return_address(ip: usize) -> Option<Symbol> {
+    let symbol = crate::resolve_symbol(ip.checked_sub(1)?)?;
+    Some(Symbol { offset: symbol.offset + 1, ..symbol })
+}
+
+/// Formats the frames of the call stack: a `Fram` line per frame with its bounds and its function, a `Retn` line with
+/// the return address to its caller and where it is in the frame, then the bytes of the frame copied in `copies`.
+///
+/// `frames` may hold a frame more than shown, the caller of the last one, to find its return address.
+pub(crate) fn write_frames(out: &mut String, frames: &[StackFrame], shown: usize, copies: &[Vec<u8>]) -> fmt::Result {
+    let thread = std::thread::current();
+    match thread.name() {
+        Some(name) => writeln!(out, "Name: stack frames of thread \"{}\"", name)?,
+        None => writeln!(out, "Name: stack frames of thread {:?}", thread.id())?,
+    }
+    let shown = shown.min(frames.len());
+    let size: usize = frames[..shown].iter().map(StackFrame::size).sum();
+    writeln!(out, "Frms: {} frames from the caller of the walk, {} bytes of stack", shown, size)?;
+    if crate::config().miri_friendly {
+        return render::write_miri_note(out);
+    }
+    writeln!(out)?;
+
+    for (index, frame) in frames[..shown].iter().enumerate() {
+        write!(out, "Fram: #{} ", index)?;
+        match frame.size() {
+            0 => write!(out, "bounds unknown")?,
+            size => write!(out, "{}..{}, {} bytes", crate::_Addr(frame.start), crate::_Addr(frame.end), size)?,
+        }
+        match &frame.symbol {
+            Some(symbol) => {
+                write!(out, ", {}", symbol)?;
+                if let (Some(file), Some(line)) = (&symbol.file, symbol.line) {
+                    write!(out, " at {}:{}", file, line)?;
+                }
+                writeln!(out)?;
+            }
+            None => writeln!(out, ", resumes at {} (no symbol)", crate::StyledAddr(frame.ip))?,
+        }
+
+        let bytes = copies.get(index).map_or(&[][..], Vec::as_slice);
+        let caller = frames.get(index + 1);
+        // The return address is stored in an aligned word of the frame on most targets
+        let stored = caller.and_then(|caller| {
+            (frame.start.next_multiple_of(WORD) - frame.start..bytes.len().saturating_sub(WORD - 1))
+                .step_by(WORD)
+                .find(|offset| usize::from_ne_bytes(bytes[*offset..*offset + WORD].try_into().unwrap()) == caller.ip)
+        });
+        if let Some(caller) = caller {
+            write!(out, "Retn: {}", crate::StyledAddr(caller.ip))?;
+            if let Some(symbol) = &caller.symbol {
+                write!(out, " {}", symbol)?;
+            }
+            match stored {
+                Some(offset) => writeln!(out, " at +{:#x}", offset)?,
+                None if bytes.is_empty() => writeln!(out)?,
+                None => writeln!(out, " not in the frame: kept in a register, or overwritten")?,
+            }
+        }
+        if bytes.is_empty() {
+            writeln!(out)?;
+            continue;
+        }
+        let annotate = |offset: usize, out: &mut dyn fmt::Write| match stored {
+            Some(stored) if offset == stored => write!(out, "return address to #{}", index + 1),
+            Some(stored) if (stored..stored + WORD).contains(&offset) => write!(out, "return address +{}", offset - stored),
+            _ => Ok(()),
+        };
+        Table { label: "frame", annotate: Some(&annotate), ..Table::new(MEMORY_CONTENT, frame.start, bytes) }.write(out)?;
+    }
+    Ok(())
+}
+
+#[macro_export]
+/// Macro to walk the call stack of the current thread and view the memory of each frame, with its symbolized return
+/// address.
+///
+/// The frames are found by the unwinder of the `backtrace` crate, see [`stack_frames`](crate::stack_frames), from the
+/// function calling the macro outwards. A `Fram` line per frame gives its bounds and its function with the source
+/// line, a `Retn` line the return address to its caller, symbolized, and the offset of the word holding it, annotated
+/// in the table. A return address missing from its frame tells a smashed frame, or a target keeping it in a register.
+/// Requires the `symbols` feature. In Miri-friendly mode nothing is walked.
+///
+/// # Argument
+///
+/// * `max_frames` - The maximal number of frames shown.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// #[inline(never)]
+/// fn my_parser(input: &[u8]) -> usize {
+///     view_frames!(2);
+///     input.len()
+/// }
+///
+/// my_parser(b"GET / HTTP/1.1");
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: stack frames of thread "main"
+/// Frms: 2 frames from the caller of the walk, 208 bytes of stack
+///
+/// Fram: #0 00007ffd2a1c3e40..00007ffd2a1c3e90, 80 bytes, <rust_out::main::my_parser+0x2c> at src/main.rs:5
+/// Retn: 000055d1c3a3fb64 <rust_out::main+0x54> at +0x48
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ----------------------Memory Content--------------------
+/// ...
+///  00007ffd2a1c3e88 | 64  | 100 | 01100100 |   d   | ... | return address to #1
+///  00007ffd2a1c3e89 | fb  | 251 | 11111011 |  ...  | ... | return address +1
+/// ...
+/// ```
+macro_rules! view_frames {
+    ($max_frames: expr) => {
+        $crate::_show_frames($max_frames)
+    };
+}
+
+/// Displays the innermost `max_frames` frames of the call stack with their memory.
+///
+/// (This is supposed to be private usage of view_frames! macro usage.)
+///
+/// # Argument
+///
+/// * `max_frames` - The maximal number of frames shown.
+#[inline(never)]
+pub fn _show_frames(max_frames: usize) {
+    let frames = stack_frames(max_frames.saturating_add(1));
+    let copies: Vec<Vec<u8>> = frames.iter().take(max_frames).map(read_frame).collect();
+    render::emit(|out| write_frames(out, &frames, max_frames, &copies));
+}
+
+/// Copies the bytes of a frame of a caller, or nothing if its bounds aren't within the stack of the thread.
+pub(crate) fn read_frame(frame: &StackFrame) -> Vec<u8> {
+    let within = crate::stack_bounds().is_some_and(|stack| frame.size() > 0 && stack.contains(&frame.start) && frame.end <= stack.end);
+    if !within {
+        return Vec::new();
+    }
+    // SAFETY: the frame is in the stack of the thread, above the frame of the caller of this function
+    unsafe { crate::dump::read_volatile_bytes(core::ptr::with_exposed_provenance(frame.start), frame.size()) }
+}
@@ -0,0 +1,385 @@
+//! Dumps of futures, with the state of their generated state machine located.
+//!
+//! An `async fn` or an `async` block is compiled into a state machine: an enum with a state per await point, plus
+//! `Unresumed`, `Returned` and `Panicked`, whose tag tells where the future stopped. The arguments and the locals
+//! held across an await point are its fields, those of the states overlapping, so the largest state sizes the future.
+//! Rust doesn't expose this layout at run time, but the compiler prints it with `-Zprint-type-sizes`:
+//! [`parse_type_sizes`] reads it back, and [`view_future!`](crate::view_future) then locates the tag and the fields of
+//! the current state in the bytes of the future.
+
+use std::fmt::{self, Write};
+
+use crate::render::{self, Table, MEMORY_CONTENT};
+
+/// A field of a state of a future: an argument captured by the future (`upvar`) or a local held across an await point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateField {
+    /// Name of the argument or local.
+    pub name: &'static str,
+    /// `true` for a local, `false` for an argument.
+    pub local: bool,
+    /// Type of the field, only printed by the compiler for the futures awaited (`__awaitee`).
+    pub type_name: Option<&'static str>,
+    /// Offset of the field from the start of the future, in bytes.
+    pub offset: usize,
+    /// Size of the field in bytes.
+    pub size: usize,
+}
+
+/// A state of a future: `Unresumed`, `Returned`, `Panicked`, or `Suspend0`, `Suspend1`, ... for its await points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FutureState {
+    /// Name of the state.
+    pub name: &'static str,
+    /// Fields stored in this state, in memory order.
+    pub fields: Vec<StateField>,
+}
+
+impl FutureState {
+    /// Returns the value of the tag of the future in this state: 0 for `Unresumed`, 1 for `Returned`, 2 for
+    /// `Panicked`, 3 for `Suspend0`, 4 for `Suspend1`, ...
+    pub fn tag(&self) -> Option<u64> {
+        match self.name {
+            "Unresumed" => Some(0),
+            "Returned" => Some(1),
+            "Panicked" => Some(2),
+            name => name.strip_prefix("Suspend")?.parse::<u64>().ok().map(|index| index + 3),
+        }
+    }
+
+    /// Returns the number of bytes taken by the fields of this state.
+    pub fn field_bytes(&self) -> usize {
+        self.fields.iter().map(|field| field.size).sum()
+    }
+
+    /// Returns the offset past the last field of this state.
+    pub fn end(&self) -> usize {
+        self.fields.iter().map(|field| field.offset + field.size).max().unwrap_or(0)
+    }
+}
+
+/// Layout of the state machine of a future, as printed by the compiler with `-Zprint-type-sizes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FutureLayout {
+    /// Name of the type as printed by the compiler, e.g. `{async fn body of fetch()}` or
+    /// `{async block@src/main.rs:3:13: 3:18}`.
+    pub name: &'static str,
+    /// Size of the future in bytes.
+    pub size: usize,
+    /// Alignment of the future in bytes.
+    pub align: usize,
+    /// Size of the tag in bytes.
+    pub tag_size: usize,
+    /// States of the future, in the order printed.
+    pub states: Vec<FutureState>,
+}
+
+impl FutureLayout {
+    /// Returns the offset of the tag: the first bytes, aligned to its size, covered by no field of any state.
+    pub fn tag_offset(&self) -> Option<usize> {
+        let covered = |offset: usize| self.states.iter().flat_map(|state| &state.fields).any(|field| (field.offset..field.offset + field.size).contains(&offset));
+        (0..self.size.saturating_sub(self.tag_size) + 1)
+            .step_by(self.tag_size.max(1))
+            .find(|&offset| !(offset..offset + self.tag_size).any(covered))
+    }
+
+    /// Returns the state whose tag is `tag`, if any.
+    pub fn state(&self, tag: u64) -> Option<&FutureState> {
+        self.states.iter().find(|state| state.tag() == Some(tag))
+    }
+
+    /// Returns the largest field of all the states, with the number of states storing it.
+    pub fn largest_field(&self) -> Option<(&StateField, usize)> {
+        let field = self.states.iter().flat_map(|state| &state.fields).max_by_key(|field| field.size)?;
+        let states = self.states.iter().filter(|state| state.fields.contains(field)).count();
+        Some((field, states))
+    }
+
+    /// Returns `true` if this layout is the one of the future of type `type_name`, as given by
+    /// `core::any::type_name`, taking `size` bytes.
+    ///
+    /// An `async fn` is recognized by its name, e.g. `{async fn body of Client::fetch()}` is the layout of
+    /// `my_crate::Client::fetch::{{closure}}`. An `async` block has no name at run time, it is recognized by its size.
+    pub fn matches(&self, type_name: &str, size: usize) -> bool {
+        let Some(path) = type_name.strip_suffix("::{{closure}}") else {
+            return false;
+        };
+        if self.size != size {
+            return false;
+        }
+        match self.name.strip_prefix("{async fn body of ") {
+            Some(function) => last_segment(function.trim_end_matches("()}")) == last_segment(path),
+            None => self.name.starts_with("{async block@"),
+        }
+    }
+}
+
+/// Returns the last segment of a path, without the generic arguments, e.g. `fetch` for `my_crate::fetch::<u32>`.
+fn last_segment(path: &str) -> String {
+    let mut depth = 0;
+    let plain: String = path
+        .chars()
+        .filter(|&c| {
+            depth += (c == '<') as i32;
+            let keep = depth == 0;
+            depth -= (c == '>') as i32;
+            keep
+        })
+        .collect();
+    plain.trim_end_matches("::").rsplit("::").next().unwrap_or_default().to_string()
+}
+
+/// Returns the layouts of the futures printed by the compiler with `-Zprint-type-sizes`.
+///
+/// The output is printed by a nightly compiler, e.g. `cargo +nightly rustc -- -Zprint-type-sizes > type-sizes.txt`.
+/// Only the state machines of `async fn`s and `async` blocks are kept. The offsets of the fields are computed like the
+/// compiler prints them: from the size of the tag, the `offset` given to a field placed before it and the `padding`
+/// lines between the fields. The names are borrowed from the
+/// output, which is usually included with `include_str!`.
+///
+/// # Argument
+///
+/// * `output` - The lines printed with `-Zprint-type-sizes`, with or without their `print-type-size` prefix.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let layouts = parse_type_sizes("\
+/// print-type-size type: `{async fn body of fetch()}`: 24 bytes, alignment: 8 bytes
+/// print-type-size     discriminant: 1 bytes
+/// print-type-size     variant `Unresumed`: 4 bytes
+/// print-type-size         upvar `.id`: 4 bytes, offset: 0 bytes, alignment: 4 bytes
+/// print-type-size     variant `Suspend0`: 15 bytes
+/// print-type-size         upvar `.id`: 4 bytes, offset: 0 bytes, alignment: 4 bytes
+/// print-type-size         padding: 4 bytes
+/// print-type-size         local `.__awaitee`: 8 bytes, alignment: 8 bytes, type: Sleep
+/// print-type-size         local `.retries`: 3 bytes
+/// ");
+/// assert_eq!(layouts[0].states[1].fields[1].offset, 8);
+/// assert_eq!(layouts[0].tag_offset(), Some(4));
+/// ```
+pub fn parse_type_sizes(output: &'static str) -> Vec<FutureLayout> {
+    let mut layouts: Vec<FutureLayout> = Vec::new();
+    // Whether the type being read is a future, and the offset of the next field of its current state
+    let mut in_future = false;
+    let mut offset = 0;
+    for line in output.lines() {
+        let line = line.trim_start().strip_prefix("print-type-size").unwrap_or(line).trim();
+        if let Some(rest) = line.strip_prefix("type: `") {
+            in_future = rest.starts_with("{async");
+            if let (true, Some((name, rest))) = (in_future, rest.split_once("`: ")) {
+                let (size, align) = rest.split_once(", alignment: ").unwrap_or((rest, "1 bytes"));
+                layouts.push(FutureLayout { name, size: bytes(size).unwrap_or(0), align: bytes(align).unwrap_or(1), tag_size: 0, states: Vec::new() });
+            }
+            continue;
+        }
+        let Some(layout) = layouts.last_mut().filter(|_| in_future) else {
+            continue;
+        };
+        if let Some(size) = line.strip_prefix("discriminant: ") {
+            layout.tag_size = bytes(size).unwrap_or(0);
+        } else if let Some(rest) = line.strip_prefix("variant `") {
+            let name = rest.split('`').next().unwrap_or_default();
+            layout.states.push(FutureState { name, fields: Vec::new() });
+            // The compiler counts the padding of every state from the end of the tag
+            offset = layout.tag_size;
+        } else if let Some(size) = line.strip_prefix("padding: ") {
+            offset += bytes(size).unwrap_or(0);
+        } else if let Some((local, rest)) = line.strip_prefix("upvar `").map(|rest| (false, rest)).or_else(|| line.strip_prefix("local `").map(|rest| (true, rest))) {
+            let (Some(state), Some((name, rest))) = (layout.states.last_mut(), rest.split_once("`: ")) else {
+                continue;
+            };
+            let mut attributes = rest.split(", ");
+            let size = attributes.next().and_then(bytes).unwrap_or(0);
+            let mut type_name = None;
+            for attribute in attributes {
+                if let Some(at) = attribute.strip_prefix("offset: ").and_then(bytes) {
+                    offset = at;
+                } else if let Some(name) = attribute.strip_prefix("type: ") {
+                    type_name = Some(name);
+                }
+            }
+            state.fields.push(StateField { name: name.trim_start_matches('.'), local, type_name, offset, size });
+            offset += size;
+        }
+    }
+    layouts
+}
+
+/// Parses a size printed by the compiler, e.g. `24 bytes`.
+fn bytes(text: &str) -> Option<usize> {
+    text.trim().strip_suffix(" bytes").or_else(|| text.trim().strip_suffix(" byte"))?.parse().ok()
+}
+
+/// Writes the annotation of the byte at `offset` of a future: the tag, or the field of the current state containing it.
+fn annotate(state: Option<&FutureState>, tag: Option<(usize, usize, u64)>, offset: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+    if let Some((at, size, value)) = tag {
+        if offset == at {
+            write!(out, "state tag = {}", value)?;
+            return match state {
+                Some(state) => write!(out, " ({})", state.name),
+                None => Ok(()),
+            };
+        }
+        if (at..at + size).contains(&offset) {
+            return write!(out, "state tag +{}", offset - at);
+        }
+    }
+    let Some(field) = state.and_then(|state| state.fields.iter().find(|field| (field.offset..field.offset + field.size).contains(&offset))) else {
+        return Ok(());
+    };
+    match field.type_name {
+        _ if offset > field.offset => write!(out, "{} +{}", field.name, offset - field.offset),
+        Some(type_name) => write!(out, "{}: {}", field.name, type_name),
+        None if field.local => write!(out, "{} (local)", field.name),
+        None => write!(out, "{} (argument)", field.name),
+    }
+}
+
+/// Formats a future: its header, its current state located with its layout if one matches, then its bytes annotated
+/// with the fields of this state.
+pub(crate) fn write_future<F>(out: &mut String, name: &str, future: &F, layouts: &[FutureLayout]) -> fmt::Result {
+    let ptr = future as *const F as *const u8;
+    let size = core::mem::size_of::<F>();
+    let type_name = core::any::type_name::<F>();
+    crate::write::write_header(out, name, type_name, ptr.addr(), size)?;
+    let Some(layout) = layouts.iter().find(|layout| layout.matches(type_name, size)) else {
+        writeln!(out, "Stat: unknown, no layout given for this future, see parse_type_sizes")?;
+        if crate::config().miri_friendly {
+            return render::write_miri_note(out);
+        }
+        let bytes = unsafe { core::slice::from_raw_parts(ptr, size) };
+        return Table { label: name, ..Table::new(MEMORY_CONTENT, ptr.addr(), bytes) }.write(out);
+    };
+    writeln!(out, "Lout: {}, {} states, tag of {} byte(s)", layout.name, layout.states.len(), layout.tag_size)?;
+    if crate::config().miri_friendly {
+        return render::write_miri_note(out);
+    }
+
+    // Like `view_mem!`, the bytes are read whatever the state, those of the other states included
+    let bytes = unsafe { core::slice::from_raw_parts(ptr, size) };
+    let tag = layout.tag_offset().filter(|_| (1..=8).contains(&layout.tag_size)).map(|at| {
+        let mut value = [0u8; 8];
+        let tag_bytes = &bytes[at..at + layout.tag_size];
+        if cfg!(target_endian = "little") {
+            value[..layout.tag_size].copy_from_slice(tag_bytes);
+        } else {
+            value[8 - layout.tag_size..].copy_from_slice(tag_bytes);
+        }
+        (at, layout.tag_size, u64::from_ne_bytes(value))
+    });
+    let state = tag.and_then(|(_, _, value)| layout.state(value));
+    match (tag, state) {
+        (Some((at, _, value)), Some(state)) => writeln!(out, "Stat: {}, tag {} at +{:#x}", state.name, value, at)?,
+        (Some((at, _, value)), None) => writeln!(out, "Stat: INVALID tag {} at +{:#x}, no state has this value", value, at)?,
+        (None, _) => writeln!(out, "Stat: unknown, the tag isn't found in the layout")?,
+    }
+    let largest = layout.states.iter().map(FutureState::end).max().unwrap_or(0);
+    for (index, other) in layout.states.iter().enumerate() {
+        let label = if index == 0 { "Stts: " } else { "      " };
+        write!(out, "{}{}: {} field(s), {} bytes up to +{:#x}", label, other.name, other.fields.len(), other.field_bytes(), other.end())?;
+        if other.end() == largest {
+            out.push_str(", largest");
+        }
+        if state == Some(other) {
+            out.push_str(", current");
+        }
+        writeln!(out)?;
+    }
+    if let Some((field, states)) = layout.largest_field() {
+        writeln!(out, "Bigg: {}, {} bytes, stored by {} of the {} states", field.name, field.size, states, layout.states.len())?;
+    }
+    if let Some(state) = state {
+        let first = state.fields.iter().map(|field| field.offset).min().unwrap_or(0);
+        writeln!(out, "Live: +{:#x}..+{:#x}, {} bytes in {} field(s) of {}, the other bytes are the tag, padding or left by other states", first, state.end(), state.field_bytes(), state.fields.len(), state.name)?;
+    }
+
+    let annotate = |offset: usize, out: &mut dyn fmt::Write| annotate(state, tag, offset, out);
+    Table { label: name, annotate: Some(&annotate), ..Table::new(MEMORY_CONTENT, ptr.addr(), bytes) }.write(out)
+}
+
+#[macro_export]
+/// Macro to view the memory content of a future, with the current state of its state machine located.
+///
+/// The compiler turns an `async fn` or an `async` block into an enum with a state per await point, whose fields are
+/// the arguments and the locals held across it. Without its layout, the macro shows the size of the future and its
+/// bytes. With the layouts printed by the compiler with `-Zprint-type-sizes`, read by
+/// [`parse_type_sizes`](crate::parse_type_sizes), it finds the layout of the future and shows it: a `Stat` line with
+/// the current state and where its tag is, a `Stts` line per state with the bytes taken by its fields, the largest
+/// sizing the future, a `Bigg` line with the largest field, and a `Live` line with the region of the fields of the
+/// current state, whose bytes are annotated in the table. In Miri-friendly mode the memory content isn't shown.
+///
+/// # Arguments
+///
+/// * `future` - The future to dump, dereferenced like for `view_mem!` when pinned, e.g. `*my_future`.
+/// * `layouts` - Optionally, the layouts returned by `parse_type_sizes`.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// async fn fetch(id: u32) -> u32 {
+///     let buffer = [0u8; 64];
+///     std::future::ready(()).await;
+///     id + buffer[0] as u32
+/// }
+///
+/// let my_future = fetch(7);
+/// view_future!(my_future);
+///
+/// // With the layouts printed by `cargo +nightly rustc -- -Zprint-type-sizes > type-sizes.txt`
+/// // let layouts = parse_type_sizes(include_str!("../type-sizes.txt"));
+/// // view_future!(my_future, layouts);
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_future
+/// Type: rust_out::main::fetch::{{closure}}
+/// Addr: 00007ffd2a81c2b0
+/// Size: 76 bytes
+/// Lout: {async fn body of main::fetch()}, 4 states, tag of 1 byte(s)
+/// Stat: Unresumed, tag 0 at +0x48
+/// Stts: Unresumed: 1 field(s), 4 bytes up to +0x44, current
+///       Suspend0: 3 field(s), 72 bytes up to +0x48, largest
+///       Returned: 1 field(s), 4 bytes up to +0x44
+///       Panicked: 1 field(s), 4 bytes up to +0x44
+/// Bigg: buffer, 64 bytes, stored by 1 of the 4 states
+/// Live: +0x40..+0x44, 4 bytes in 1 field(s) of Unresumed, the other bytes are the tag, padding or left by other states
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ----------------------Memory Content--------------------
+///  00007ffd2a81c2b0 | 00  | 000 | 00000000 |  NUL  | ... |
+/// ...
+///  00007ffd2a81c2f0 | 07  | 007 | 00000111 |  BEL  | ... | id (argument)
+///  00007ffd2a81c2f1 | 00  | 000 | 00000000 |  NUL  | ... | id +1
+///  00007ffd2a81c2f2 | 00  | 000 | 00000000 |  NUL  | ... | id +2
+///  00007ffd2a81c2f3 | 00  | 000 | 00000000 |  NUL  | ... | id +3
+/// ...
+///  00007ffd2a81c2f8 | 00  | 000 | 00000000 |  NUL  | XXX | state tag = 0 (Unresumed)
+/// ...
+/// ```
+macro_rules! view_future {
+    ($future: expr) => {
+        $crate::_show_future(stringify!($future), &$future, &[])
+    };
+    ($future: expr, $layouts: expr) => {
+        $crate::_show_future(stringify!($future), &$future, &$layouts)
+    };
+}
+
+/// Displays the memory content of a future, with its current state located if one of the layouts is its own.
+///
+/// (This is supposed to be private usage of view_future! macro usage.)
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `future` - The future to display.
+/// * `layouts` - The layouts of futures parsed from the compiler output.
+pub fn _show_future<F: core::future::Future>(name: &str, future: &F, layouts: &[FutureLayout]) {
+    render::emit(|out| write_future(out, name, future, layouts));
+}
@@ -0,0 +1,215 @@
+//! glibc malloc introspection.
+//!
+//! Reports the allocator statistics of `mallinfo2`/`malloc_info` and decodes the chunk header glibc stores right
+//! before every allocation, which helps when debugging C code that mixes allocators with Rust.
+//!
+//! Enabled with the `glibc` feature, only available on Linux with glibc (2.33 or newer for `mallinfo2`).
+
+use std::fmt::Write;
+use std::mem::size_of;
+
+use crate::render::{Table, MEMORY_CONTENT};
+
+// Flag bits stored in the low bits of the chunk `size` field, see `malloc/malloc.c`.
+const PREV_INUSE: usize = 0x1;
+const IS_MMAPPED: usize = 0x2;
+const NON_MAIN_ARENA: usize = 0x4;
+const SIZE_BITS: usize = PREV_INUSE | IS_MMAPPED | NON_MAIN_ARENA;
+
+/// Allocator statistics as reported by `mallinfo2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MallInfo {
+    /// Non-mmapped space allocated from the system.
+    pub arena: usize,
+    /// Number of free chunks.
+    pub ordblks: usize,
+    /// Number of free fastbin blocks.
+    pub smblks: usize,
+    /// Number of mmapped regions.
+    pub hblks: usize,
+    /// Space allocated in mmapped regions.
+    pub hblkhd: usize,
+    /// Maximum total allocated space (always 0 since glibc 2.33).
+    pub usmblks: usize,
+    /// Space in freed fastbin blocks.
+    pub fsmblks: usize,
+    /// Total allocated space.
+    pub uordblks: usize,
+    /// Total free space.
+    pub fordblks: usize,
+    /// Top-most, releasable space.
+    pub keepcost: usize,
+}
+
+/// Returns the allocator statistics of the main arena and all thread arenas.
+pub fn mallinfo() -> MallInfo {
+    let info = unsafe { libc::mallinfo2() };
+    MallInfo {
+        arena: info.arena,
+        ordblks: info.ordblks,
+        smblks: info.smblks,
+        hblks: info.hblks,
+        hblkhd: info.hblkhd,
+        usmblks: info.usmblks,
+        fsmblks: info.fsmblks,
+        uordblks: info.uordblks,
+        fordblks: info.fordblks,
+        keepcost: info.keepcost,
+    }
+}
+
+/// Prints the allocator statistics of `mallinfo2`.
+pub fn print_mallinfo() {
+    let info = mallinfo();
+    crate::render::emit(|out| {
+        writeln!(out, "Arena (non-mmapped) : {} bytes", info.arena)?;
+        writeln!(out, "Free chunks         : {}", info.ordblks)?;
+        writeln!(out, "Free fastbin blocks : {}", info.smblks)?;
+        writeln!(out, "Mmapped regions     : {}", info.hblks)?;
+        writeln!(out, "Mmapped space       : {} bytes", info.hblkhd)?;
+        writeln!(out, "Fastbin free space  : {} bytes", info.fsmblks)?;
+        writeln!(out, "Allocated space     : {} bytes", info.uordblks)?;
+        writeln!(out, "Free space          : {} bytes", info.fordblks)?;
+        writeln!(out, "Releasable space    : {} bytes", info.keepcost)?;
+        writeln!(out)
+    });
+}
+
+/// Returns the XML report of `malloc_info`, with per arena and per bin details.
+pub fn malloc_info_xml() -> Option<String> {
+    unsafe {
+        let mut buf: *mut libc::c_char = std::ptr::null_mut();
+        let mut len: libc::size_t = 0;
+        let stream = libc::open_memstream(&mut buf, &mut len);
+        if stream.is_null() {
+            return None;
+        }
+        let status = libc::malloc_info(0, stream);
+        libc::fclose(stream);
+        if buf.is_null() {
+            return None;
+        }
+        let xml = String::from_utf8_lossy(std::slice::from_raw_parts(buf as *const u8, len)).into_owned();
+        libc::free(buf as *mut libc::c_void);
+        (status == 0).then_some(xml)
+    }
+}
+
+/// The header glibc stores right before the memory returned by `malloc`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkHeader {
+    /// Address of the chunk, which is the address of the `prev_size` field.
+    pub addr: usize,
+    /// Size of the previous chunk, only meaningful if it is free.
+    pub prev_size: usize,
+    /// Raw `size` field including the flag bits.
+    pub raw_size: usize,
+    /// Usable size of the allocation as reported by `malloc_usable_size`.
+    pub usable_size: usize,
+}
+
+impl ChunkHeader {
+    /// Size of the chunk in bytes, header included, without the flag bits.
+    pub fn size(&self) -> usize {
+        self.raw_size & !SIZE_BITS
+    }
+
+    /// The previous chunk is in use.
+    pub fn prev_inuse(&self) -> bool {
+        self.raw_size & PREV_INUSE != 0
+    }
+
+    /// The chunk was allocated with `mmap`.
+    pub fn is_mmapped(&self) -> bool {
+        self.raw_size & IS_MMAPPED != 0
+    }
+
+    /// The chunk belongs to a thread arena instead of the main arena.
+    pub fn non_main_arena(&self) -> bool {
+        self.raw_size & NON_MAIN_ARENA != 0
+    }
+
+    /// Address of the user memory, the pointer returned by `malloc`.
+    pub fn mem(&self) -> usize {
+        self.addr + 2 * size_of::<usize>()
+    }
+
+    /// Address of the next chunk in the same heap, `None` for mmapped chunks.
+    pub fn next_chunk(&self) -> Option<usize> {
+        (!self.is_mmapped()).then(|| self.addr + self.size())
+    }
+}
+
+/// Reads the chunk header of an allocation.
+///
+/// # Argument
+///
+/// * `ptr` - A pointer returned by glibc `malloc` (Rust's `System` allocator, as long as the alignment is at most
+///   16 bytes).
+///
+/// # Safety
+///
+/// `ptr` must be a live allocation of glibc malloc.
+pub unsafe fn chunk_header(ptr: *const u8) -> ChunkHeader {
+    let fields = (ptr as *const usize).sub(2);
+    ChunkHeader {
+        addr: fields.addr(),
+        prev_size: fields.read(),
+        raw_size: fields.add(1).read(),
+        usable_size: libc::malloc_usable_size(ptr as *mut libc::c_void),
+    }
+}
+
+/// Prints the decoded chunk header of an allocation, followed by a dump of the header bytes.
+///
+/// # Argument
+///
+/// * `ptr` - A pointer returned by glibc `malloc`.
+///
+/// # Safety
+///
+/// `ptr` must be a live allocation of glibc malloc.
+pub unsafe fn view_chunk(ptr: *const u8) {
+    let chunk = chunk_header(ptr);
+    let header = ptr.sub(2 * size_of::<usize>());
+    crate::render::emit(|out| {
+        writeln!(out, "Chunk         : {}", crate::_Addr(chunk.addr))?;
+        writeln!(out, "Mem           : {}", crate::_Addr(chunk.mem()))?;
+        writeln!(out, "prev_size     : {} bytes", chunk.prev_size)?;
+        writeln!(out, "size          : {} bytes (raw {:#x})", chunk.size(), chunk.raw_size)?;
+        writeln!(out, "PREV_INUSE    : {}", chunk.prev_inuse())?;
+        writeln!(out, "IS_MMAPPED    : {}", chunk.is_mmapped())?;
+        writeln!(out, "NON_MAIN_ARENA: {}", chunk.non_main_arena())?;
+        writeln!(out, "Usable size   : {} bytes", chunk.usable_size)?;
+        match chunk.next_chunk() {
+            Some(next) => writeln!(out, "Next chunk    : {}", crate::_Addr(next))?,
+            None => writeln!(out, "Next chunk    : none (mmapped)")?,
+        }
+        if crate::config().miri_friendly {
+            return crate::render::write_miri_note(out);
+        }
+        let bytes = std::slice::from_raw_parts(header, 2 * size_of::<usize>());
+        Table { label: "chunk header", ..Table::new(MEMORY_CONTENT, header.addr(), bytes) }.write(out)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunk_of_malloc() {
+        unsafe {
+            let ptr = libc::malloc(100) as *const u8;
+            let chunk = chunk_header(ptr);
+            assert_eq!(chunk.mem(), ptr.addr());
+            assert!(chunk.size() >= 100 + size_of::<usize>());
+            assert!(chunk.usable_size >= 100);
+            assert!(!chunk.is_mmapped());
+            view_chunk(ptr);
+            libc::free(ptr as *mut libc::c_void);
+        }
+        print_mallinfo();
+        assert!(malloc_info_xml().unwrap().contains("<malloc"));
+    }
+}
@@ -0,0 +1,76 @@
+//! The decoded UTF-8 characters of the memory content tables.
+//!
+//! The UTF-8 column shows the text starting at every byte, and the ASCII column the bytes one by one: neither tells
+//! which bytes make a character. Enabled with [`Config::glyphs`](crate::Config::glyphs), a `Glyph` column shows every
+//! character on the row of its first byte, with a bracket spanning the rows of its other bytes, so non-ASCII text
+//! reads in the dump.
+
+use core::fmt;
+
+/// Width of the column of the characters: `| ┌ é   `.
+pub(crate) const GLYPH_WIDTH: usize = 8;
+
+/// Shown instead of the characters which are invisible or would break the row: controls, format characters, line and
+/// paragraph separators.
+const INVISIBLE: char = '⍰';
+
+/// Shown for the bytes of no valid UTF-8 sequence.
+const INVALID: char = '\u{fffd}';
+
+/// Writes the title of the column.
+pub(crate) fn write_title<W: fmt::Write + ?Sized>(out: &mut W) -> fmt::Result {
+    out.write_str(" | Glyph")
+}
+
+/// Writes the cell of the byte at `index`: the character starting there, `┌` marking it if it takes several bytes,
+/// then `│` and `└` on the rows of its other bytes.
+pub(crate) fn write_column<W: fmt::Write + ?Sized>(out: &mut W, bytes: &[u8], index: usize) -> fmt::Result {
+    let (marker, glyph) = match crate::render::utf8_mark(bytes, index) {
+        None => (' ', Some(printable(bytes[index] as char))),
+        Some("BAD") => (' ', Some(INVALID)),
+        Some(mark) if mark.starts_with('1') => {
+            let len = (mark.as_bytes()[2] - b'0') as usize;
+            let c = core::str::from_utf8(&bytes[index..index + len]).ok().and_then(|text| text.chars().next());
+            ('┌', c.map(printable))
+        }
+        Some(mark) if mark.as_bytes()[0] == mark.as_bytes()[2] => ('└', None),
+        Some(_) => ('│', None),
+    };
+    match glyph {
+        Some(glyph) if is_combining(glyph) => write!(out, "| {} ◌{}   ", marker, glyph),
+        Some(glyph) => write!(out, "| {} {:<pad$} ", marker, glyph, pad = 4 - width(glyph)),
+        None => write!(out, "| {}     ", marker),
+    }
+}
+
+/// Returns the character shown for `c`: itself, the picture of an ASCII control, or [`INVISIBLE`].
+fn printable(c: char) -> char {
+    match c {
+        '\0'..='\x1f' => char::from_u32(0x2400 + c as u32).unwrap_or(INVISIBLE),
+        '\x7f' => '␡',
+        '\u{80}'..='\u{9f}' | '\u{ad}' | '\u{200b}'..='\u{200f}' | '\u{2028}'..='\u{202e}' | '\u{2060}'..='\u{206f}' | '\u{feff}' => INVISIBLE,
+        c => c,
+    }
+}
+
+/// Returns whether `c` is a combining mark, drawn on a dotted circle so it doesn't combine with the border.
+fn is_combining(c: char) -> bool {
+    matches!(c, '\u{300}'..='\u{36f}' | '\u{1ab0}'..='\u{1aff}' | '\u{1dc0}'..='\u{1dff}' | '\u{20d0}'..='\u{20ff}' | '\u{fe20}'..='\u{fe2f}')
+}
+
+/// Returns the number of terminal columns of `c`: 2 for the wide characters of the CJK scripts and the emoji, else 1.
+fn width(c: char) -> usize {
+    match c {
+        '\u{1100}'..='\u{115f}'
+        | '\u{2e80}'..='\u{a4cf}'
+        | '\u{ac00}'..='\u{d7a3}'
+        | '\u{f900}'..='\u{faff}'
+        | '\u{fe30}'..='\u{fe4f}'
+        | '\u{ff00}'..='\u{ff60}'
+        | '\u{ffe0}'..='\u{ffe6}'
+        | '\u{1f300}'..='\u{1f64f}'
+        | '\u{1f900}'..='\u{1f9ff}'
+        | '\u{20000}'..='\u{3fffd}' => 2,
+        _ => 1,
+    }
+}
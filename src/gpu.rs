@@ -0,0 +1,108 @@
+//! Read back and dump of GPU buffers with wgpu.
+//!
+//! A GPU buffer can't be read from the CPU directly: its content is copied into a mappable staging buffer, which is
+//! mapped once the copy is done. The dump uses the element tables of [`write_elements`](crate::write_elements), so GPU
+//! buffers are inspected like CPU slices.
+
+use std::fmt::{self, Write};
+use std::sync::mpsc;
+
+use crate::elements::{write_elements, ElementType};
+
+/// Error of [`read_gpu_buffer`].
+#[derive(Debug)]
+pub enum GpuReadError {
+    /// The buffer wasn't created with `BufferUsages::COPY_SRC`, so it can't be copied to a staging buffer.
+    NotCopySrc,
+    /// Waiting for the copy failed.
+    Poll(wgpu::PollError),
+    /// Mapping the staging buffer failed.
+    Map(wgpu::BufferAsyncError),
+}
+
+impl fmt::Display for GpuReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GpuReadError::NotCopySrc => write!(f, "the buffer lacks the COPY_SRC usage"),
+            GpuReadError::Poll(error) => write!(f, "waiting for the buffer copy failed: {}", error),
+            GpuReadError::Map(error) => write!(f, "mapping the staging buffer failed: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for GpuReadError {}
+
+/// Reads back the content of a GPU buffer, blocking until it is available.
+///
+/// The buffer must have the `COPY_SRC` usage. Copies are made of 4 bytes words, the last `size % 4` bytes of a buffer
+/// whose size isn't a multiple of 4 are left out.
+///
+/// # Arguments
+///
+/// * `device` - The device owning the buffer.
+/// * `queue` - The queue of the device, used to submit the copy.
+/// * `buffer` - The buffer to read.
+pub fn read_gpu_buffer(device: &wgpu::Device, queue: &wgpu::Queue, buffer: &wgpu::Buffer) -> Result<Vec<u8>, GpuReadError> {
+    if !buffer.usage().contains(wgpu::BufferUsages::COPY_SRC) {
+        return Err(GpuReadError::NotCopySrc);
+    }
+    let size = buffer.size() / wgpu::COPY_BUFFER_ALIGNMENT * wgpu::COPY_BUFFER_ALIGNMENT;
+    if size == 0 {
+        return Ok(Vec::new());
+    }
+
+    let staging = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("mem_viewer staging buffer"),
+        size,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("mem_viewer readback"),
+    });
+    encoder.copy_buffer_to_buffer(buffer, 0, &staging, 0, size);
+    queue.submit([encoder.finish()]);
+
+    let (sender, receiver) = mpsc::channel();
+    staging.map_async(wgpu::MapMode::Read, .., move |result| {
+        let _ = sender.send(result);
+    });
+    device.poll(wgpu::PollType::Wait).map_err(GpuReadError::Poll)?;
+    // The callback has run once the device is idle
+    receiver.recv().unwrap_or(Err(wgpu::BufferAsyncError)).map_err(GpuReadError::Map)?;
+
+    let bytes = staging.get_mapped_range(..).to_vec();
+    staging.unmap();
+    Ok(bytes)
+}
+
+/// Reads back a GPU buffer and displays its elements, one per row with its value.
+///
+/// GPU buffers have no CPU address, the addresses displayed are offsets into the buffer.
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `device` - The device owning the buffer.
+/// * `queue` - The queue of the device, used to submit the copy.
+/// * `buffer` - The buffer to display, with the `COPY_SRC` usage.
+/// * `element` - The type of the elements.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use mem_viewer::*;
+///
+/// view_gpu_buffer("my_vertex_buffer", &device, &queue, &my_vertex_buffer, ElementType::F32)?;
+/// ```
+pub fn view_gpu_buffer(name: &str, device: &wgpu::Device, queue: &wgpu::Queue, buffer: &wgpu::Buffer, element: ElementType) -> Result<(), GpuReadError> {
+    let bytes = read_gpu_buffer(device, queue, buffer)?;
+    crate::render::emit(|out| {
+        writeln!(out, "Name: {}", name)?;
+        writeln!(out, "Type: wgpu::Buffer of {}", element)?;
+        writeln!(out, "Usag: {:?}", buffer.usage())?;
+        writeln!(out, "Size: {} bytes", buffer.size())?;
+        write_elements(out, 0, &bytes, element)
+    });
+    Ok(())
+}
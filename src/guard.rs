@@ -0,0 +1,159 @@
+//! Stack protector canaries and guard magic values found in dumped memory.
+//!
+//! [`MemDump::find_guards`] flags the words of a dump holding the stack protector canary of the current thread or a
+//! well-known guard value, e.g. the `fdfdfdfd` guard bytes of the MSVC debug heap or `deadbeef`: a canary in a dump
+//! of a stack frame marks the end of its locals, and a guard value overwritten by something else is the footprint of
+//! an overflow.
+
+use core::fmt;
+
+use alloc::vec::Vec;
+
+use crate::dump::MemDump;
+
+/// Well-known 32-bit guard and poison values, matched in the byte order of the machine.
+const MAGIC_VALUES: [(u32, &str); 8] = [
+    (0xdead_beef, "DEADBEEF poison"),
+    (0xbaad_f00d, "HeapAlloc uninitialized"),
+    (0xfeee_feee, "HeapFree freed"),
+    (0xabab_abab, "HeapAlloc guard after block"),
+    (0xfdfd_fdfd, "MSVC no man's land guard"),
+    (0xcafe_babe, "CAFEBABE magic"),
+    (0xdead_c0de, "DEADC0DE poison"),
+    (0x57ac_6e9d, "Linux STACK_END_MAGIC"),
+];
+
+/// A word of a dump holding the stack protector canary or a guard value, see [`MemDump::find_guards`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GuardHit {
+    /// Offset of the first byte of the word from the start of the dump.
+    pub offset: usize,
+    /// Size of the word in bytes: the size of a pointer for the canary, 4 for the guard values.
+    pub size: usize,
+    /// The value of the word, read in the byte order of the machine.
+    pub value: u64,
+    /// What the value is, e.g. `stack protector canary`.
+    pub name: &'static str,
+}
+
+/// Returns the stack protector canary of the current thread, the value that functions compiled with
+/// `-fstack-protector` store below their return address and check before returning.
+///
+/// The canary is read from the thread control block, where glibc and musl keep it on x86 and x86-64 Linux (`fs:0x28`
+/// and `gs:0x14`). Elsewhere, and under Miri, `None` is returned. The canary of glibc starts with a zero byte, which
+/// stops string overflows from copying it.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// if let Some(canary) = stack_canary() {
+///     println!("Canary: {:016x}", canary);
+/// }
+/// ```
+pub fn stack_canary() -> Option<u64> {
+    #[cfg(all(target_os = "linux", target_arch = "x86_64", not(miri)))]
+    {
+        let canary: u64;
+        // SAFETY: fs points to the thread control block of the current thread, which holds the canary at 0x28
+        unsafe { core::arch::asm!("mov {}, qword ptr fs:[0x28]", out(reg) canary, options(nostack, readonly, preserves_flags)) };
+        Some(canary)
+    }
+    #[cfg(all(target_os = "linux", target_arch = "x86", not(miri)))]
+    {
+        let canary: u32;
+        // SAFETY: gs points to the thread control block of the current thread, which holds the canary at 0x14
+        unsafe { core::arch::asm!("mov {}, dword ptr gs:[0x14]", out(reg) canary, options(nostack, readonly, preserves_flags)) };
+        Some(canary as u64)
+    }
+    #[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "x86"), not(miri))))]
+    None
+}
+
+/// Returns the guard values and occurrences of `canary` in `bytes`, at the offsets aligned to their size.
+pub(crate) fn find_guards(bytes: &[u8], canary: Option<u64>) -> Vec<GuardHit> {
+    const WORD: usize = core::mem::size_of::<usize>();
+    let mut hits = Vec::new();
+    for offset in (0..bytes.len()).step_by(4) {
+        // A zero canary would match every zeroed word
+        if let Some(canary) = canary.filter(|canary| *canary != 0) {
+            if offset.is_multiple_of(WORD) && offset + WORD <= bytes.len() {
+                let mut word = [0; 8];
+                word[..WORD].copy_from_slice(&bytes[offset..offset + WORD]);
+                let value = if WORD == 8 { u64::from_ne_bytes(word) } else { u32::from_ne_bytes(word[..4].try_into().unwrap()) as u64 };
+                if value == canary {
+                    hits.push(GuardHit { offset, size: WORD, value, name: "stack protector canary" });
+                    continue;
+                }
+            }
+        }
+        let Some(word) = bytes.get(offset..offset + 4) else {
+            break;
+        };
+        let value = u32::from_ne_bytes(word.try_into().unwrap());
+        if let Some((_, name)) = MAGIC_VALUES.iter().find(|(magic, _)| *magic == value) {
+            hits.push(GuardHit { offset, size: 4, value: value as u64, name });
+        }
+    }
+    hits
+}
+
+impl MemDump {
+    /// Returns the words of the dump holding the [stack protector canary](stack_canary) of the current thread or a
+    /// well-known guard value (`deadbeef`, `baadf00d`, `feeefeee`, `abababab`, `fdfdfdfd`, `cafebabe`, `deadc0de`,
+    /// `57ac6e9d`), in the byte order of the machine.
+    ///
+    /// The canary is searched at the offsets aligned to the size of a pointer, the guard values at the offsets aligned
+    /// to 4 bytes, as compilers and allocators store them aligned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let mut my_block = [0u8; 16];
+    /// my_block[12..].copy_from_slice(&0xfdfd_fdfdu32.to_ne_bytes());
+    /// let dump = MemDump::of("my_block", &my_block);
+    /// let hits = dump.find_guards();
+    /// assert_eq!((hits[0].offset, hits[0].name), (12, "MSVC no man's land guard"));
+    /// ```
+    pub fn find_guards(&self) -> Vec<GuardHit> {
+        find_guards(&self.bytes, stack_canary())
+    }
+
+    /// Writes the canary of the current thread and the [guard values](MemDump::find_guards) found in the dump, a row
+    /// per word.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - Where to write the guard values.
+    /// * `hits` - The guard values found in the dump.
+    ///
+    /// # Output
+    ///
+    /// ```none
+    /// Name: my_frame
+    /// Cnry: 2f8a61c4d90b7e00 (stack protector canary of this thread)
+    /// Hits: 2 guard values
+    ///  00007ffd5e1c2a18 | +0x18 | 2f8a61c4d90b7e00 | stack protector canary
+    ///  00007ffd5e1c2a20 | +0x20 | deadbeef         | DEADBEEF poison
+    /// ```
+    pub fn write_guards<W: fmt::Write + ?Sized>(&self, out: &mut W, hits: &[GuardHit]) -> fmt::Result {
+        writeln!(out, "Name: {}", self.name)?;
+        match stack_canary() {
+            Some(canary) => writeln!(out, "Cnry: {:0width$x} (stack protector canary of this thread)", canary, width = core::mem::size_of::<usize>() * 2)?,
+            None => writeln!(out, "Cnry: unknown on this platform")?,
+        }
+        if hits.is_empty() {
+            writeln!(out, "Hits: none")?;
+            return writeln!(out);
+        }
+        writeln!(out, "Hits: {} guard value{}", hits.len(), if hits.len() == 1 { "" } else { "s" })?;
+        for hit in hits {
+            write!(out, " {} | +{:<#4x} | {:0width$x}", crate::_Addr(self.addr + hit.offset), hit.offset, hit.value, width = hit.size * 2)?;
+            writeln!(out, "{:pad$} | {}", "", hit.name, pad = 16 - hit.size * 2)?;
+        }
+        writeln!(out)
+    }
+}
@@ -0,0 +1,210 @@
+//! Windows heap walking with `GetProcessHeaps`/`HeapWalk`.
+//!
+//! This enumerates the heap blocks of every heap in the current process and lets you dump the ones you are
+//! interested in. It does not need any allocator to be installed, so it also works inside a `cdylib` plugin
+//! loaded by a host you don't control.
+//!
+//! Enabled with the `heapwalk` feature, only available on Windows.
+
+use windows_sys::Win32::Foundation::HANDLE;
+use windows_sys::Win32::System::Memory::{GetProcessHeaps, HeapLock, HeapUnlock, HeapWalk, PROCESS_HEAP_ENTRY};
+
+use std::fmt::Write;
+
+use crate::render::{separator, Table, ADDR_TITLE, MEMORY_CONTENT};
+
+// Flags of `PROCESS_HEAP_ENTRY::wFlags`, see `winbase.h`.
+const PROCESS_HEAP_REGION: u16 = 0x0001;
+const PROCESS_HEAP_UNCOMMITTED_RANGE: u16 = 0x0002;
+const PROCESS_HEAP_ENTRY_BUSY: u16 = 0x0004;
+
+/// Name of the heap handle column, as wide as the addresses.
+#[cfg(target_pointer_width = "64")]
+const HEAP_TITLE: &str = "      Heap        ";
+#[cfg(target_pointer_width = "32")]
+const HEAP_TITLE: &str = "   Heap   ";
+
+/// A single entry reported by `HeapWalk`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeapBlock {
+    /// Handle of the heap owning this block.
+    pub heap: usize,
+    /// Address of the data portion of the block.
+    pub addr: usize,
+    /// Size of the data portion in bytes.
+    pub size: usize,
+    /// Bookkeeping overhead of the heap manager in bytes.
+    pub overhead: u8,
+    /// Index of the region containing the block.
+    pub region_index: u8,
+    /// Raw `wFlags` of the entry.
+    pub flags: u16,
+}
+
+impl HeapBlock {
+    /// Returns `true` if the block is allocated (not free).
+    pub fn is_busy(&self) -> bool {
+        self.flags & PROCESS_HEAP_ENTRY_BUSY != 0
+    }
+
+    /// Returns `true` if the entry describes a whole heap region instead of a block.
+    pub fn is_region(&self) -> bool {
+        self.flags & PROCESS_HEAP_REGION != 0
+    }
+
+    /// Returns `true` if the entry describes an uncommitted range.
+    pub fn is_uncommitted(&self) -> bool {
+        self.flags & PROCESS_HEAP_UNCOMMITTED_RANGE != 0
+    }
+
+    /// Returns `true` if `ptr` lies within the data portion of the block.
+    pub fn contains(&self, ptr: *const u8) -> bool {
+        let ptr = ptr.addr();
+        ptr >= self.addr && ptr < self.addr + self.size
+    }
+
+    /// Short human readable kind of the entry.
+    pub fn kind(&self) -> &'static str {
+        if self.is_region() {
+            "Region"
+        } else if self.is_uncommitted() {
+            "Uncommitted"
+        } else if self.is_busy() {
+            "Busy"
+        } else {
+            "Free"
+        }
+    }
+}
+
+/// Returns the handles of all heaps of the current process.
+pub fn process_heaps() -> Vec<usize> {
+    let mut heaps: Vec<HANDLE> = Vec::new();
+    loop {
+        // The number of heaps may grow between the calls, so retry until it fits.
+        let count = unsafe { GetProcessHeaps(heaps.len() as u32, heaps.as_mut_ptr()) } as usize;
+        if count <= heaps.len() {
+            heaps.truncate(count);
+            return heaps.into_iter().map(|heap| heap.expose_provenance()).collect();
+        }
+        heaps = vec![std::ptr::null_mut(); count];
+    }
+}
+
+/// Walks `heap` while holding its lock, calling `f` for every entry until it returns `false`.
+///
+/// `f` must not allocate from the walked heap.
+fn walk(heap: HANDLE, mut f: impl FnMut(&PROCESS_HEAP_ENTRY) -> bool) {
+    unsafe {
+        if HeapLock(heap) == 0 {
+            return;
+        }
+        let mut entry: PROCESS_HEAP_ENTRY = std::mem::zeroed();
+        while HeapWalk(heap, &mut entry) != 0 {
+            if !f(&entry) {
+                break;
+            }
+        }
+        HeapUnlock(heap);
+    }
+}
+
+/// Returns every entry of the heap `heap`.
+///
+/// # Argument
+///
+/// * `heap` - A heap handle as returned by [`process_heaps`].
+pub fn heap_blocks(heap: usize) -> Vec<HeapBlock> {
+    let handle: HANDLE = std::ptr::with_exposed_provenance_mut(heap);
+
+    // Count first, the result vector must not be allocated while walking (it may live in the same heap).
+    let mut count = 0;
+    walk(handle, |_| {
+        count += 1;
+        true
+    });
+
+    let mut blocks: Vec<HeapBlock> = Vec::with_capacity(count + count / 4 + 16);
+    walk(handle, |entry| {
+        if blocks.len() == blocks.capacity() {
+            return false;
+        }
+        blocks.push(HeapBlock {
+            heap,
+            addr: entry.lpData.expose_provenance(),
+            size: entry.cbData as usize,
+            overhead: entry.cbOverhead,
+            region_index: entry.iRegionIndex,
+            flags: entry.wFlags,
+        });
+        true
+    });
+    blocks
+}
+
+/// Returns every entry of every heap in the current process.
+pub fn all_heap_blocks() -> Vec<HeapBlock> {
+    process_heaps().into_iter().flat_map(heap_blocks).collect()
+}
+
+/// Finds the busy heap block containing `ptr`, if any.
+///
+/// # Argument
+///
+/// * `ptr` - Any address, typically obtained from a `Box`, `Vec` or a C allocation.
+pub fn find_block(ptr: *const u8) -> Option<HeapBlock> {
+    all_heap_blocks().into_iter().find(|block| block.is_busy() && block.contains(ptr))
+}
+
+/// Prints a table of the entries of every heap in the current process.
+pub fn print_heap_blocks() {
+    let blocks = all_heap_blocks();
+    crate::render::emit(|out| {
+        writeln!(out, "{}|{}|    Size    | Ovh | Rgn |    Kind", HEAP_TITLE, ADDR_TITLE)?;
+        writeln!(out, "{}", separator("---------------------------------Heap Blocks----------------------------------", 2))?;
+        for block in &blocks {
+            writeln!(out, " {} | {} | {:>10} | {:>3} | {:>3} | {}", crate::_Addr(block.heap), crate::_Addr(block.addr), block.size, block.overhead, block.region_index, block.kind())?;
+        }
+        writeln!(out)
+    });
+}
+
+/// Dumps the data portion of a heap block.
+///
+/// # Argument
+///
+/// * `block` - A block as returned by [`heap_blocks`] or [`find_block`].
+///
+/// # Safety
+///
+/// The block must still be busy, it may have been freed since it was enumerated.
+pub unsafe fn view_heap_block(block: &HeapBlock) {
+    crate::render::emit(|out| {
+        writeln!(out, "Heap: {}", crate::_Addr(block.heap))?;
+        writeln!(out, "Addr: {}", crate::_Addr(block.addr))?;
+        writeln!(out, "Size: {} bytes", block.size)?;
+        writeln!(out, "Kind: {}", block.kind())?;
+        if crate::config().miri_friendly {
+            return crate::render::write_miri_note(out);
+        }
+        let bytes = std::slice::from_raw_parts(std::ptr::with_exposed_provenance::<u8>(block.addr), block.size);
+        Table { label: "heap block", ..Table::new(MEMORY_CONTENT, block.addr, bytes) }.write(out)
+    });
+}
+
+/// Dumps every busy heap block for which `filter` returns `true`.
+///
+/// # Argument
+///
+/// * `filter` - Selects the blocks to dump.
+///
+/// # Safety
+///
+/// The selected blocks must not be freed by another thread while they are dumped.
+pub unsafe fn view_heap_blocks(mut filter: impl FnMut(&HeapBlock) -> bool) {
+    for block in all_heap_blocks() {
+        if block.is_busy() && filter(&block) {
+            view_heap_block(&block);
+        }
+    }
+}
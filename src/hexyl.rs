@@ -0,0 +1,115 @@
+//! The memory content tables in the layout of [hexyl](https://github.com/sharkdp/hexyl).
+//!
+//! Selected with [`Preset::Hexyl`](crate::Preset::Hexyl): 16 bytes per row between box-drawing borders, the address
+//! column, two panels of 8 hex bytes, the character panel, and runs of identical rows squeezed into a `*` line. The
+//! bytes are colored by category like hexyl does: null, printable ASCII, ASCII whitespace, other ASCII and non-ASCII.
+
+use core::fmt;
+
+use crate::render::{Table, ADDR_WIDTH, HEX};
+
+/// Number of bytes per row.
+const ROW_LEN: usize = 16;
+
+const NULL_COLOR: &str = "\x1b[90m";
+const PRINTABLE_COLOR: &str = "\x1b[36m";
+const WHITESPACE_COLOR: &str = "\x1b[32m";
+const OTHER_COLOR: &str = "\x1b[32m";
+const NON_ASCII_COLOR: &str = "\x1b[33m";
+const OFFSET_COLOR: &str = "\x1b[90m";
+const RESET: &str = "\x1b[0m";
+
+/// Returns the character of the byte in the character panel and its color.
+fn category(byte: u8) -> (char, &'static str) {
+    match byte {
+        0 => ('⋄', NULL_COLOR),
+        b' ' => (' ', WHITESPACE_COLOR),
+        b'\t' | b'\n' | 0x0b | 0x0c | b'\r' => ('_', WHITESPACE_COLOR),
+        _ if byte.is_ascii_graphic() => (byte as char, PRINTABLE_COLOR),
+        _ if byte.is_ascii() => ('•', OTHER_COLOR),
+        _ => ('×', NON_ASCII_COLOR),
+    }
+}
+
+/// Writes a border line: `left`, then the horizontal lines of the columns separated by `middle`, then `right`.
+fn write_border<W: fmt::Write + ?Sized>(out: &mut W, left: char, middle: char, right: char) -> fmt::Result {
+    out.write_char(left)?;
+    for (index, width) in [ADDR_WIDTH, 25, 25, 8, 8].into_iter().enumerate() {
+        if index > 0 {
+            out.write_char(middle)?;
+        }
+        for _ in 0..width {
+            out.write_char('─')?;
+        }
+    }
+    out.write_char(right)?;
+    writeln!(out)
+}
+
+/// Writes the row of up to 16 bytes starting at `addr`.
+fn write_row<W: fmt::Write + ?Sized>(out: &mut W, addr: usize, row: &[u8], color: bool) -> fmt::Result {
+    if color {
+        write!(out, "│{}{}{}│", OFFSET_COLOR, crate::_Addr(addr), RESET)?;
+    } else {
+        write!(out, "│{}│", crate::_Addr(addr))?;
+    }
+    for index in 0..ROW_LEN {
+        if index == 8 {
+            out.write_str(" ┊")?;
+        }
+        match row.get(index) {
+            Some(byte) if color => write!(out, " {}{}{}", category(*byte).1, HEX[*byte as usize], RESET)?,
+            Some(byte) => write!(out, " {}", HEX[*byte as usize])?,
+            None => out.write_str("   ")?,
+        }
+    }
+    out.write_str(" │")?;
+    for index in 0..ROW_LEN {
+        if index == 8 {
+            out.write_char('┊')?;
+        }
+        match row.get(index) {
+            Some(byte) if color => {
+                let (character, color) = category(*byte);
+                write!(out, "{}{}{}", color, character, RESET)?;
+            }
+            Some(byte) => out.write_char(category(*byte).0)?,
+            None => out.write_char(' ')?,
+        }
+    }
+    writeln!(out, "│")
+}
+
+/// Writes the rows of the bytes in `range`, squeezing the rows repeating the previous one into a `*` line.
+fn write_rows<W: fmt::Write + ?Sized>(out: &mut W, table: &Table<'_>, range: core::ops::Range<usize>) -> fmt::Result {
+    let mut previous: Option<&[u8]> = None;
+    let mut squeezed = false;
+    for start in range.clone().step_by(ROW_LEN) {
+        let row = &table.bytes[start..(start + ROW_LEN).min(range.end)];
+        if previous == Some(row) && row.len() == ROW_LEN {
+            if !squeezed {
+                writeln!(out, "│*{:width$}│{:25}┊{:25}│{:8}┊{:8}│", "", "", "", "", "", width = ADDR_WIDTH - 1)?;
+                squeezed = true;
+            }
+            continue;
+        }
+        write_row(out, table.addr + start, row, table.color)?;
+        previous = Some(row);
+        squeezed = false;
+    }
+    Ok(())
+}
+
+/// Formats a table in the layout of hexyl, the bytes omitted by `max_bytes` being replaced by a line.
+pub(crate) fn write_table<W: fmt::Write + ?Sized>(out: &mut W, table: &Table<'_>) -> fmt::Result {
+    write_border(out, '┌', '┬', '┐')?;
+    match crate::_omitted_range(table.bytes.len(), table.max_bytes) {
+        Some(omitted) => {
+            write_rows(out, table, 0..omitted.start)?;
+            writeln!(out, "│ … {} bytes omitted …", crate::Thousands(omitted.len()))?;
+            write_rows(out, table, omitted.end..table.bytes.len())?;
+        }
+        None => write_rows(out, table, 0..table.bytes.len())?,
+    }
+    write_border(out, '└', '┴', '┘')
+}
@@ -0,0 +1,73 @@
+//! Statistics and histogram of the byte values of a dump.
+//!
+//! Enabled with [`Config::statistics`](crate::Config::statistics), the counts of zero, printable ASCII, `0xff` and
+//! distinct bytes are written below the memory content tables. Enabled with
+//! [`Config::histogram`](crate::Config::histogram), the most frequent byte values are listed with a bar of their
+//! share. Both reveal fill patterns and uninitialized memory at a glance.
+
+use core::fmt;
+
+use crate::fill::fill_name;
+use crate::render::HEX;
+
+/// Length of the bar of a byte value making up the whole dump.
+const BAR_WIDTH: usize = 40;
+
+/// Returns the number of occurrences of every byte value in `bytes`.
+fn count_values(bytes: &[u8]) -> [usize; 256] {
+    let mut counts = [0usize; 256];
+    for byte in bytes {
+        counts[*byte as usize] += 1;
+    }
+    counts
+}
+
+/// Writes the `Stat` line of `bytes`, the footer of the memory content tables.
+pub(crate) fn write_statistics<W: fmt::Write + ?Sized>(out: &mut W, bytes: &[u8]) -> fmt::Result {
+    if bytes.is_empty() {
+        return Ok(());
+    }
+    let counts = count_values(bytes);
+    let printable: usize = counts[0x20..0x7f].iter().sum();
+    let distinct = counts.iter().filter(|count| **count > 0).count();
+    let share = |count: usize| count as f64 * 100.0 / bytes.len() as f64;
+    writeln!(
+        out,
+        "Stat: {} bytes, {} zero ({:.1}%), {} printable ASCII ({:.1}%), {} 0xff ({:.1}%), {} distinct values",
+        crate::Thousands(bytes.len()),
+        crate::Thousands(counts[0]),
+        share(counts[0]),
+        crate::Thousands(printable),
+        share(printable),
+        crate::Thousands(counts[0xff]),
+        share(counts[0xff]),
+        distinct
+    )
+}
+
+/// Writes the `top` most frequent byte values of `bytes`, the footer of the memory content tables.
+pub(crate) fn write_histogram<W: fmt::Write + ?Sized>(out: &mut W, top: usize, bytes: &[u8]) -> fmt::Result {
+    if top == 0 || bytes.is_empty() {
+        return Ok(());
+    }
+    let counts = count_values(bytes);
+    let mut values: [u8; 256] = core::array::from_fn(|value| value as u8);
+    // The most frequent values first, the lowest value first among equals
+    values.sort_by(|a, b| counts[*b as usize].cmp(&counts[*a as usize]).then(a.cmp(b)));
+    let distinct = counts.iter().filter(|count| **count > 0).count();
+    writeln!(out, "Hist: {} bytes, {} distinct values", crate::Thousands(bytes.len()), distinct)?;
+    let count_width = counts[values[0] as usize].ilog10() as usize + 1;
+    for value in values.iter().take(top.min(distinct)) {
+        let count = counts[*value as usize];
+        let percent = count as f64 * 100.0 / bytes.len() as f64;
+        write!(out, "  {} | {:>count_width$} | {:>5.1}% | ", HEX[*value as usize], count, percent)?;
+        for _ in 0..(count * BAR_WIDTH).div_ceil(bytes.len()) {
+            out.write_char('#')?;
+        }
+        if let Some(name) = fill_name(*value) {
+            write!(out, " {}", name)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,176 @@
+//! Dumps rendered as HTML tables.
+//!
+//! The tables are self-contained fragments (styles included) to paste into reports: 16 bytes per row, the hex cells
+//! carrying a tooltip with their offset, decimal and binary values, and optionally colored by the field of a
+//! [`StructLayout`] they belong to.
+
+use core::fmt::Write as _;
+
+use alloc::string::String;
+
+use crate::dump::MemDump;
+use crate::layout::StructLayout;
+use crate::render::{BIN, DEC, HEX};
+
+/// Number of bytes per row.
+const ROW_LEN: usize = 16;
+
+/// Background colors of the fields, used in turn in the order of the fields.
+const FIELD_COLORS: [&str; 8] = ["#cfe8ff", "#d7f5d0", "#ffe3b8", "#f3d1f4", "#fff6a8", "#c9f1ef", "#ffd0d0", "#e0dcff"];
+
+/// Background color of the padding bytes.
+const PADDING_COLOR: &str = "#e4e4e4";
+
+const STYLE: &str = "<style>\
+.mem-viewer{border-collapse:collapse;font-family:monospace}\
+.mem-viewer th,.mem-viewer td{padding:1px 4px;text-align:center}\
+.mem-viewer .addr{color:#777;text-align:right}\
+.mem-viewer .chars{text-align:left;white-space:pre}\
+.mem-viewer td.zero{color:#aaa}\
+</style>\n";
+
+impl MemDump {
+    /// Returns the dump as an HTML table, to share in reports.
+    ///
+    /// The fragment starts with its styles and a caption with the name, type, size and address of the dump. Every row
+    /// has the address, 16 hex bytes and their printable characters, and hovering a byte shows its offset, decimal and
+    /// binary values. The bytes beyond the `max_bytes` cap of the global configuration are replaced by a row telling
+    /// how many were omitted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let dump = MemDump::new("my_u16", "u16", 0x1000, vec![0x45, 0x00]);
+    /// assert!(dump.to_html().contains("<td title=\"offset 0x0, 069, 0b01000101\">45</td>"));
+    /// ```
+    pub fn to_html(&self) -> String {
+        self.html(None)
+    }
+
+    /// Same as [`MemDump::to_html`], the bytes being colored by the field of `layout` they belong to.
+    ///
+    /// A legend below the table lists the fields with their color, and the tooltips name the field of every byte.
+    /// Padding bytes are gray.
+    ///
+    /// # Argument
+    ///
+    /// * `layout` - The layout of the struct at the start of the dump.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// #[repr(C)]
+    /// struct MyStruct {
+    ///     a: u8,
+    ///     b: u32,
+    /// }
+    ///
+    /// let my_struct = MyStruct { a: 1, b: 2 };
+    /// let html = MemDump::of("my_struct", &my_struct).to_html_with_layout(&layout_of!(MyStruct { a, b }));
+    /// assert!(html.contains("b: u32"));
+    /// ```
+    pub fn to_html_with_layout(&self, layout: &StructLayout) -> String {
+        self.html(Some(layout))
+    }
+
+    fn html(&self, layout: Option<&StructLayout>) -> String {
+        let mut out = String::with_capacity(self.bytes.len() * 72 + 1024);
+        out.push_str(STYLE);
+        out.push_str("<table class=\"mem-viewer\">\n");
+        let _ = writeln!(out, "<caption><b>{}</b>: {}, {} bytes at {}</caption>", Escaped(&self.name), Escaped(self.type_name), crate::Thousands(self.bytes.len()), crate::_Addr(self.addr));
+        out.push_str("<tr><th>Address</th>");
+        for column in 0..ROW_LEN {
+            let _ = write!(out, "<th>{:x}</th>", column);
+        }
+        out.push_str("<th>ASCII</th></tr>\n");
+        match crate::_omitted_range(self.bytes.len(), crate::config().max_bytes) {
+            Some(omitted) => {
+                self.write_html_rows(&mut out, layout, 0..omitted.start);
+                let _ = writeln!(out, "<tr><td colspan=\"{}\">… {} bytes omitted …</td></tr>", ROW_LEN + 2, crate::Thousands(omitted.len()));
+                self.write_html_rows(&mut out, layout, omitted.end..self.bytes.len());
+            }
+            None => self.write_html_rows(&mut out, layout, 0..self.bytes.len()),
+        }
+        out.push_str("</table>\n");
+        if let Some(layout) = layout {
+            out.push_str("<p class=\"mem-viewer\">");
+            for (index, field) in layout.fields.iter().enumerate() {
+                let _ = write!(out, "<span style=\"background:{}\">{}: {}</span> ", FIELD_COLORS[index % FIELD_COLORS.len()], Escaped(field.name), Escaped(field.type_name));
+            }
+            let _ = writeln!(out, "<span style=\"background:{}\">(padding)</span></p>", PADDING_COLOR);
+        }
+        out
+    }
+
+    /// Writes the rows of the bytes in `range`, which starts at a row boundary unless it follows omitted bytes.
+    fn write_html_rows(&self, out: &mut String, layout: Option<&StructLayout>, range: core::ops::Range<usize>) {
+        let mut start = range.start;
+        while start < range.end {
+            let end = (start + ROW_LEN).min(range.end);
+            let _ = write!(out, "<tr><td class=\"addr\">{}</td>", crate::_Addr(self.addr + start));
+            for offset in start..end {
+                let cell = self.bytes[offset] as usize;
+                out.push_str("<td");
+                if cell == 0 {
+                    out.push_str(" class=\"zero\"");
+                }
+                let _ = write!(out, " title=\"offset {:#x}, {}, 0b{}", offset, DEC[cell], BIN[cell]);
+                if let Some(layout) = layout {
+                    let color = match layout.fields.iter().position(|field| offset >= field.offset && offset < field.offset + field.size) {
+                        Some(index) => {
+                            let field = &layout.fields[index];
+                            let _ = write!(out, ", {} +{}", Escaped(field.name), offset - field.offset);
+                            FIELD_COLORS[index % FIELD_COLORS.len()]
+                        }
+                        None if offset < layout.size => {
+                            out.push_str(", padding");
+                            PADDING_COLOR
+                        }
+                        None => "",
+                    };
+                    if !color.is_empty() {
+                        let _ = write!(out, "\" style=\"background:{}", color);
+                    }
+                }
+                let _ = write!(out, "\">{}</td>", HEX[cell]);
+            }
+            for _ in end..start + ROW_LEN {
+                out.push_str("<td></td>");
+            }
+            out.push_str("<td class=\"chars\">");
+            for byte in &self.bytes[start..end] {
+                match byte {
+                    b'<' => out.push_str("&lt;"),
+                    b'>' => out.push_str("&gt;"),
+                    b'&' => out.push_str("&amp;"),
+                    0x20..=0x7e => out.push(*byte as char),
+                    _ => out.push('.'),
+                }
+            }
+            out.push_str("</td></tr>\n");
+            start = end;
+        }
+    }
+}
+
+/// Displays a string with the characters of HTML markup escaped.
+struct Escaped<'a>(&'a str);
+
+impl core::fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '&' => f.write_str("&amp;")?,
+                '"' => f.write_str("&quot;")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
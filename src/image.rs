@@ -0,0 +1,474 @@
+//! Firmware and EEPROM images loaded as memory regions.
+//!
+//! Images are loaded as [`MemDump`]s placed at the address they are programmed at, so they can be printed, iterated by
+//! rows and compared with [`MemDiff`](crate::MemDiff) like regions captured from a live process.
+
+use core::fmt;
+use core::ops::Range;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::dump::MemDump;
+
+/// Value of the bytes of an image not covered by any Intel HEX or S-record, the value of erased flash.
+pub const ERASED_BYTE: u8 = 0xff;
+
+/// Maximal number of bytes of an Intel HEX or S-record image loaded in memory, 256 MiB: its data records and the gaps
+/// filled between them, so that a corrupted or hostile file can't allocate gigabytes.
+pub const MAX_IMAGE_SIZE: usize = 256 << 20;
+
+/// Maximal gap between the data records of a segment of [`MemDump::ihex_segments`] and [`MemDump::srec_segments`],
+/// 64 KiB: closer records are loaded in the same segment, the gap filled with [`ERASED_BYTE`], farther ones start a new
+/// segment.
+pub const MAX_FILLED_GAP: usize = 64 << 10;
+
+/// Number of data bytes per record written by [`MemDump::write_ihex`] and [`MemDump::write_srec`].
+const RECORD_DATA_LEN: usize = 16;
+
+/// Error of [`MemDump::from_ihex`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IhexError {
+    /// Line of the invalid record, from 1.
+    pub line: usize,
+    /// What is wrong with the record.
+    pub message: &'static str,
+}
+
+impl fmt::Display for IhexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid Intel HEX at line {}: {}", self.line, self.message)
+    }
+}
+
+impl core::error::Error for IhexError {}
+
+/// Error of [`MemDump::from_srec`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SrecError {
+    /// Line of the invalid record, from 1.
+    pub line: usize,
+    /// What is wrong with the record.
+    pub message: &'static str,
+}
+
+impl fmt::Display for SrecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid S-record at line {}: {}", self.line, self.message)
+    }
+}
+
+impl core::error::Error for SrecError {}
+
+impl MemDump {
+    /// Loads a raw binary image, e.g. a `.bin` firmware or an EEPROM read out by a programmer.
+    ///
+    /// For Intel HEX and S-record files, read the file to a string and use [`MemDump::from_ihex`] or
+    /// [`MemDump::from_srec`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the image, its file name is the name of the dump.
+    /// * `base` - The address the image is programmed at, e.g. `0x0800_0000` for the flash of an STM32.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use mem_viewer::*;
+    ///
+    /// let old = MemDump::from_image("firmware_v1.bin", 0x0800_0000)?;
+    /// let new = MemDump::from_image("firmware_v2.bin", 0x0800_0000)?;
+    /// MemDiff::new(&old, &new).with_page_size(2048).view();
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_image(path: impl AsRef<std::path::Path>, base: usize) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)?;
+        let name = path.file_name().map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().into_owned());
+        Ok(MemDump::new(name, "image", base, bytes))
+    }
+
+    /// Loads an Intel HEX image.
+    ///
+    /// The dump starts at the lowest address of the data records and ends after the highest one, the gaps between
+    /// records are filled with [`ERASED_BYTE`]. Extended segment (`02`) and linear (`04`) address records are applied,
+    /// start address records (`03`, `05`) are ignored, and the end of file record (`01`) ends the image. Fails if the
+    /// dump would take more than [`MAX_IMAGE_SIZE`] bytes with its gaps filled: load sparse images, e.g. the flash and
+    /// the option bytes of a microcontroller, with [`MemDump::ihex_segments`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the image.
+    /// * `text` - The content of the `.hex` file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let image = MemDump::from_ihex("eeprom.hex", ":0400100001020304E2\n:00000001FF\n").unwrap();
+    /// assert_eq!((image.addr, image.bytes.as_slice()), (0x10, &[1, 2, 3, 4][..]));
+    /// ```
+    pub fn from_ihex(name: impl Into<String>, text: &str) -> Result<Self, IhexError> {
+        let name = name.into();
+        let records = ihex_records(text)?;
+        let message = "the data records are too far apart to fill the gaps, load the image with MemDump::ihex_segments";
+        let mut segments = records.assemble(name.clone(), usize::MAX, message).map_err(|(line, message)| IhexError { line, message })?;
+        Ok(segments.pop().unwrap_or_else(|| MemDump::new(name, "image", 0, Vec::new())))
+    }
+
+    /// Loads a sparse Intel HEX image as a dump per segment, e.g. the flash and the option bytes of a microcontroller.
+    ///
+    /// The records are read like [`MemDump::from_ihex`] does, then grouped in segments ordered by address: the records
+    /// at most [`MAX_FILLED_GAP`] bytes apart are in the same segment, the gaps between them filled with
+    /// [`ERASED_BYTE`]. Fails if the segments take more than [`MAX_IMAGE_SIZE`] bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of every segment.
+    /// * `text` - The content of the `.hex` file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let hex = ":020000040800F2\n:0100000011EE\n:020000041FFFDC\n:01C00000221D\n:00000001FF\n";
+    /// let segments = MemDump::ihex_segments("stm32.hex", hex).unwrap();
+    /// assert_eq!(segments.iter().map(|segment| segment.addr).collect::<Vec<_>>(), [0x0800_0000, 0x1fff_c000]);
+    /// ```
+    pub fn ihex_segments(name: impl Into<String>, text: &str) -> Result<Vec<Self>, IhexError> {
+        let message = "the data records and the gaps filled between them take more than MAX_IMAGE_SIZE bytes";
+        ihex_records(text)?.assemble(name, MAX_FILLED_GAP, message).map_err(|(line, message)| IhexError { line, message })
+    }
+
+    /// Writes the dump as Intel HEX records, to be programmed by flashing tools or loaded with
+    /// [`MemDump::from_ihex`].
+    ///
+    /// Data records hold 16 bytes, extended linear address records (`04`) are written whenever the upper 16 bits of
+    /// the address change, and the end of file record (`01`) ends the image. Fails if the dump reaches beyond 4 GiB,
+    /// the address space of the format.
+    ///
+    /// # Argument
+    ///
+    /// * `out` - The writer receiving the records, e.g. a `String` saved as a `.hex` file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let image = MemDump::new("eeprom", "image", 0x10, vec![1, 2, 3, 4]);
+    /// let mut hex = String::new();
+    /// image.write_ihex(&mut hex).unwrap();
+    /// assert_eq!(hex, ":0400100001020304E2\n:00000001FF\n");
+    /// ```
+    pub fn write_ihex<W: fmt::Write + ?Sized>(&self, out: &mut W) -> fmt::Result {
+        if self.addr.checked_add(self.bytes.len()).is_none_or(|end| end as u64 > 1 << 32) {
+            return Err(fmt::Error);
+        }
+        let mut upper = 0;
+        for (index, data) in self.bytes.chunks(RECORD_DATA_LEN).enumerate() {
+            let addr = self.addr + index * RECORD_DATA_LEN;
+            // A record can't cross a 64 KiB boundary, its offset would wrap
+            let split = data.len().min(0x1_0000 - (addr & 0xffff));
+            for (addr, data) in [(addr, &data[..split]), (addr + split, &data[split..])] {
+                if data.is_empty() {
+                    continue;
+                }
+                if addr >> 16 != upper {
+                    upper = addr >> 16;
+                    write_record(out, ":", &[0x02, 0x00, 0x00, 0x04], &(upper as u16).to_be_bytes(), ihex_checksum)?;
+                }
+                let [high, low] = (addr as u16).to_be_bytes();
+                write_record(out, ":", &[data.len() as u8, high, low, 0x00], data, ihex_checksum)?;
+            }
+        }
+        out.write_str(":00000001FF\n")
+    }
+
+    /// Loads a Motorola S-record image (`.s19`, `.s28`, `.s37`, `.srec`).
+    ///
+    /// The dump starts at the lowest address of the data records (`S1`, `S2`, `S3`) and ends after the highest one, the
+    /// gaps between records are filled with [`ERASED_BYTE`]. The header (`S0`) and count (`S5`, `S6`) records are
+    /// ignored, and the termination records (`S7`, `S8`, `S9`) end the image. Fails if the dump would take more than
+    /// [`MAX_IMAGE_SIZE`] bytes with its gaps filled: load sparse images, e.g. the flash and the option bytes of a
+    /// microcontroller, with [`MemDump::srec_segments`].
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the image.
+    /// * `text` - The content of the S-record file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let image = MemDump::from_srec("eeprom.s19", "S107001001020304DE\nS9030000FC\n").unwrap();
+    /// assert_eq!((image.addr, image.bytes.as_slice()), (0x10, &[1, 2, 3, 4][..]));
+    /// ```
+    pub fn from_srec(name: impl Into<String>, text: &str) -> Result<Self, SrecError> {
+        let name = name.into();
+        let records = srec_records(text)?;
+        let message = "the data records are too far apart to fill the gaps, load the image with MemDump::srec_segments";
+        let mut segments = records.assemble(name.clone(), usize::MAX, message).map_err(|(line, message)| SrecError { line, message })?;
+        Ok(segments.pop().unwrap_or_else(|| MemDump::new(name, "image", 0, Vec::new())))
+    }
+
+    /// Loads a sparse S-record image as a dump per segment, e.g. the flash and the option bytes of a microcontroller.
+    ///
+    /// The records are read like [`MemDump::from_srec`] does, then grouped in segments ordered by address: the records
+    /// at most [`MAX_FILLED_GAP`] bytes apart are in the same segment, the gaps between them filled with
+    /// [`ERASED_BYTE`]. Fails if the segments take more than [`MAX_IMAGE_SIZE`] bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of every segment.
+    /// * `text` - The content of the S-record file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let segments = MemDump::srec_segments("stm32.s37", "S3060800000011E0\nS3061FFFC00022F9\nS70500000000FA\n").unwrap();
+    /// assert_eq!(segments.iter().map(|segment| segment.addr).collect::<Vec<_>>(), [0x0800_0000, 0x1fff_c000]);
+    /// ```
+    pub fn srec_segments(name: impl Into<String>, text: &str) -> Result<Vec<Self>, SrecError> {
+        let message = "the data records and the gaps filled between them take more than MAX_IMAGE_SIZE bytes";
+        srec_records(text)?.assemble(name, MAX_FILLED_GAP, message).map_err(|(line, message)| SrecError { line, message })
+    }
+
+    /// Writes the dump as Motorola S-records, to be programmed by flashing tools or loaded with
+    /// [`MemDump::from_srec`].
+    ///
+    /// The name of the dump is written in the header record (`S0`). Data records hold 16 bytes, with the shortest
+    /// address able to reach the end of the dump: `S1` (16 bits), `S2` (24 bits) or `S3` (32 bits). A count record
+    /// (`S5` or `S6`) and the matching termination record (`S9`, `S8` or `S7`) end the image. Fails if the dump reaches
+    /// beyond 4 GiB, the address space of the format.
+    ///
+    /// # Argument
+    ///
+    /// * `out` - The writer receiving the records, e.g. a `String` saved as a `.srec` file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let image = MemDump::new("", "image", 0x10, vec![1, 2, 3, 4]);
+    /// let mut srec = String::new();
+    /// image.write_srec(&mut srec).unwrap();
+    /// assert_eq!(srec, "S0030000FC\nS107001001020304DE\nS5030001FB\nS9030000FC\n");
+    /// ```
+    pub fn write_srec<W: fmt::Write + ?Sized>(&self, out: &mut W) -> fmt::Result {
+        let end = match self.addr.checked_add(self.bytes.len()) {
+            Some(end) if end as u64 <= 1 << 32 => end as u64,
+            _ => return Err(fmt::Error),
+        };
+        let last = end.saturating_sub(1);
+        let (data_kind, end_kind, addr_len) = if last <= 0xffff {
+            ("S1", "S9", 2)
+        } else if last <= 0xff_ffff {
+            ("S2", "S8", 3)
+        } else {
+            ("S3", "S7", 4)
+        };
+
+        let mut header = alloc::vec![0x00, 0x00];
+        // The byte count of a record can't exceed 255
+        header.extend(self.name.bytes().take(250));
+        write_record(out, "S0", &[header.len() as u8 + 1], &header, srec_checksum)?;
+
+        let mut count = 0u32;
+        for (index, data) in self.bytes.chunks(RECORD_DATA_LEN).enumerate() {
+            let addr = (self.addr + index * RECORD_DATA_LEN) as u32;
+            let mut fields = alloc::vec![(addr_len + data.len() + 1) as u8];
+            fields.extend_from_slice(&addr.to_be_bytes()[4 - addr_len..]);
+            write_record(out, data_kind, &fields, data, srec_checksum)?;
+            count += 1;
+        }
+
+        if count <= 0xffff {
+            write_record(out, "S5", &[3], &(count as u16).to_be_bytes(), srec_checksum)?;
+        } else {
+            write_record(out, "S6", &[4], &count.to_be_bytes()[1..], srec_checksum)?;
+        }
+        let mut termination = alloc::vec![addr_len as u8 + 1];
+        termination.resize(addr_len + 1, 0);
+        write_record(out, end_kind, &termination, &[], srec_checksum)
+    }
+}
+
+/// Decodes a string of hex digit pairs, `None` if it has an odd length or other characters.
+fn hex_pairs(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len()).step_by(2).map(|start| u8::from_str_radix(&hex[start..start + 2], 16).ok()).collect()
+}
+
+/// Reads the data records of an Intel HEX image.
+fn ihex_records(text: &str) -> Result<Records, IhexError> {
+    let mut records = Records::default();
+    let mut base = 0usize;
+    for (index, line) in text.lines().enumerate() {
+        let error = |message| IhexError { line: index + 1, message };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let hex = line.strip_prefix(':').ok_or(error("a record starts with ':'"))?;
+        let bytes = hex_pairs(hex).ok_or(error("a record is made of hex digit pairs"))?;
+        if bytes.len() < 5 || bytes.len() != 5 + bytes[0] as usize {
+            return Err(error("the record length doesn't match its byte count"));
+        }
+        if bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0 {
+            return Err(error("wrong checksum"));
+        }
+
+        let offset = u16::from_be_bytes([bytes[1], bytes[2]]) as usize;
+        let data = &bytes[4..bytes.len() - 1];
+        match bytes[3] {
+            0x00 => {
+                let addr = base.checked_add(offset).ok_or(error("the address doesn't fit the target"))?;
+                records.push(index + 1, addr, data).map_err(error)?;
+            }
+            0x01 => break,
+            0x02 | 0x04 => {
+                let [high, low] = data else {
+                    return Err(error("an extended address record has 2 data bytes"));
+                };
+                let shift = if bytes[3] == 0x02 { 4 } else { 16 };
+                base = (u16::from_be_bytes([*high, *low]) as usize) << shift;
+            }
+            0x03 | 0x05 => {}
+            _ => return Err(error("unknown record type")),
+        }
+    }
+    Ok(records)
+}
+
+/// Reads the data records of an S-record image.
+fn srec_records(text: &str) -> Result<Records, SrecError> {
+    let mut records = Records::default();
+    for (index, line) in text.lines().enumerate() {
+        let error = |message| SrecError { line: index + 1, message };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let record = line.strip_prefix('S').ok_or(error("a record starts with 'S'"))?;
+        let kind = record.chars().next().ok_or(error("a record has a type digit"))?;
+        let bytes = hex_pairs(&record[kind.len_utf8()..]).ok_or(error("a record is made of hex digit pairs"))?;
+        if bytes.is_empty() || bytes.len() != 1 + bytes[0] as usize {
+            return Err(error("the record length doesn't match its byte count"));
+        }
+        if bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0xff {
+            return Err(error("wrong checksum"));
+        }
+
+        let addr_len = match kind {
+            '0' | '1' | '5' | '9' => 2,
+            '2' | '6' | '8' => 3,
+            '3' | '7' => 4,
+            _ => return Err(error("unknown record type")),
+        };
+        let fields = &bytes[1..bytes.len() - 1];
+        if fields.len() < addr_len {
+            return Err(error("the record is shorter than its address"));
+        }
+        let (addr, data) = fields.split_at(addr_len);
+        let addr = addr.iter().fold(0u64, |addr, byte| addr << 8 | *byte as u64);
+        match kind {
+            '1' | '2' | '3' => {
+                let addr = usize::try_from(addr).map_err(|_| error("the address doesn't fit the target"))?;
+                records.push(index + 1, addr, data).map_err(error)?;
+            }
+            '7' | '8' | '9' => break,
+            _ => {}
+        }
+    }
+    Ok(records)
+}
+
+/// Data records of an image being loaded, in file order with their line.
+#[derive(Default)]
+struct Records {
+    records: Vec<(usize, usize, Vec<u8>)>,
+    size: usize,
+}
+
+impl Records {
+    /// Adds the data record of `line`, failing if it ends beyond the address space or the records would hold more than
+    /// [`MAX_IMAGE_SIZE`] bytes.
+    fn push(&mut self, line: usize, addr: usize, data: &[u8]) -> Result<(), &'static str> {
+        addr.checked_add(data.len()).ok_or("the record ends beyond the address space")?;
+        self.size += data.len();
+        if self.size > MAX_IMAGE_SIZE {
+            return Err("the data records hold more than MAX_IMAGE_SIZE bytes");
+        }
+        self.records.push((line, addr, data.to_vec()));
+        Ok(())
+    }
+
+    /// Places the records in a dump per segment, ordered by address: the records at most `max_gap` bytes apart are in
+    /// the same segment, the gaps filled with [`ERASED_BYTE`], and the later records of the file overwrite the earlier
+    /// ones. Fails with `message` and the line of the record making the segments take more than [`MAX_IMAGE_SIZE`]
+    /// bytes.
+    fn assemble(self, name: impl Into<String>, max_gap: usize, message: &'static str) -> Result<Vec<MemDump>, (usize, &'static str)> {
+        let mut order: Vec<&(usize, usize, Vec<u8>)> = self.records.iter().collect();
+        order.sort_by_key(|(_, addr, _)| *addr);
+        let mut spans: Vec<Range<usize>> = Vec::new();
+        let mut size = 0usize;
+        for (line, addr, data) in order {
+            let end = addr + data.len();
+            let grown = match spans.last_mut() {
+                Some(span) if *addr <= span.end.saturating_add(max_gap) => {
+                    let grown = end.saturating_sub(span.end);
+                    span.end = span.end.max(end);
+                    grown
+                }
+                _ => {
+                    spans.push(*addr..end);
+                    data.len()
+                }
+            };
+            size += grown;
+            if size > MAX_IMAGE_SIZE {
+                return Err((*line, message));
+            }
+        }
+
+        let name = name.into();
+        let mut segments: Vec<MemDump> = spans.iter().map(|span| MemDump::new(name.clone(), "image", span.start, alloc::vec![ERASED_BYTE; span.len()])).collect();
+        for (_, addr, data) in self.records {
+            let segment = &mut segments[spans.partition_point(|span| span.start <= addr) - 1];
+            let offset = addr - segment.addr;
+            segment.bytes[offset..offset + data.len()].copy_from_slice(&data);
+        }
+        Ok(segments)
+    }
+}
+
+/// Checksum of an Intel HEX record: the two's complement of the sum of its bytes.
+fn ihex_checksum(sum: u8) -> u8 {
+    sum.wrapping_neg()
+}
+
+/// Checksum of an S-record: the one's complement of the sum of its bytes.
+fn srec_checksum(sum: u8) -> u8 {
+    !sum
+}
+
+/// Writes a record line: its start code, its fields and data in upper case hex, and its checksum.
+fn write_record<W: fmt::Write + ?Sized>(out: &mut W, start: &str, fields: &[u8], data: &[u8], checksum: fn(u8) -> u8) -> fmt::Result {
+    out.write_str(start)?;
+    let mut sum = 0u8;
+    for byte in fields.iter().chain(data) {
+        sum = sum.wrapping_add(*byte);
+        write!(out, "{:02X}", byte)?;
+    }
+    writeln!(out, "{:02X}", checksum(sum))
+}
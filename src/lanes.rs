@@ -0,0 +1,163 @@
+//! Lane views of SIMD vectors.
+//!
+//! A SIMD register holds several lanes of the same type, e.g. 4 `f32` in a 128-bit `__m128`, and the same bytes are
+//! often reinterpreted as other lanes from an instruction to the next. A byte per row hides all this: the lane view
+//! shows the value of every lane, a row per lane type, lane 0 first.
+
+use core::fmt;
+
+use alloc::string::String;
+
+use crate::elements::ElementType;
+
+/// Lane types shown when none are asked for.
+const DEFAULT_LANES: [ElementType; 6] = [ElementType::U8, ElementType::U16, ElementType::U32, ElementType::U64, ElementType::F32, ElementType::F64];
+
+/// Size of the blocks the lanes of wider vectors are split in: 128 bits, the lanes of the AVX instructions.
+const BLOCK: usize = 16;
+
+/// Separator line of the lane tables.
+const SIMD_LANES: &str = "-----------------------SIMD Lanes-----------------------";
+
+/// Writes the lanes of the SIMD vector made of `bytes`, a row per lane type with the value of every lane, lane 0
+/// first.
+///
+/// Lane types not dividing the size of the vector are skipped; without `lanes`, the vector is shown as `u8`, `u16`,
+/// `u32`, `u64`, `f32` and `f64` lanes. Vectors wider than 128 bits are split in blocks of 128 bits, as most AVX
+/// instructions work within these blocks, each with its own rows.
+///
+/// # Arguments
+///
+/// * `out` - The writer receiving the lanes.
+/// * `bytes` - The bytes of the vector, in memory order: the first byte is the lowest of lane 0.
+/// * `lanes` - The lane types to show, all the default ones if empty.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_vector: Vec<u8> = [1.0f32, 2.0, -0.5, 4.0].iter().flat_map(|lane| lane.to_ne_bytes()).collect();
+/// let mut out = String::new();
+/// write_lanes(&mut out, &my_vector, &[ElementType::F32]).unwrap();
+/// assert!(out.contains(" x4  f32 |            1.0 |            2.0 |           -0.5 |            4.0 |\n"));
+/// ```
+pub fn write_lanes<W: fmt::Write + ?Sized>(out: &mut W, bytes: &[u8], lanes: &[ElementType]) -> fmt::Result {
+    write_lane_rows(out, bytes, lanes, false)
+}
+
+/// Writes the lanes like [`write_lanes`], with every value replaced by `***` if `redact` is set.
+pub(crate) fn write_lane_rows<W: fmt::Write + ?Sized>(out: &mut W, bytes: &[u8], lanes: &[ElementType], redact: bool) -> fmt::Result {
+    let lanes = if lanes.is_empty() { &DEFAULT_LANES[..] } else { lanes };
+    let block = bytes.len().min(BLOCK);
+    let lanes: alloc::vec::Vec<ElementType> = lanes.iter().copied().filter(|lane| block != 0 && block.is_multiple_of(lane.size()) && bytes.len().is_multiple_of(block)).collect();
+    if lanes.is_empty() {
+        writeln!(out, "Lane: the {} bytes of the value don't split into whole lanes of the asked types", bytes.len())?;
+        return writeln!(out);
+    }
+
+    writeln!(out, " Lanes   | Lane 0 first, in the byte order of the machine")?;
+    writeln!(out, "{}", SIMD_LANES)?;
+    for (index, chunk) in bytes.chunks(block).enumerate() {
+        if bytes.len() > BLOCK {
+            writeln!(out, " +{:#x}..+{:#x}, bits {}..{}", index * block, (index + 1) * block, index * block * 8, (index + 1) * block * 8)?;
+        }
+        for lane in &lanes {
+            write!(out, " x{:<2} {:<4}|", chunk.len() / lane.size(), lane.name())?;
+            for value in chunk.chunks(lane.size()) {
+                let mut text = String::new();
+                if redact {
+                    text.push_str("***");
+                } else {
+                    lane.write_value(&mut text, value)?;
+                }
+                write!(out, " {:>width$} |", text, width = lane.column_width())?;
+            }
+            writeln!(out)?;
+        }
+    }
+    writeln!(out)
+}
+
+#[cfg(feature = "std")]
+#[macro_export]
+/// Macro to view a SIMD vector lane by lane.
+///
+/// Works with the vector types of `core::arch` (`__m128`, `__m256i`, `uint8x16_t`, ...), `std::simd` vectors and
+/// plain arrays such as `[u8; 16]`: the bytes of the value are shown a row per lane type, e.g. `x16 u8` or `x4 f32`,
+/// with the value of every lane, lane 0 first. The lane types follow the value as [`ElementType`](crate::ElementType)
+/// variants; without them, the vector is shown as `u8`, `u16`, `u32`, `u64`, `f32` and `f64` lanes. Vectors wider than
+/// 128 bits are split in blocks of 128 bits. When [`Config::redact`](crate::Config::redact) is set, every lane value
+/// reads `***`. In Miri-friendly mode the lanes aren't shown.
+///
+/// # Arguments
+///
+/// * `var` - The vector to dump.
+/// * `lanes` - Optionally, the lane types to show, e.g. `F32, U8`.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_vector: [i16; 8] = [1, -1, 2, -2, 3, -3, 4, -4];
+/// view_simd!(my_vector, I16, U8);
+///
+/// #[cfg(target_arch = "x86_64")]
+/// {
+///     let my_register = unsafe { std::mem::transmute::<[f32; 4], std::arch::x86_64::__m128>([1.0, 2.0, 3.0, 4.0]) };
+///     view_simd!(my_register, F32);
+/// }
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_vector
+/// Type: [i16; 8]
+/// Addr: 00007ffc3a4e1b50
+/// Size: 16 bytes
+///  Lanes   | Lane 0 first, in the byte order of the machine
+/// -----------------------SIMD Lanes-----------------------
+///  x8  i16 |      1 |     -1 |      2 |     -2 |      3 |     -3 |      4 |     -4 |
+///  x16 u8  |   1 |   0 | 255 | 255 |   2 |   0 | 254 | 255 |   3 |   0 | 253 | 255 |   4 |   0 | 252 | 255 |
+///
+/// Name: my_register
+/// Type: core::core_arch::x86::__m128
+/// Addr: 00007ffc3a4e1b60
+/// Size: 16 bytes
+///  Lanes   | Lane 0 first, in the byte order of the machine
+/// -----------------------SIMD Lanes-----------------------
+///  x4  f32 |            1.0 |            2.0 |            3.0 |            4.0 |
+/// ```
+macro_rules! view_simd {
+    ($var: expr) => {
+        $crate::_show_simd(stringify!($var), &$var, &[])
+    };
+    ($var: expr, $($lane: ident),+ $(,)?) => {
+        $crate::_show_simd(stringify!($var), &$var, &[$($crate::ElementType::$lane),+])
+    };
+}
+
+/// Displays the lanes of a SIMD vector.
+///
+/// (This is supposed to be private usage of view_simd! macro usage.)
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `vector` - The vector to display.
+/// * `lanes` - The lane types to show, all the default ones if empty.
+#[cfg(feature = "std")]
+pub fn _show_simd<T>(name: &str, vector: &T, lanes: &[ElementType]) {
+    crate::render::emit(|out| {
+        let addr = (vector as *const T).addr();
+        crate::write::write_header(out, name, core::any::type_name::<T>(), addr, core::mem::size_of::<T>())?;
+        if crate::config().miri_friendly {
+            return crate::render::write_miri_note(out);
+        }
+        // Vector types and arrays have no padding, all their bytes are initialized
+        let bytes = unsafe { core::slice::from_raw_parts(vector as *const T as *const u8, core::mem::size_of::<T>()) };
+        write_lane_rows(out, bytes, lanes, crate::config().redact)
+    });
+}
@@ -0,0 +1,348 @@
+//! Field layout descriptions of structs.
+//!
+//! A [`StructLayout`] is usually built with the [`layout_of!`](crate::layout_of) macro, which reads the offset, size
+//! and type of every listed field from the compiler, so it always matches the real layout.
+
+use core::fmt;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Layout of a single field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldLayout {
+    /// Name of the field.
+    pub name: &'static str,
+    /// Type name of the field.
+    pub type_name: &'static str,
+    /// Offset of the field from the start of the struct, in bytes.
+    pub offset: usize,
+    /// Size of the field in bytes.
+    pub size: usize,
+    /// Alignment of the field in bytes.
+    pub align: usize,
+}
+
+impl FieldLayout {
+    /// Returns `true` if the field occupies no memory, e.g. `PhantomData` or `()`.
+    pub fn is_zero_sized(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Returns `true` if the bytes of the field can change behind a shared reference, i.e. the field is an
+    /// `UnsafeCell` or a type wrapping one: `Cell`, `RefCell`, `OnceCell`, an atomic, `Mutex`, `RwLock`, ... or an
+    /// array of them.
+    ///
+    /// Only the type name is looked at, so a struct of the user wrapping a cell isn't recognized, and neither are the
+    /// fields of a [parsed](StructLayout::parse) layout.
+    pub fn is_interior_mutable(&self) -> bool {
+        const CELLS: [&str; 11] = ["UnsafeCell", "SyncUnsafeCell", "Cell", "RefCell", "OnceCell", "LazyCell", "Mutex", "RwLock", "OnceLock", "LazyLock", "ReentrantLock"];
+        let outer = self.type_name.trim_start_matches('[');
+        let path = outer.split(['<', ';']).next().unwrap_or_default();
+        path.starts_with("core::sync::atomic::") || (path.contains("::") && CELLS.contains(&path.rsplit("::").next().unwrap_or_default()))
+    }
+}
+
+/// Layout of a struct: its size, alignment and fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructLayout {
+    /// Type name of the struct.
+    pub name: &'static str,
+    /// Size of the struct in bytes.
+    pub size: usize,
+    /// Alignment of the struct in bytes.
+    pub align: usize,
+    /// Fields of the struct, in declaration order.
+    pub fields: Vec<FieldLayout>,
+}
+
+impl StructLayout {
+    /// Returns the fields sorted by offset, which is the order they are stored in memory.
+    pub fn fields_by_offset(&self) -> Vec<&FieldLayout> {
+        let mut fields: Vec<&FieldLayout> = self.fields.iter().collect();
+        fields.sort_by_key(|field| (field.offset, field.size));
+        fields
+    }
+
+    /// Returns the fields occupying no memory.
+    pub fn zero_sized_fields(&self) -> Vec<&FieldLayout> {
+        self.fields.iter().filter(|field| field.is_zero_sized()).collect()
+    }
+
+    /// Returns the [interior-mutable](FieldLayout::is_interior_mutable) fields.
+    pub fn interior_mutable_fields(&self) -> Vec<&FieldLayout> {
+        self.fields.iter().filter(|field| field.is_interior_mutable()).collect()
+    }
+
+    /// Returns the non zero-sized field containing the byte at `offset`, if any.
+    pub fn field_at(&self, offset: usize) -> Option<&FieldLayout> {
+        self.fields.iter().find(|field| offset >= field.offset && offset < field.offset + field.size)
+    }
+
+    /// Parses a layout description, e.g. of a C struct whose Rust definition isn't available.
+    ///
+    /// The description lists the fields as `name: type @ offset + size`, separated by commas, in braces after the
+    /// struct name. Numbers are decimal or `0x` hexadecimal. The names are borrowed from the description, hence its
+    /// `'static` lifetime. Alignments aren't described, they are reported as 1, and the size of the struct is the
+    /// end of its last field.
+    ///
+    /// # Argument
+    ///
+    /// * `description` - The layout description.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let layout = StructLayout::parse("point_t { id: uint16_t @ 0 + 2, flags: uint8_t @ 2 + 1, x: float @ 4 + 4 }").unwrap();
+    /// assert_eq!(layout.size, 8);
+    /// assert_eq!(layout.padding(), vec![3..4]);
+    /// ```
+    pub fn parse(description: &'static str) -> Result<Self, LayoutParseError> {
+        let open = description.find('{').ok_or(LayoutParseError::new(0, "expected `{` after the struct name"))?;
+        let close = description.rfind('}').filter(|&close| close > open).ok_or(LayoutParseError::new(description.len(), "expected `}` after the fields"))?;
+        if !description[close + 1..].trim().is_empty() {
+            return Err(LayoutParseError::new(close + 1, "unexpected text after `}`"));
+        }
+        let name = description[..open].trim();
+        if name.is_empty() {
+            return Err(LayoutParseError::new(0, "expected the struct name before `{`"));
+        }
+
+        let mut fields = Vec::new();
+        let mut position = open + 1;
+        for item in description[open + 1..close].split(',') {
+            if !item.trim().is_empty() {
+                fields.push(parse_field(item, position)?);
+            }
+            position += item.len() + 1;
+        }
+        Ok(StructLayout {
+            name,
+            size: fields.iter().map(|field| field.offset + field.size).max().unwrap_or(0),
+            align: 1,
+            fields,
+        })
+    }
+
+    /// Returns the byte ranges not covered by any field (padding).
+    pub fn padding(&self) -> Vec<core::ops::Range<usize>> {
+        let mut padding = Vec::new();
+        let mut end = 0;
+        for field in self.fields_by_offset() {
+            if field.offset > end {
+                padding.push(end..field.offset);
+            }
+            end = end.max(field.offset + field.size);
+        }
+        if self.size > end {
+            padding.push(end..self.size);
+        }
+        padding
+    }
+}
+
+/// Parses a `name: type @ offset + size` field description starting at `position` in the layout description.
+fn parse_field(item: &'static str, position: usize) -> Result<FieldLayout, LayoutParseError> {
+    let position = position + (item.len() - item.trim_start().len());
+    let syntax = LayoutParseError::new(position, "expected `name: type @ offset + size`");
+    let (name, rest) = item.split_once(':').ok_or(syntax)?;
+    let (type_name, place) = rest.rsplit_once('@').ok_or(syntax)?;
+    let (offset, size) = place.split_once('+').ok_or(syntax)?;
+    let (name, type_name) = (name.trim(), type_name.trim());
+    if name.is_empty() || type_name.is_empty() {
+        return Err(syntax);
+    }
+    Ok(FieldLayout {
+        name,
+        type_name,
+        offset: parse_number(offset).ok_or(LayoutParseError::new(position, "invalid field offset"))?,
+        size: parse_number(size).ok_or(LayoutParseError::new(position, "invalid field size"))?,
+        align: 1,
+    })
+}
+
+/// Parses a decimal or `0x` hexadecimal number.
+fn parse_number(text: &str) -> Option<usize> {
+    let text = text.trim();
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/// Error of [`StructLayout::parse`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutParseError {
+    /// Byte position of the error in the description.
+    pub position: usize,
+    /// What was expected there.
+    pub message: &'static str,
+}
+
+impl LayoutParseError {
+    fn new(position: usize, message: &'static str) -> Self {
+        LayoutParseError { position, message }
+    }
+}
+
+impl fmt::Display for LayoutParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid layout description at byte {}: {}", self.position, self.message)
+    }
+}
+
+impl core::error::Error for LayoutParseError {}
+
+/// Builds the layout of a field from an accessor closure.
+///
+/// (This is supposed to be private usage of layout_of! macro usage.)
+///
+/// # Arguments
+///
+/// * `name` - The name of the field.
+/// * `offset` - The offset of the field, from `offset_of!`.
+/// * `_accessor` - A closure returning a reference to the field, only used to infer its type.
+pub fn _field_layout<T, F>(name: &'static str, offset: usize, _accessor: fn(&T) -> &F) -> FieldLayout {
+    FieldLayout {
+        name,
+        type_name: core::any::type_name::<F>(),
+        offset,
+        size: core::mem::size_of::<F>(),
+        align: core::mem::align_of::<F>(),
+    }
+}
+
+#[macro_export]
+/// Macro to describe the layout of a struct.
+///
+/// Lists the fields (named or tuple indices) whose offset, size and type should be reported. Fields not listed are
+/// shown as padding.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+/// use std::marker::PhantomData;
+///
+/// struct Meters<T> {
+///     value: f64,
+///     unit: PhantomData<T>,
+///     tag: u8,
+/// }
+///
+/// let layout = layout_of!(Meters<u32> { value, unit, tag });
+/// assert_eq!(layout.zero_sized_fields().len(), 1);
+/// ```
+macro_rules! layout_of {
+    ($ty: ty { $($field: tt),* $(,)? }) => {
+        $crate::StructLayout {
+            name: ::core::any::type_name::<$ty>(),
+            size: ::core::mem::size_of::<$ty>(),
+            align: ::core::mem::align_of::<$ty>(),
+            fields: $crate::_vec![
+                $($crate::_field_layout::<$ty, _>(stringify!($field), ::core::mem::offset_of!($ty, $field), |value: &$ty| &value.$field)),*
+            ],
+        }
+    };
+}
+
+#[cfg(feature = "std")]
+#[macro_export]
+/// Macro to print the layout of a struct, with an explanation of its zero-sized fields.
+///
+/// Takes the same arguments as [`layout_of!`](crate::layout_of). Zero-sized fields such as `PhantomData<T>` are
+/// reported explicitly, showing that they take no bytes and don't move the other fields.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+/// use std::marker::PhantomData;
+///
+/// struct Meters<T> {
+///     value: f64,
+///     unit: PhantomData<T>,
+/// }
+///
+/// view_layout!(Meters<u32> { value, unit });
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: Meters<u32>
+/// Size: 8 bytes
+/// Algn: 8 bytes
+///  Offset |  Size | Algn | Field
+/// -----------------------Layout-------------------------
+///  0x0000 |     8 |    8 | value: f64
+///  0x0008 |     0 |    1 | unit: core::marker::PhantomData<u32> (zero-sized)
+///
+/// Zero-sized fields: unit
+///   They contribute 0 bytes: the fields above add up to 8 bytes (with 0 bytes of padding),
+///   which is the whole size of the struct. They only exist for the type system (e.g. PhantomData
+///   marks ownership of or variance over a type it never stores) and don't move any other field.
+/// ```
+macro_rules! view_layout {
+    ($($layout: tt)*) => {
+        $crate::_show_layout(&$crate::layout_of!($($layout)*));
+    };
+}
+
+/// Formats the layout table of `layout` into `out`.
+pub(crate) fn write_layout<W: fmt::Write + ?Sized>(out: &mut W, layout: &StructLayout) -> fmt::Result {
+    writeln!(out, "Name: {}", layout.name)?;
+    writeln!(out, "Size: {} bytes", layout.size)?;
+    writeln!(out, "Algn: {} bytes", layout.align)?;
+    writeln!(out, " Offset |  Size | Algn | Field")?;
+    writeln!(out, "-----------------------Layout-------------------------")?;
+
+    let padding = layout.padding();
+    // Rows are sorted by offset, fields before the padding starting at the same offset (after a zero-sized field)
+    let mut rows: Vec<(usize, bool, String)> = padding.iter().map(|range| (range.start, true, format!(" {:#06x} | {:>5} |      | (padding)", range.start, range.len()))).collect();
+    for field in layout.fields_by_offset() {
+        let zst = if field.is_zero_sized() { " (zero-sized)" } else { "" };
+        let cell = if field.is_interior_mutable() { " (interior mutable)" } else { "" };
+        rows.push((field.offset, false, format!(" {:#06x} | {:>5} | {:>4} | {}: {}{}{}", field.offset, field.size, field.align, field.name, field.type_name, zst, cell)));
+    }
+    rows.sort_by_key(|(offset, is_padding, _)| (*offset, *is_padding));
+    for (_, _, row) in rows {
+        writeln!(out, "{}", row)?;
+    }
+
+    let zero_sized = layout.zero_sized_fields();
+    if !zero_sized.is_empty() {
+        let names: Vec<&str> = zero_sized.iter().map(|field| field.name).collect();
+        let used: usize = layout.fields.iter().map(|field| field.size).sum();
+        let padded: usize = padding.iter().map(|range| range.len()).sum();
+        writeln!(out)?;
+        writeln!(out, "Zero-sized fields: {}", names.join(", "))?;
+        writeln!(out, "  They contribute 0 bytes: the fields above add up to {} bytes (with {} bytes of padding),", used, padded)?;
+        writeln!(out, "  which is the whole size of the struct. They only exist for the type system (e.g. PhantomData")?;
+        writeln!(out, "  marks ownership of or variance over a type it never stores) and don't move any other field.")?;
+    }
+
+    let cells = layout.interior_mutable_fields();
+    if !cells.is_empty() {
+        let names: Vec<&str> = cells.iter().map(|field| field.name).collect();
+        writeln!(out)?;
+        writeln!(out, "Interior-mutable fields: {}", names.join(", "))?;
+        writeln!(out, "  Their bytes can change behind a shared reference, through an UnsafeCell. The other bytes are")?;
+        writeln!(out, "  immutable while the struct is shared.")?;
+    }
+    writeln!(out)
+}
+
+/// Writes the annotation of the byte at `offset` of a value with the layout `layout`: the field containing it, or
+/// `(padding)`.
+pub(crate) fn annotate(layout: &StructLayout, offset: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+    match layout.field_at(offset) {
+        Some(field) if offset == field.offset && field.is_interior_mutable() => write!(out, "{}: {} (interior mutable)", field.name, field.type_name),
+        Some(field) if offset == field.offset => write!(out, "{}: {}", field.name, field.type_name),
+        Some(field) => write!(out, "{} +{}", field.name, offset - field.offset),
+        None => out.write_str("(padding)"),
+    }
+}
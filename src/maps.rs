@@ -0,0 +1,415 @@
+//! Dumps of the maps of the standard library: their control structures, then every entry where it is stored.
+//!
+//! `view_mem!(my_map)` shows only the handle of a `HashMap` or a `BTreeMap`, and `safe_view_mem!` serializes the
+//! entries into one blob which tells nothing of where they live. The [`view_map!`](crate::view_map) macro summarizes
+//! the table of a `HashMap`, its buckets and control bytes, or the nodes of a `BTreeMap`, then dumps the bytes of every
+//! key and value in place, one entry at a time.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Write};
+
+use crate::containers::{annotate_handle, handle_bytes, write_header, Field, HANDLE};
+use crate::layout::{self, FieldLayout, StructLayout};
+use crate::render::{self, Table};
+
+/// Separator line of the tables of the control bytes of a `HashMap`.
+const CONTROL_BYTES: &str = "---------------------Control Bytes----------------------";
+
+/// Separator line of the tables of the buckets of a `HashMap`.
+const BUCKET: &str = "-------------------------Bucket-------------------------";
+
+/// Separator line of the tables of the keys.
+const KEY: &str = "--------------------------Key---------------------------";
+
+/// Separator line of the tables of the values.
+const VALUE: &str = "-------------------------Value--------------------------";
+
+/// Maximal number of entries dumped, and of nodes listed, the others only counted.
+const MAX_MAP_ENTRIES: usize = 16;
+
+/// Number of entries a node of a `BTreeMap` holds at most: `2 * B - 1`, with the `B = 6` of the standard library.
+const NODE_CAPACITY: usize = 11;
+
+/// Control byte of an empty bucket of a `HashMap`.
+const EMPTY: u8 = 0xff;
+
+/// Control byte of a bucket of a `HashMap` whose entry was removed.
+const DELETED: u8 = 0x80;
+
+const WORD: usize = core::mem::size_of::<usize>();
+
+/// An entry of a map: its index in iteration order, and the addresses of its key and value.
+struct Entry {
+    index: usize,
+    key: *const u8,
+    value: *const u8,
+}
+
+/// Returns the entries of a map, in iteration order.
+fn entries<'a, K: 'a, V: 'a>(iter: impl Iterator<Item = (&'a K, &'a V)>) -> Vec<Entry> {
+    let entry = |(index, (key, value)): (usize, (&K, &V))| Entry { index, key: key as *const K as *const u8, value: value as *const V as *const u8 };
+    iter.enumerate().map(entry).collect()
+}
+
+/// Returns the layout of a `(K, V)` pair, the bucket of a `HashMap`, with its fields named `key` and `value`.
+fn pair_layout<K, V>() -> StructLayout {
+    let field = |name, type_name, offset, size, align| FieldLayout { name, type_name, offset, size, align };
+    StructLayout {
+        name: core::any::type_name::<(K, V)>(),
+        size: core::mem::size_of::<(K, V)>(),
+        align: core::mem::align_of::<(K, V)>(),
+        fields: vec![
+            field("key", core::any::type_name::<K>(), core::mem::offset_of!((K, V), 0), core::mem::size_of::<K>(), core::mem::align_of::<K>()),
+            field("value", core::any::type_name::<V>(), core::mem::offset_of!((K, V), 1), core::mem::size_of::<V>(), core::mem::align_of::<V>()),
+        ],
+    }
+}
+
+/// Returns the words of the handle of a map with `len` entries, none for an empty map: they are only looked for to be
+/// matched against its entries, and some of them may be uninitialized then, like the height of an empty `BTreeMap`.
+fn words(handle: &[u8], len: usize) -> Vec<usize> {
+    if len == 0 {
+        return Vec::new();
+    }
+    handle.chunks_exact(WORD).map(|word| usize::from_ne_bytes(word.try_into().unwrap())).collect()
+}
+
+/// Returns the capacity of a table of `hashbrown` with this bucket mask: 7/8 of its buckets, all but one below 8.
+fn mask_capacity(mask: usize) -> usize {
+    if mask < 8 {
+        mask
+    } else {
+        (mask + 1) / 8 * 7
+    }
+}
+
+/// Writes the entries past [`MAX_MAP_ENTRIES`], which aren't dumped.
+fn write_more(out: &mut String, len: usize) -> fmt::Result {
+    if len > MAX_MAP_ENTRIES {
+        writeln!(out, "... {} more entries not shown\n", len - MAX_MAP_ENTRIES)?;
+    }
+    Ok(())
+}
+
+/// Writes the key and the value of an entry in separate tables, the zero-sized ones skipped.
+fn write_key_value(out: &mut String, name: &str, entry: &Entry, pair: &StructLayout) -> fmt::Result {
+    for (separator, field, ptr) in [(KEY, &pair.fields[0], entry.key), (VALUE, &pair.fields[1], entry.value)] {
+        if field.size == 0 {
+            continue;
+        }
+        let bytes = unsafe { core::slice::from_raw_parts(ptr, field.size) };
+        let annotate = |offset: usize, out: &mut dyn fmt::Write| match offset {
+            0 => write!(out, "{}: {}", field.name, field.type_name),
+            offset => write!(out, "{} +{}", field.name, offset),
+        };
+        Table { label: name, annotate: Some(&annotate), ..Table::new(separator, ptr.addr(), bytes) }.write(out)?;
+    }
+    Ok(())
+}
+
+/// Where the table of a `HashMap` is, found from its entries.
+struct HashTable {
+    /// The address of the control bytes, the buckets stored below it from the first to the last.
+    ctrl: usize,
+    /// The number of buckets minus one.
+    mask: usize,
+}
+
+/// Formats the handle, the control bytes and the entries of a `HashMap`.
+///
+/// The `hashbrown` table behind a `HashMap` stores its handle as a pointer to the control bytes, the bucket mask, the
+/// number of entries and the room left before it grows: this layout isn't specified, the words are found in `handle`
+/// by their values. Bucket `i` holds a `(K, V)` pair stored `i + 1` pairs below the control bytes, which is how the
+/// control pointer is told from the other words, and its control byte tells whether it is empty, deleted or full.
+fn write_hash_map(out: &mut String, name: &str, type_name: &str, handle: &[u8], entries: &[Entry], capacity: usize, pair: &StructLayout) -> fmt::Result {
+    let len = entries.len();
+    let size = pair.size;
+    let start = |entry: &Entry| entry.key.addr().wrapping_sub(pair.fields[0].offset);
+    let words = words(handle, len);
+    let paired = size > 0 && !entries.is_empty() && entries.iter().all(|entry| entry.value.addr() == start(entry) + pair.fields[1].offset);
+    let ctrl = words.iter().copied().find(|ctrl| paired && entries.iter().all(|entry| *ctrl > start(entry) && (*ctrl - start(entry)) % size == 0));
+    let bucket = |ctrl: usize, entry: &Entry| (ctrl - start(entry)) / size - 1;
+    let table = ctrl.and_then(|ctrl| {
+        let last = entries.iter().map(|entry| bucket(ctrl, entry)).max().unwrap_or(0);
+        let mask = words.iter().copied().filter(|mask| *mask >= last && mask.checked_add(1).is_some_and(usize::is_power_of_two) && mask_capacity(*mask) >= capacity).min()?;
+        Some(HashTable { ctrl, mask })
+    });
+
+    write_header(out, name, type_name, handle)?;
+    let growth = capacity - len;
+    match &table {
+        Some(HashTable { ctrl, mask }) => {
+            writeln!(out, "Tabl: {} entr{} in {} buckets of {} bytes, capacity {}, {} more before it grows", len, if len == 1 { "y" } else { "ies" }, mask + 1, size, capacity, growth)?;
+            writeln!(out, "Heap: {}, the buckets stored from the last to the first below the {} control bytes at {}", crate::_HeaderAddr(ctrl - (mask + 1) * size), mask + 1, crate::_HeaderAddr(*ctrl))?;
+        }
+        None => {
+            writeln!(out, "Tabl: {} entr{}, capacity {}, {} more before it grows", len, if len == 1 { "y" } else { "ies" }, capacity, growth)?;
+            if capacity == 0 {
+                writeln!(out, "Heap: none, the capacity is 0")?;
+            } else if size == 0 {
+                writeln!(out, "Heap: the entries are zero-sized, the buckets take no bytes")?;
+            } else {
+                writeln!(out, "Heap: not located, the buckets are found from the entries")?;
+            }
+        }
+    }
+    if crate::config().miri_friendly {
+        return render::write_miri_note(out);
+    }
+
+    let mut fields: Vec<Field> = vec![("items", len, false), ("growth_left", growth, false)];
+    if let Some(HashTable { ctrl, mask }) = table {
+        fields.extend([("ctrl", ctrl, true), ("bucket_mask", mask, false)]);
+    }
+    let annotate = |offset: usize, out: &mut dyn fmt::Write| annotate_handle(handle, &fields, offset, out);
+    Table { label: name, annotate: Some(&annotate), ..Table::new(HANDLE, handle.as_ptr().addr(), handle) }.write(out)?;
+
+    let Some(HashTable { ctrl, mask }) = table else {
+        for entry in entries.iter().take(MAX_MAP_ENTRIES) {
+            writeln!(out, "Entr: [{}]", entry.index)?;
+            write_key_value(out, name, entry, pair)?;
+        }
+        return write_more(out, len);
+    };
+    // The control bytes are in the allocation of the buckets, reached from an entry
+    let first = &entries[0];
+    let control = unsafe { core::slice::from_raw_parts(first.key.wrapping_add(ctrl - first.key.addr()), mask + 1) };
+    let mut owners = vec![None; mask + 1];
+    for entry in entries {
+        owners[bucket(ctrl, entry)] = Some(entry.index);
+    }
+    let annotate = |offset: usize, out: &mut dyn fmt::Write| match control[offset] {
+        EMPTY => write!(out, "bucket {}: EMPTY", offset),
+        DELETED => write!(out, "bucket {}: DELETED", offset),
+        h2 if h2 < 0x80 => {
+            write!(out, "bucket {}: FULL, h2 = {:#04x}", offset, h2)?;
+            match owners[offset] {
+                Some(index) => write!(out, ", entry [{}]", index),
+                None => Ok(()),
+            }
+        }
+        byte => write!(out, "bucket {}: {:#04x}", offset, byte),
+    };
+    Table { label: name, annotate: Some(&annotate), ..Table::new(CONTROL_BYTES, ctrl, control) }.write(out)?;
+
+    for entry in entries.iter().take(MAX_MAP_ENTRIES) {
+        let index = bucket(ctrl, entry);
+        writeln!(out, "Entr: [{}] in bucket {}, {} bytes below the control bytes", entry.index, index, (index + 1) * size)?;
+        let bytes = unsafe { core::slice::from_raw_parts(entry.key.wrapping_sub(pair.fields[0].offset), size) };
+        let annotate = |offset: usize, out: &mut dyn fmt::Write| layout::annotate(pair, offset, out);
+        Table { label: name, annotate: Some(&annotate), ..Table::new(BUCKET, start(entry), bytes) }.write(out)?;
+    }
+    write_more(out, len)
+}
+
+/// A node of a `BTreeMap`, found from the entries it holds.
+struct Node {
+    /// The address of the first key, or of the first value when the keys are zero-sized.
+    start: usize,
+    /// The slots and the indexes of its entries.
+    entries: Vec<(usize, usize)>,
+}
+
+/// Formats the handle, the nodes and the entries of a `BTreeMap`.
+///
+/// A node of a `BTreeMap` holds up to [`NODE_CAPACITY`] keys in an array, and as many values in another: the nodes
+/// are told apart by the addresses of the keys, consecutive in a node and in separate allocations between nodes. The
+/// handle holds the length, and the root node with the height of the tree, found by their values.
+fn write_btree_map(out: &mut String, name: &str, type_name: &str, handle: &[u8], entries: &[Entry], pair: &StructLayout) -> fmt::Result {
+    let len = entries.len();
+    let (key, value) = (&pair.fields[0], &pair.fields[1]);
+    // The slots of a node are found from its keys, or its values when the keys are zero-sized
+    let (slot_size, addr): (usize, fn(&Entry) -> usize) = if key.size > 0 { (key.size, |entry| entry.key.addr()) } else { (value.size, |entry| entry.value.addr()) };
+    let mut sorted: Vec<&Entry> = entries.iter().collect();
+    sorted.sort_by_key(|entry| addr(entry));
+    let mut nodes: Vec<Node> = Vec::new();
+    for entry in sorted.iter().filter(|_| slot_size > 0) {
+        match nodes.last_mut() {
+            Some(node) if addr(entry) - node.start < NODE_CAPACITY * slot_size => node.entries.push(((addr(entry) - node.start) / slot_size, entry.index)),
+            _ => nodes.push(Node { start: addr(entry), entries: vec![(0, entry.index)] }),
+        }
+    }
+    // The arrays of a node follow its parent pointer and its length: the root pointer is a bit below its first slot
+    let reach = 2 * WORD + NODE_CAPACITY * (key.size + value.size) + key.align + value.align;
+    let words = words(handle, len);
+    let root = words.iter().copied().filter(|word| *word != len).find_map(|word| {
+        let node = nodes.iter().position(|node| word <= node.start && node.start - word <= reach)?;
+        Some((word, node))
+    });
+
+    write_header(out, name, type_name, handle)?;
+    match (len, nodes.len()) {
+        (0, _) => writeln!(out, "Tree: 0 entries, no node allocated")?,
+        (_, 0) => writeln!(out, "Tree: {} entr{}, zero-sized: their nodes take no bytes and aren't located", len, if len == 1 { "y" } else { "ies" })?,
+        (_, count) => {
+            let plural = if count == 1 { "" } else { "s" };
+            write!(out, "Tree: {} entr{} in {} node{} of up to {} entries", len, if len == 1 { "y" } else { "ies" }, count, plural, NODE_CAPACITY)?;
+            match root {
+                Some((_, node)) => writeln!(out, ", the root is node {}", node)?,
+                None => writeln!(out)?,
+            }
+        }
+    }
+    for (number, node) in nodes.iter().enumerate().take(MAX_MAP_ENTRIES) {
+        let label = if number == 0 { "Node: " } else { "      " };
+        let first = entries.iter().find(|entry| entry.index == node.entries[0].1).unwrap();
+        let indexes: Vec<String> = node.entries.iter().map(|(_, index)| format!("[{}]", index)).collect();
+        let held = node.entries.len();
+        let plural = if held == 1 { "y" } else { "ies" };
+        writeln!(out, "{}{}: {} entr{}, keys at {}, values at {}: {}", label, number, held, plural, crate::_HeaderAddr(first.key.addr()), crate::_HeaderAddr(first.value.addr()), indexes.join(" "))?;
+    }
+    if nodes.len() > MAX_MAP_ENTRIES {
+        writeln!(out, "      ... {} more nodes", nodes.len() - MAX_MAP_ENTRIES)?;
+    }
+    if crate::config().miri_friendly {
+        return render::write_miri_note(out);
+    }
+
+    let mut fields: Vec<Field> = vec![("len", len, false)];
+    if let Some((word, _)) = root {
+        fields.push(("root", word, true));
+    }
+    if nodes.len() == 1 {
+        fields.push(("height", 0, false));
+    }
+    let annotate = |offset: usize, out: &mut dyn fmt::Write| annotate_handle(handle, &fields, offset, out);
+    Table { label: name, annotate: Some(&annotate), ..Table::new(HANDLE, handle.as_ptr().addr(), handle) }.write(out)?;
+
+    for entry in entries.iter().take(MAX_MAP_ENTRIES) {
+        let place = nodes.iter().enumerate().find_map(|(number, node)| node.entries.iter().find(|(_, index)| *index == entry.index).map(|(slot, _)| (number, *slot)));
+        match place {
+            Some((number, slot)) => writeln!(out, "Entr: [{}] in node {}, slot {}", entry.index, number, slot)?,
+            None => writeln!(out, "Entr: [{}]", entry.index)?,
+        }
+        write_key_value(out, name, entry, pair)?;
+    }
+    write_more(out, len)
+}
+
+/// A map shown by [`view_map!`](crate::view_map): `HashMap` or `BTreeMap`.
+///
+/// (This is supposed to be private usage of view_map! macro usage.)
+pub trait _Map {
+    /// Formats the handle, the control structures and the entries.
+    fn write_map(&self, out: &mut String, name: &str) -> fmt::Result;
+}
+
+impl<K, V, S> _Map for HashMap<K, V, S> {
+    fn write_map(&self, out: &mut String, name: &str) -> fmt::Result {
+        let type_name = core::any::type_name::<HashMap<K, V, S>>();
+        write_hash_map(out, name, type_name, handle_bytes(self), &entries(self.iter()), self.capacity(), &pair_layout::<K, V>())
+    }
+}
+
+impl<K, V> _Map for BTreeMap<K, V> {
+    fn write_map(&self, out: &mut String, name: &str) -> fmt::Result {
+        let type_name = core::any::type_name::<BTreeMap<K, V>>();
+        write_btree_map(out, name, type_name, handle_bytes(self), &entries(self.iter()), &pair_layout::<K, V>())
+    }
+}
+
+#[macro_export]
+/// Macro to view a `HashMap` or a `BTreeMap`: its control structures, then the bytes of every entry where it is
+/// stored.
+///
+/// A `HashMap` is summarized on a `Tabl` line, its entries, buckets and capacity, and a `Heap` line locating its
+/// buckets and control bytes. The words of the handle are named (`ctrl`, `bucket_mask`, `items`, `growth_left`), the
+/// control bytes follow, one per bucket, annotated `EMPTY`, `DELETED` or `FULL` with the hash bits they keep and the
+/// entry in the bucket, then every entry in a `Bucket` table, its key, its value and the padding between them
+/// annotated. A `BTreeMap` is summarized on a `Tree` line, its entries and nodes, with a `Node` line per node giving
+/// where its keys and values are and which entries it holds; the words of the handle are named (`root`, `len`), then
+/// every entry is dumped in a `Key` and a `Value` table, with its node and slot. The first 16 entries are dumped, the
+/// others counted. In Miri-friendly mode the memory content isn't shown.
+///
+/// # Argument
+///
+/// * `var` - The `HashMap` or `BTreeMap` to dump.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+/// use std::collections::{BTreeMap, HashMap};
+///
+/// let my_map: HashMap<u8, u32> = HashMap::from([(1, 10)]);
+/// view_map!(my_map);
+///
+/// let my_tree: BTreeMap<u16, char> = BTreeMap::from([(1, 'a'), (2, 'b')]);
+/// view_map!(my_tree);
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_map
+/// Type: std::collections::hash::map::HashMap<u8, u32>
+/// Addr: 00007ffd3c5a1e20
+/// Size: 48 bytes
+/// Aloc: Likely Stack (handle)
+/// Tabl: 1 entry in 4 buckets of 8 bytes, capacity 3, 2 more before it grows
+/// Heap: 000055f6e4a3bb00, the buckets stored from the last to the first below the 4 control bytes at 000055f6e4a3bb20
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// -------------------------Handle-------------------------
+///  00007ffd3c5a1e20 | 20  | 032 | 00100000 |       | ... | ctrl = 000055f6e4a3bb20
+/// ...
+///  00007ffd3c5a1e28 | 03  | 003 | 00000011 |  ETX  | ... | bucket_mask = 3
+/// ...
+///  00007ffd3c5a1e30 | 02  | 002 | 00000010 |  STX  | ... | growth_left = 2
+/// ...
+///  00007ffd3c5a1e38 | 01  | 001 | 00000001 |  SOH  | ... | items = 1
+/// ...
+///
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ---------------------Control Bytes----------------------
+///  000055f6e4a3bb20 | ff  | 255 | 11111111 |  ...  | ... | bucket 0: EMPTY
+///  000055f6e4a3bb21 | ff  | 255 | 11111111 |  ...  | ... | bucket 1: EMPTY
+///  000055f6e4a3bb22 | 5d  | 093 | 01011101 |   ]   | ... | bucket 2: FULL, h2 = 0x5d, entry [0]
+///  000055f6e4a3bb23 | ff  | 255 | 11111111 |  ...  | XXX | bucket 3: EMPTY
+///
+/// Entr: [0] in bucket 2, 24 bytes below the control bytes
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// -------------------------Bucket-------------------------
+///  000055f6e4a3bb08 | 01  | 001 | 00000001 |  SOH  | ... | key: u8
+///  000055f6e4a3bb09 | 00  | 000 | 00000000 |  NUL  | ... | (padding)
+///  000055f6e4a3bb0a | 00  | 000 | 00000000 |  NUL  | ... | (padding)
+///  000055f6e4a3bb0b | 00  | 000 | 00000000 |  NUL  | ... | (padding)
+///  000055f6e4a3bb0c | 0a  | 010 | 00001010 |  LF   | ... | value: u32
+///  000055f6e4a3bb0d | 00  | 000 | 00000000 |  NUL  | XXX | value +1
+/// ...
+///
+/// Name: my_tree
+/// Type: alloc::collections::btree::map::BTreeMap<u16, char>
+/// Addr: 00007ffd3c5a1e60
+/// Size: 24 bytes
+/// Aloc: Likely Stack (handle)
+/// Tree: 2 entries in 1 node of up to 11 entries, the root is node 0
+/// Node: 0: 2 entries, keys at 000055f6e4a3bc8c, values at 000055f6e4a3bc60: [0] [1]
+/// ...
+/// Entr: [1] in node 0, slot 1
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// --------------------------Key---------------------------
+///  000055f6e4a3bc8e | 02  | 002 | 00000010 |  STX  | ... | key: u16
+///  000055f6e4a3bc8f | 00  | 000 | 00000000 |  NUL  | XXX | key +1
+///
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// -------------------------Value--------------------------
+///  000055f6e4a3bc64 | 62  | 098 | 01100010 |   b   | ... | value: char
+/// ...
+/// ```
+macro_rules! view_map {
+    ($var: expr) => {
+        $crate::_show_map(stringify!($var), &$var)
+    };
+}
+
+/// Displays the control structures and the entries of a map.
+///
+/// (This is supposed to be private usage of view_map! macro usage.)
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `map` - The `HashMap` or `BTreeMap` to display.
+pub fn _show_map<M: _Map>(name: &str, map: &M) {
+    render::emit(|out| map.write_map(out, name));
+}
@@ -0,0 +1,81 @@
+//! Dumps rendered as GitHub-flavored Markdown tables.
+
+use core::fmt::Write as _;
+
+use alloc::string::String;
+
+use crate::dump::MemDump;
+use crate::render::{ASCII, BIN, DEC, HEX};
+
+impl MemDump {
+    /// Returns the dump as a GitHub-flavored Markdown table, to paste memory snapshots into issues and design docs.
+    ///
+    /// A line with the name, type, size and address of the dump precedes the table, which has a row per byte with the
+    /// `Address`, `Hex`, `Dec`, `Bin` and `ASCII` columns of the tables printed by this crate. The bytes beyond the
+    /// `max_bytes` cap of the global configuration are replaced by a row telling how many were omitted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let dump = MemDump::new("my_u16", "u16", 0x1000, vec![0x45, 0x00]);
+    /// println!("{}", dump.to_markdown());
+    /// ```
+    ///
+    /// # Output
+    ///
+    /// ```none
+    /// **my_u16**: `u16`, 2 bytes at `0000000000001000`
+    ///
+    /// | Address | Hex | Dec | Bin | ASCII |
+    /// |---|---|--:|---|:-:|
+    /// | `0000000000001000` | `45` | 069 | `01000101` | `E` |
+    /// | `0000000000001001` | `00` | 000 | `00000000` | `NUL` |
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::with_capacity((self.bytes.len() + 4) * 64);
+        let _ = writeln!(out, "**{}**: `{}`, {} bytes at `{}`", escape(&self.name), self.type_name, crate::Thousands(self.bytes.len()), crate::_Addr(self.addr));
+        out.push('\n');
+        if self.bytes.is_empty() {
+            out.push_str("There is no memory content to show.\n");
+            return out;
+        }
+        out.push_str("| Address | Hex | Dec | Bin | ASCII |\n|---|---|--:|---|:-:|\n");
+        match crate::_omitted_range(self.bytes.len(), crate::config().max_bytes) {
+            Some(omitted) => {
+                write_rows(&mut out, self, 0..omitted.start);
+                let _ = writeln!(out, "| … {} bytes omitted … | | | | |", crate::Thousands(omitted.len()));
+                write_rows(&mut out, self, omitted.end..self.bytes.len());
+            }
+            None => write_rows(&mut out, self, 0..self.bytes.len()),
+        }
+        out
+    }
+}
+
+/// Writes the rows of the bytes of `dump` in `range`.
+fn write_rows(out: &mut String, dump: &MemDump, range: core::ops::Range<usize>) {
+    for index in range {
+        let cell = dump.bytes[index] as usize;
+        let _ = write!(out, "| `{}` | `{}` | {} | `{}` | ", crate::_Addr(dump.addr + index), HEX[cell], DEC[cell], BIN[cell]);
+        // A pipe ends the cell even in a code span, and a backtick needs a longer span
+        let _ = match ASCII[cell].trim() {
+            "|" => writeln!(out, "`\\|` |"),
+            "`" => writeln!(out, "`` ` `` |"),
+            ascii => writeln!(out, "`{}` |", ascii),
+        };
+    }
+}
+
+/// Escapes the characters of `text` with a meaning in Markdown.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\`*_[]<>|#".contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
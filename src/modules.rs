@@ -0,0 +1,338 @@
+//! Loaded-module tables: the executable and the shared libraries mapped in the process, with their sections.
+//!
+//! Statics and string literals live in the sections of the module that defines them: `.rodata` for the constants and
+//! literals, `.data` for the initialized mutable statics, `.bss` for the zero-initialized ones (`.rdata`, `.data` and
+//! `.bss` on Windows). [`loaded_modules`] lists the modules with the load address of their sections, read from
+//! `/proc/self/maps` and the ELF section headers of their files on Linux, and from the PE headers mapped in memory on
+//! Windows, and [`locate_static`] tells in which section of which module an address is.
+
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+
+/// A section of a loaded module, at its load address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleSection {
+    /// Name of the section, e.g. `.rodata`.
+    pub name: String,
+    /// Address of the first byte of the section in the process.
+    pub addr: usize,
+    /// Size of the section in bytes.
+    pub size: usize,
+}
+
+impl ModuleSection {
+    /// Returns what the section usually holds, for the sections of statics and code.
+    pub fn kind(&self) -> Option<&'static str> {
+        let name = self.name.as_str();
+        match name {
+            ".rodata" | ".rdata" => Some("read-only data: constants and string literals"),
+            ".data" => Some("initialized mutable statics"),
+            ".bss" => Some("zero-initialized statics"),
+            ".tdata" | ".tbss" => Some("initial values of the thread-locals"),
+            ".text" => Some("code"),
+            _ if name.starts_with(".data.rel.ro") => Some("read-only data relocated at load time"),
+            _ if name.starts_with(".rodata") => Some("read-only data"),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if `addr` is in the section.
+    pub fn contains(&self, addr: usize) -> bool {
+        (self.addr..self.addr + self.size).contains(&addr)
+    }
+}
+
+/// A module loaded in the process: the executable or a shared library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadedModule {
+    /// Path of the file of the module.
+    pub path: String,
+    /// Address the module is loaded at, the one of its first mapped byte.
+    pub base: usize,
+    /// Address past the last byte of the module, its sections included.
+    pub end: usize,
+    /// Sections of the module loaded in memory, by address.
+    pub sections: Vec<ModuleSection>,
+}
+
+impl LoadedModule {
+    /// Returns the file name of the module, e.g. `libc.so.6`.
+    pub fn name(&self) -> &str {
+        self.path.rsplit(['/', '\\']).next().unwrap_or(&self.path)
+    }
+
+    /// Returns `true` if `addr` is in the module.
+    pub fn contains(&self, addr: usize) -> bool {
+        (self.base..self.end).contains(&addr)
+    }
+
+    /// Returns the section containing `addr`, if any.
+    pub fn section_at(&self, addr: usize) -> Option<&ModuleSection> {
+        self.sections.iter().find(|section| section.contains(addr))
+    }
+}
+
+/// Where an address is in the loaded modules, see [`locate_static`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticLocation {
+    /// Path of the module containing the address.
+    pub module: String,
+    /// Offset of the address from the base of the module, which doesn't change from run to run.
+    pub module_offset: usize,
+    /// The section containing the address.
+    pub section: ModuleSection,
+    /// Offset of the address from the start of the section.
+    pub section_offset: usize,
+}
+
+impl fmt::Display for StaticLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.module.rsplit(['/', '\\']).next().unwrap_or(&self.module);
+        write!(f, "{} +{:#x}", self.section.name, self.section_offset)?;
+        if let Some(kind) = self.section.kind() {
+            write!(f, " ({})", kind)?;
+        }
+        write!(f, ", {}+{:#x}", name, self.module_offset)
+    }
+}
+
+/// Returns the modules loaded in the process, with their sections.
+///
+/// On Linux the modules are the files mapped in `/proc/self/maps`, their sections are read from the ELF section
+/// headers of the files. On Windows only the module linking this crate (usually the executable) is listed, with the
+/// sections of its PE headers. The list is empty on the other systems, or when the maps can't be read, e.g. under
+/// Miri. The sections of a file are read once and cached.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// for module in loaded_modules() {
+///     println!("{} at {:x}, {} sections", module.name(), module.base, module.sections.len());
+/// }
+/// ```
+pub fn loaded_modules() -> Vec<LoadedModule> {
+    platform::modules()
+}
+
+/// Returns where `addr` is in the loaded modules: the section containing it and its offsets from the section and from
+/// the base of the module, or `None` if it isn't in a section of a module (a local, a heap allocation...).
+///
+/// The address of a static, a constant promoted to a static or a string literal is found in `.rodata`, `.data` or
+/// `.bss`, whose name tells whether it is read-only, initialized or zero-initialized. In Miri-friendly mode nothing is
+/// located, the addresses are hidden.
+///
+/// # Argument
+///
+/// * `addr` - The address to locate.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// static MY_TABLE: [u16; 4] = [1, 2, 4, 8];
+/// if let Some(location) = locate_static(MY_TABLE.as_ptr().addr()) {
+///     println!("{}", location); // .rodata +0x1a0 (read-only data: constants and string literals), my_app+0x4a1a0
+/// }
+/// ```
+pub fn locate_static(addr: usize) -> Option<StaticLocation> {
+    if crate::config().miri_friendly {
+        return None;
+    }
+    loaded_modules().into_iter().find_map(|module| {
+        let section = module.section_at(addr)?.clone();
+        Some(StaticLocation { module_offset: addr - module.base, section_offset: addr - section.addr, section, module: module.path })
+    })
+}
+
+/// Returns the loaded module containing `addr`, if any.
+pub(crate) fn module_at(addr: usize) -> Option<LoadedModule> {
+    loaded_modules().into_iter().find(|module| module.contains(addr))
+}
+
+/// Returns the sections of the file at `path` loaded at `base`, read once with `read`.
+fn cached_sections(path: &str, base: usize, read: impl FnOnce() -> Option<Vec<ModuleSection>>) -> Vec<ModuleSection> {
+    type Cache = Mutex<Vec<(String, usize, Vec<ModuleSection>)>>;
+    static CACHE: OnceLock<Cache> = OnceLock::new();
+    let mut cache = CACHE.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((_, _, sections)) = cache.iter().find(|(cached, at, _)| cached == path && *at == base) {
+        return sections.clone();
+    }
+    let sections = read().unwrap_or_default();
+    cache.push((path.to_string(), base, sections.clone()));
+    sections
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod platform {
+    use std::fs::File;
+    use std::os::unix::fs::FileExt;
+
+    use super::{cached_sections, LoadedModule, ModuleSection};
+
+    /// Flag of the sections occupying memory at run time.
+    const SHF_ALLOC: u64 = 0x2;
+    /// Type of the loadable segments.
+    const PT_LOAD: u32 = 1;
+
+    /// Lists the files mapped in `/proc/self/maps`, in address order.
+    pub(super) fn modules() -> Vec<LoadedModule> {
+        let Ok(maps) = std::fs::read_to_string("/proc/self/maps") else {
+            return Vec::new();
+        };
+        // (path, lowest address, address past the highest mapping, whether a mapping is executable)
+        let mut mapped: Vec<(&str, usize, usize, bool)> = Vec::new();
+        for line in maps.lines() {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            let [range, permissions, offset, _, _, path] = columns[..] else {
+                continue;
+            };
+            let Some((start, end)) = range.split_once('-') else {
+                continue;
+            };
+            let (Ok(start), Ok(end), Ok(offset)) =
+                (usize::from_str_radix(start, 16), usize::from_str_radix(end, 16), usize::from_str_radix(offset, 16))
+            else {
+                continue;
+            };
+            if !path.starts_with('/') {
+                continue;
+            }
+            let executable = permissions.contains('x');
+            // An image starts with the mapping of the start of its file, the other mappings of the file follow it
+            match mapped.iter_mut().rev().find(|(other, _, _, _)| *other == path) {
+                Some((_, low, high, any_executable)) if offset != 0 => {
+                    (*low, *high) = ((*low).min(start), (*high).max(end));
+                    *any_executable |= executable;
+                }
+                _ => mapped.push((path, start, end, executable)),
+            }
+        }
+        // The files mapped as data, e.g. by the `backtrace` crate reading the debug info, have no code
+        mapped.retain(|(_, _, _, executable)| *executable);
+        mapped
+            .into_iter()
+            .map(|(path, base, end, _)| {
+                let sections = cached_sections(path, base, || read_sections(path, base));
+                // `.bss` is mapped anonymously after the file
+                let end = sections.iter().map(|section| section.addr + section.size).max().unwrap_or(0).max(end);
+                LoadedModule { path: path.to_string(), base, end, sections }
+            })
+            .collect()
+    }
+
+    /// Reads the allocated sections of the ELF file at `path`, relocated for a module loaded at `base`.
+    fn read_sections(path: &str, base: usize) -> Option<Vec<ModuleSection>> {
+        let file = File::open(path).ok()?;
+        let read = |offset: u64, len: usize| -> Option<Vec<u8>> {
+            let mut buf = vec![0; len];
+            file.read_exact_at(&mut buf, offset).ok()?;
+            Some(buf)
+        };
+        let header = read(0, 64)?;
+        if header[..4] != *b"\x7fELF" {
+            return None;
+        }
+        let wide = header[4] == 2;
+        let little = header[5] == 1;
+        let field = |bytes: &[u8], at: usize, len: usize| -> u64 {
+            let mut value = [0u8; 8];
+            if little {
+                value[..len].copy_from_slice(&bytes[at..at + len]);
+                u64::from_le_bytes(value)
+            } else {
+                value[8 - len..].copy_from_slice(&bytes[at..at + len]);
+                u64::from_be_bytes(value)
+            }
+        };
+        let word = if wide { 8 } else { 4 };
+        let (phoff, shoff) = if wide { (field(&header, 0x20, 8), field(&header, 0x28, 8)) } else { (field(&header, 0x1c, 4), field(&header, 0x20, 4)) };
+        let at = if wide { 0x36 } else { 0x2a };
+        let (phentsize, phnum) = (field(&header, at, 2) as usize, field(&header, at + 2, 2) as usize);
+        let (shentsize, shnum, shstrndx) = (field(&header, at + 4, 2) as usize, field(&header, at + 6, 2) as usize, field(&header, at + 8, 2) as usize);
+
+        // The load bias: where the first loadable segment was mapped, minus where the file asks for it
+        let segments = read(phoff, phentsize * phnum)?;
+        let first_load = segments.chunks_exact(phentsize).find(|segment| field(segment, 0, 4) as u32 == PT_LOAD)?;
+        let (p_offset, p_vaddr) = if wide { (field(first_load, 8, 8), field(first_load, 16, 8)) } else { (field(first_load, 4, 4), field(first_load, 8, 4)) };
+        let bias = base.wrapping_sub(p_vaddr.wrapping_sub(p_offset) as usize);
+
+        let headers = read(shoff, shentsize * shnum)?;
+        let header_at = |index: usize| headers.get(index * shentsize..(index + 1) * shentsize);
+        // (name, flags, addr, offset, size) of a section header
+        let section = |header: &[u8]| {
+            let flags = field(header, 8, word);
+            let addr = field(header, 8 + word, word);
+            let offset = field(header, 8 + 2 * word, word);
+            let size = field(header, 8 + 3 * word, word);
+            (field(header, 0, 4) as usize, flags, addr, offset, size)
+        };
+        let (_, _, _, names_offset, names_size) = section(header_at(shstrndx)?);
+        let names = read(names_offset, names_size as usize)?;
+
+        let mut sections: Vec<ModuleSection> = (0..shnum)
+            .filter_map(header_at)
+            .map(section)
+            .filter(|&(_, flags, addr, _, size)| flags & SHF_ALLOC != 0 && addr != 0 && size != 0)
+            .map(|(name, _, addr, _, size)| {
+                let name = names.get(name..).unwrap_or_default();
+                let name = &name[..name.iter().position(|&byte| byte == 0).unwrap_or(name.len())];
+                ModuleSection { name: String::from_utf8_lossy(name).into_owned(), addr: bias.wrapping_add(addr as usize), size: size as usize }
+            })
+            .collect();
+        sections.sort_by_key(|section| section.addr);
+        Some(sections)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{cached_sections, LoadedModule, ModuleSection};
+
+    extern "C" {
+        /// The DOS header of the module linking this crate, defined by the MSVC and MinGW linkers.
+        static __ImageBase: u8;
+    }
+
+    /// Lists the module linking this crate, with the sections of its PE headers.
+    pub(super) fn modules() -> Vec<LoadedModule> {
+        let base = unsafe { core::ptr::addr_of!(__ImageBase) }.expose_provenance();
+        // The headers of a loaded module stay mapped and readable, beyond the byte of `__ImageBase`
+        let read = |offset: usize, len: usize| unsafe { core::slice::from_raw_parts(core::ptr::with_exposed_provenance::<u8>(base + offset), len) };
+        let u16_at = |offset: usize| u16::from_le_bytes(read(offset, 2).try_into().unwrap()) as usize;
+        let u32_at = |offset: usize| u32::from_le_bytes(read(offset, 4).try_into().unwrap()) as usize;
+        let pe = u32_at(0x3c);
+        if read(pe, 4) != b"PE\0\0" {
+            return Vec::new();
+        }
+        let (count, optional_size) = (u16_at(pe + 6), u16_at(pe + 20));
+        let optional = pe + 24;
+        let end = base + u32_at(optional + 56);
+        let path = std::env::current_exe().map(|path| path.display().to_string()).unwrap_or_else(|_| String::from("image"));
+        let sections = cached_sections(&path, base, || {
+            let table = optional + optional_size;
+            let sections = (0..count)
+                .map(|index| {
+                    let header = table + index * 40;
+                    let name = read(header, 8);
+                    let name = &name[..name.iter().position(|&byte| byte == 0).unwrap_or(8)];
+                    ModuleSection { name: String::from_utf8_lossy(name).into_owned(), addr: base + u32_at(header + 12), size: u32_at(header + 8) }
+                })
+                .collect();
+            Some(sections)
+        });
+        vec![LoadedModule { path, base, end, sections }]
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", windows)))]
+mod platform {
+    use super::LoadedModule;
+
+    /// The loaded modules aren't listed on this system.
+    pub(super) fn modules() -> Vec<LoadedModule> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,156 @@
+//! Dumps of the `NonZero` integers and `NonNull` pointers, with their niche explained.
+//!
+//! A `NonZero<u32>` is stored as the `u32` itself, and the all-zero bit pattern it can't hold is a niche: the compiler
+//! stores the `None` of an `Option<NonZero<u32>>` there, so the option takes no more bytes than the value. The
+//! [`view_niche!`](crate::view_niche) macro dumps the value, then `Some` of it and `None`, to show it.
+
+use std::fmt::{self, Write};
+use std::num::NonZero;
+use std::ptr::NonNull;
+
+use crate::render::{self, Table, MEMORY_CONTENT};
+
+/// Separator line of the tables of `Some` of the value.
+const SOME_VALUE: &str = "----------------------Some(value)-----------------------";
+
+/// Separator line of the tables of `None`.
+const NONE_NICHE: &str = "---------------------None (niche)-----------------------";
+
+/// Returns the bytes of a value without padding.
+fn value_bytes<T>(value: &T) -> &[u8] {
+    unsafe { core::slice::from_raw_parts(value as *const T as *const u8, core::mem::size_of::<T>()) }
+}
+
+/// Formats a value with a niche, its decoded value written by `decoded` unless `redact` is set, then `Some` of it and
+/// `None`.
+fn write_niche<T: Copy>(out: &mut String, name: &str, value: &T, decoded: &dyn fmt::Display, redact: bool) -> fmt::Result {
+    let size = core::mem::size_of::<T>();
+    let option = core::mem::size_of::<Option<T>>();
+    let addr = (value as *const T).addr();
+    writeln!(out, "Name: {}", name)?;
+    writeln!(out, "Type: {}", core::any::type_name::<T>())?;
+    writeln!(out, "Addr: {}", crate::_HeaderAddr(addr))?;
+    writeln!(out, "Size: {} bytes", size)?;
+    if redact {
+        writeln!(out, "Valu: redacted")?;
+    } else {
+        writeln!(out, "Valu: {}, stored as is", decoded)?;
+    }
+    writeln!(out, "Nich: the all-zero bit pattern is forbidden, it is left as a niche for the None of {}", core::any::type_name::<Option<T>>())?;
+    if option == size {
+        writeln!(out, "      which takes {} bytes too: Some(value) is stored as the value, None as zeros", option)?;
+    } else {
+        writeln!(out, "      which takes {} bytes, the niche isn't used", option)?;
+    }
+    if crate::config().miri_friendly {
+        return render::write_miri_note(out);
+    }
+
+    Table { label: name, redact, ..Table::new(MEMORY_CONTENT, addr, value_bytes(value)) }.write(out)?;
+    // Option<T> has no padding either when the niche is used, its bytes are those of the value or zeros
+    if option != size {
+        return Ok(());
+    }
+    let some = Some(*value);
+    let some_bytes = value_bytes(&some);
+    let annotate = |offset: usize, out: &mut dyn fmt::Write| if offset == 0 { out.write_str("Some(value)") } else { Ok(()) };
+    Table { label: name, annotate: Some(&annotate), redact, ..Table::new(SOME_VALUE, some_bytes.as_ptr().addr(), some_bytes) }.write(out)?;
+    let none: Option<T> = None;
+    let none_bytes = value_bytes(&none);
+    let annotate = |offset: usize, out: &mut dyn fmt::Write| if offset == 0 { out.write_str("None") } else { Ok(()) };
+    Table { label: name, annotate: Some(&annotate), redact, ..Table::new(NONE_NICHE, none_bytes.as_ptr().addr(), none_bytes) }.write(out)
+}
+
+/// A value with a zero niche shown by [`view_niche!`](crate::view_niche): a `NonZero` integer or a `NonNull` pointer.
+///
+/// (This is supposed to be private usage of view_niche! macro usage.)
+pub trait _Niche {
+    /// Formats the value, `Some` of it and `None`, without the decoded value if `redact` is set.
+    fn write_niche(&self, out: &mut String, name: &str, redact: bool) -> fmt::Result;
+}
+
+macro_rules! nonzero_niches {
+    ($($ty: ty),*) => {$(
+        impl _Niche for NonZero<$ty> {
+            fn write_niche(&self, out: &mut String, name: &str, redact: bool) -> fmt::Result {
+                let value = self.get();
+                let decoded = format!("{} ({:#0width$x})", value, value, width = 2 + 2 * core::mem::size_of::<$ty>());
+                write_niche(out, name, self, &decoded, redact)
+            }
+        }
+    )*};
+}
+
+nonzero_niches!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl<T> _Niche for NonNull<T> {
+    fn write_niche(&self, out: &mut String, name: &str, redact: bool) -> fmt::Result {
+        write_niche(out, name, self, &crate::_Addr(self.as_ptr().addr()), redact)
+    }
+}
+
+#[macro_export]
+/// Macro to view a `NonZero` integer or a `NonNull` pointer, with its niche explained.
+///
+/// The value is decoded on a `Valu` line: it is stored as is, the type only forbids the all-zero bit pattern. A `Nich`
+/// line tells that the compiler uses this pattern for the `None` of an `Option` of the type, which is why the option
+/// takes no more bytes. The bytes of the value follow, then those of `Some` of it, the same, and those of `None`, all
+/// zeros. When [`Config::redact`](crate::Config::redact) is set, the `Valu` line reads `Valu: redacted`. In
+/// Miri-friendly mode the memory content isn't shown.
+///
+/// # Argument
+///
+/// * `var` - The `NonZero` integer or `NonNull` pointer to dump.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+/// use std::num::NonZero;
+///
+/// let my_id = NonZero::new(42u16).unwrap();
+/// view_niche!(my_id);
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_id
+/// Type: core::num::nonzero::NonZero<u16>
+/// Addr: 00007ffe1b4c5a2e
+/// Size: 2 bytes
+/// Valu: 42 (0x002a), stored as is
+/// Nich: the all-zero bit pattern is forbidden, it is left as a niche for the None of core::option::Option<core::num::nonzero::NonZero<u16>>
+///       which takes 2 bytes too: Some(value) is stored as the value, None as zeros
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8
+/// ----------------------Memory Content--------------------
+///  00007ffe1b4c5a2e | 2a  | 042 | 00101010 |   *   | XXX
+///  00007ffe1b4c5a2f | 00  | 000 | 00000000 |  NUL  | XXX
+///
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ----------------------Some(value)-----------------------
+///  00007ffe1b4c58f6 | 2a  | 042 | 00101010 |   *   | XXX | Some(value)
+///  00007ffe1b4c58f7 | 00  | 000 | 00000000 |  NUL  | XXX |
+///
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ---------------------None (niche)-----------------------
+///  00007ffe1b4c58f8 | 00  | 000 | 00000000 |  NUL  | XXX | None
+///  00007ffe1b4c58f9 | 00  | 000 | 00000000 |  NUL  | XXX |
+/// ```
+macro_rules! view_niche {
+    ($var: expr) => {
+        $crate::_show_niche(stringify!($var), &$var)
+    };
+}
+
+/// Displays a value with a zero niche, `Some` of it and `None`.
+///
+/// (This is supposed to be private usage of view_niche! macro usage.)
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `value` - The `NonZero` integer or `NonNull` pointer to display.
+pub fn _show_niche<N: _Niche>(name: &str, value: &N) {
+    render::emit(|out| value.write_niche(out, name, crate::config().redact));
+}
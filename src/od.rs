@@ -0,0 +1,64 @@
+//! The memory content tables in the format of `od -A x -t x1z`.
+//!
+//! Selected with [`Preset::Od`](crate::Preset::Od), the rows are the same as those of GNU and BSD `od` for the same
+//! bytes, so a dump can be compared with the output of `od` in scripts and CI logs: hex offsets from the start of the
+//! region, 16 hex bytes per row, the printable ASCII characters between `>` and `<`, repeated rows replaced by `*`, and
+//! the size of the region on the last line.
+
+use core::fmt;
+
+use crate::render::{Table, HEX};
+
+/// Number of bytes per row.
+const ROW_LEN: usize = 16;
+
+/// Writes the row of up to 16 bytes at `offset`.
+fn write_row<W: fmt::Write + ?Sized>(out: &mut W, offset: usize, row: &[u8]) -> fmt::Result {
+    write!(out, "{:06x}", offset)?;
+    for byte in row {
+        write!(out, " {}", HEX[*byte as usize])?;
+    }
+    // The characters are aligned with those of the full rows
+    for _ in row.len()..ROW_LEN {
+        out.write_str("   ")?;
+    }
+    out.write_str("  >")?;
+    for byte in row {
+        out.write_char(if (0x20..0x7f).contains(byte) { *byte as char } else { '.' })?;
+    }
+    writeln!(out, "<")
+}
+
+/// Writes the rows of the bytes in `range`, replacing the rows repeating the previous one with a `*` line.
+fn write_rows<W: fmt::Write + ?Sized>(out: &mut W, bytes: &[u8], range: core::ops::Range<usize>) -> fmt::Result {
+    let mut previous: Option<&[u8]> = None;
+    let mut squeezed = false;
+    for start in range.clone().step_by(ROW_LEN) {
+        let row = &bytes[start..(start + ROW_LEN).min(range.end)];
+        if previous == Some(row) {
+            if !squeezed {
+                writeln!(out, "*")?;
+                squeezed = true;
+            }
+            continue;
+        }
+        write_row(out, start, row)?;
+        previous = Some(row);
+        squeezed = false;
+    }
+    Ok(())
+}
+
+/// Formats a table in the format of `od -A x -t x1z`, the bytes omitted by `max_bytes` being replaced by a line.
+pub(crate) fn write_table<W: fmt::Write + ?Sized>(out: &mut W, table: &Table<'_>) -> fmt::Result {
+    let bytes = table.bytes;
+    match crate::_omitted_range(bytes.len(), table.max_bytes) {
+        Some(omitted) => {
+            write_rows(out, bytes, 0..omitted.start)?;
+            writeln!(out, "… {} bytes omitted …", crate::Thousands(omitted.len()))?;
+            write_rows(out, bytes, omitted.end..bytes.len())?;
+        }
+        None => write_rows(out, bytes, 0..bytes.len())?,
+    }
+    writeln!(out, "{:06x}", bytes.len())
+}
@@ -0,0 +1,165 @@
+//! Dumps read back from their text.
+//!
+//! [`MemDump::parse`] reads the tables printed by this crate and the output of `xxd`, e.g. dumps pasted from logs, so
+//! they can be compared with [`MemDiff`](crate::MemDiff) or checked in round-trip tests.
+
+use core::fmt;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::dump::MemDump;
+
+/// Error of [`MemDump::parse`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DumpParseError {
+    /// Line of the invalid row, from 1.
+    pub line: usize,
+    /// What is wrong with the row.
+    pub message: &'static str,
+}
+
+impl fmt::Display for DumpParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid dump at line {}: {}", self.line, self.message)
+    }
+}
+
+impl core::error::Error for DumpParseError {}
+
+impl MemDump {
+    /// Reads a dump printed by this crate (`view_mem!`, `write_mem`, `MemDump` display…) or by `xxd` back into bytes.
+    ///
+    /// The name comes from the `Name` line of the header, if any, and the address from the first row. Only the first
+    /// dump of the text is read: it ends at the `Name` line of the next one. The type name isn't read back, the
+    /// dump is a `[u8]`. ANSI colors of dumps copied from a terminal are ignored. A table whose middle was omitted by
+    /// `max_bytes` can't be read back, nor can the rows of `xxd` whose offsets don't follow each other.
+    ///
+    /// # Argument
+    ///
+    /// * `text` - The text of the dump.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let my_u16: u16 = 69;
+    /// let mut text = String::new();
+    /// write_mem(&mut text, "my_u16", &my_u16).unwrap();
+    /// assert_eq!(MemDump::parse(&text).unwrap().bytes, my_u16.to_ne_bytes());
+    ///
+    /// let xxd = "00000010: 4865 6c6c 6f                             Hello\n";
+    /// let dump = MemDump::parse(xxd).unwrap();
+    /// assert_eq!((dump.addr, dump.bytes.as_slice()), (0x10, &b"Hello"[..]));
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, DumpParseError> {
+        let mut name = None;
+        let mut start = None;
+        let mut bytes = Vec::new();
+        let mut empty = false;
+        for (index, line) in text.lines().enumerate() {
+            let error = |message| DumpParseError { line: index + 1, message };
+            let line = strip_ansi(line);
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            if trimmed.starts_with("Empty:") {
+                empty = true;
+                continue;
+            }
+            if trimmed.starts_with('…') && trimmed.ends_with("omitted …") {
+                return Err(error("bytes were omitted from the table by `max_bytes`"));
+            }
+
+            if let Some((addr, row)) = classic_row(trimmed) {
+                let row = row.ok_or(error("a row has an address and a byte in hex"))?;
+                push_row(&mut start, &mut bytes, addr, &[row]).ok_or(error("the address doesn't follow the previous row"))?;
+            } else if let Some((offset, row)) = xxd_row(trimmed) {
+                let row = row.ok_or(error("an xxd row has groups of hex digit pairs"))?;
+                push_row(&mut start, &mut bytes, offset, &row).ok_or(error("the offset doesn't follow the previous row"))?;
+            } else if let Some((key, value)) = trimmed.split_once(':') {
+                if key.trim() == "Name" {
+                    // The header of the next dump
+                    if start.is_some() || empty {
+                        break;
+                    }
+                    name.get_or_insert_with(|| String::from(value.trim()));
+                }
+            }
+        }
+        let Some(addr) = start.or(empty.then_some(0)) else {
+            return Err(DumpParseError { line: text.lines().count().max(1), message: "no rows found" });
+        };
+        Ok(MemDump::new(name.unwrap_or_default(), "[u8]", addr, bytes))
+    }
+}
+
+/// Returns `line` without its ANSI escape sequences.
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // CSI sequences end with a letter, e.g. `ESC [ 2 m`
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Returns the address of a row of the tables of this crate (`address | hex | …`) and its byte, `None` if the line
+/// isn't such a row.
+fn classic_row(line: &str) -> Option<(usize, Option<u8>)> {
+    let mut fields = line.split('|').map(str::trim);
+    let addr = usize::from_str_radix(fields.next()?, 16).ok()?;
+    let byte = fields.next()?;
+    fields.next()?;
+    Some((addr, hex_byte(byte.as_bytes())))
+}
+
+/// Returns the offset of an `xxd` row (`offset: hex groups  characters`) and its bytes, `None` if the line isn't such
+/// a row.
+fn xxd_row(line: &str) -> Option<(usize, Option<Vec<u8>>)> {
+    let (offset, rest) = line.split_once(": ")?;
+    if offset.len() < 4 {
+        return None;
+    }
+    let offset = usize::from_str_radix(offset, 16).ok()?;
+    // The characters are separated from the hex groups by two spaces, even after a short last row
+    let hex = rest.split_once("  ").map_or(rest, |(hex, _)| hex);
+    let mut bytes = Vec::new();
+    for group in hex.split(' ') {
+        if group.is_empty() || !group.len().is_multiple_of(2) {
+            return Some((offset, None));
+        }
+        for pair in group.as_bytes().chunks(2) {
+            match hex_byte(pair) {
+                Some(byte) => bytes.push(byte),
+                None => return Some((offset, None)),
+            }
+        }
+    }
+    Some((offset, Some(bytes)))
+}
+
+/// Returns the byte written as exactly two hex digits.
+fn hex_byte(digits: &[u8]) -> Option<u8> {
+    match digits {
+        [high, low] => Some((char::from(*high).to_digit(16)? * 16 + char::from(*low).to_digit(16)?) as u8),
+        _ => None,
+    }
+}
+
+/// Appends the bytes of a row at `addr`, which must follow the previous rows.
+fn push_row(start: &mut Option<usize>, bytes: &mut Vec<u8>, addr: usize, row: &[u8]) -> Option<()> {
+    let start = *start.get_or_insert(addr);
+    (start.checked_add(bytes.len()) == Some(addr)).then(|| bytes.extend_from_slice(row))
+}
@@ -0,0 +1,196 @@
+//! Dumps of pinned values, with the pointers of a self-referential value into itself found and labelled.
+//!
+//! A value is pinned so that the pointers it holds into itself stay valid: it can't move until it is dropped. The
+//! [`view_pin!`](crate::view_pin) macro dumps the `Pin` wrapper, then the value it points to, and lists the aligned
+//! words of the value whose value is an address inside the value itself, naming the fields when the layout of the
+//! struct is given as to [`layout_of!`](crate::layout_of).
+
+use std::fmt::{self, Write};
+use std::ops::Deref;
+use std::pin::Pin;
+
+use crate::containers::{self, Field, HANDLE};
+use crate::layout::{self, StructLayout};
+use crate::render::{self, Table};
+
+/// Separator line of the tables of the pinned values.
+const PINNED_VALUE: &str = "----------------------Pinned Value----------------------";
+
+/// Returns the self-references of the value at `addr` made of `bytes`: the offsets of its aligned words holding an
+/// address inside the value, with the offset they point to.
+pub(crate) fn self_references(addr: usize, bytes: &[u8]) -> Vec<(usize, usize)> {
+    const WORD: usize = core::mem::size_of::<usize>();
+    let first = addr.next_multiple_of(WORD) - addr;
+    let end = addr + bytes.len();
+    (first..bytes.len().saturating_sub(WORD - 1))
+        .step_by(WORD)
+        .filter_map(|offset| {
+            let word = usize::from_ne_bytes(bytes[offset..offset + WORD].try_into().unwrap());
+            (addr..end).contains(&word).then(|| (offset, word - addr))
+        })
+        .collect()
+}
+
+/// Writes where the byte at `offset` of a value is: `+0x10`, followed by the name of the field containing it, if any.
+fn write_place(layout: Option<&StructLayout>, offset: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+    write!(out, "+{:#x}", offset)?;
+    match layout.and_then(|layout| layout.field_at(offset)) {
+        Some(field) if offset == field.offset => write!(out, " ({})", field.name),
+        Some(field) => write!(out, " ({} +{})", field.name, offset - field.offset),
+        None => Ok(()),
+    }
+}
+
+/// Writes the annotation of the byte at `offset` of a pinned value: the target of a self-reference, else the field
+/// containing it when the layout is known.
+fn annotate(layout: Option<&StructLayout>, references: &[(usize, usize)], offset: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+    const WORD: usize = core::mem::size_of::<usize>();
+    if let Some(&(_, target)) = references.iter().find(|(start, _)| *start == offset) {
+        if let Some(field) = layout.and_then(|layout| layout.field_at(offset)) {
+            write!(out, "{}: ", field.name)?;
+        }
+        out.write_str("self-reference → ")?;
+        return write_place(layout, target, out);
+    }
+    if let Some(&(start, _)) = references.iter().find(|(start, _)| (*start..*start + WORD).contains(&offset)) {
+        if layout.is_none() {
+            return write!(out, "self-reference +{}", offset - start);
+        }
+    }
+    match layout {
+        Some(layout) => layout::annotate(layout, offset, out),
+        None => Ok(()),
+    }
+}
+
+/// Formats the `Pin` wrapper made of `handle`, the value it points to and its self-references.
+pub(crate) fn write_pin<T>(out: &mut String, name: &str, type_name: &str, handle: &[u8], value: &T, layout: Option<&StructLayout>) -> fmt::Result {
+    let ptr = value as *const T as *const u8;
+    let size = core::mem::size_of::<T>();
+    containers::write_header(out, name, type_name, handle)?;
+    let aloc = if crate::_likely_stack(ptr.addr()) { "Likely Stack" } else { "Likely Heap" };
+    writeln!(out, "Pinn: {} ({}), {} bytes of {}, won't move until dropped", crate::_HeaderAddr(ptr.addr()), aloc, size, core::any::type_name::<T>())?;
+    if crate::config().miri_friendly {
+        return render::write_miri_note(out);
+    }
+
+    let bytes = unsafe { core::slice::from_raw_parts(ptr, size) };
+    let references = self_references(ptr.addr(), bytes);
+    if references.is_empty() {
+        writeln!(out, "Self: none, no word of the value points into it")?;
+    }
+    for (index, &(offset, target)) in references.iter().enumerate() {
+        out.write_str(if index == 0 { "Self: " } else { "      " })?;
+        write_place(layout, offset, out)?;
+        out.write_str(" → ")?;
+        write_place(layout, target, out)?;
+        writeln!(out)?;
+    }
+
+    let fields: [Field; 1] = [("ptr", ptr.addr(), true)];
+    let annotate_handle = |offset: usize, out: &mut dyn fmt::Write| containers::annotate_handle(handle, &fields, offset, out);
+    Table { label: name, annotate: Some(&annotate_handle), ..Table::new(HANDLE, handle.as_ptr().addr(), handle) }.write(out)?;
+    if size == 0 {
+        return render::write_zst_note(out, ptr.addr());
+    }
+    let annotate = |offset: usize, out: &mut dyn fmt::Write| annotate(layout, &references, offset, out);
+    Table { label: name, annotate: Some(&annotate), ..Table::new(PINNED_VALUE, ptr.addr(), bytes) }.write(out)
+}
+
+/// A pinned pointer shown by [`view_pin!`](crate::view_pin): `Pin<Box<T>>`, `Pin<&mut T>`, `Pin<&T>`, ...
+///
+/// (This is supposed to be private usage of view_pin! macro usage.)
+pub trait _Pinned {
+    /// Formats the wrapper, the pinned value and its self-references.
+    fn write_pin(&self, out: &mut String, name: &str, layout: Option<&StructLayout>) -> fmt::Result;
+}
+
+impl<P: Deref> _Pinned for Pin<P>
+where
+    P::Target: Sized,
+{
+    fn write_pin(&self, out: &mut String, name: &str, layout: Option<&StructLayout>) -> fmt::Result {
+        let value: &P::Target = self;
+        write_pin(out, name, core::any::type_name::<Pin<P>>(), containers::handle_bytes(self), value, layout)
+    }
+}
+
+#[macro_export]
+/// Macro to view a pinned pointer and the value it pins, with the pointers of the value into itself labelled.
+///
+/// The `Pin` wrapper is dumped with its pointer named, then the pinned value. The aligned words of the value holding
+/// an address inside the value itself are listed on `Self` lines with the offset they point to, and annotated in the
+/// table: these self-references are why the value is pinned, a move would leave them pointing to the old place. The
+/// struct and its fields can follow the pin, as given to [`layout_of!`](crate::layout_of), to name the fields of the
+/// self-references and annotate the others. In Miri-friendly mode the memory content isn't shown.
+///
+/// # Arguments
+///
+/// * `var` - The `Pin<Box<T>>`, `Pin<&mut T>` or other pinned pointer to dump.
+/// * `layout` - Optionally, the struct pinned and its fields, e.g. `Parser { text, cursor }`.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+/// use std::marker::PhantomPinned;
+///
+/// struct Parser {
+///     text: [u8; 8],
+///     cursor: *const u8,
+///     _pinned: PhantomPinned,
+/// }
+///
+/// let mut my_parser = Box::pin(Parser { text: *b"mem_view", cursor: std::ptr::null(), _pinned: PhantomPinned });
+/// unsafe {
+///     let parser = my_parser.as_mut().get_unchecked_mut();
+///     parser.cursor = parser.text.as_ptr().add(4);
+/// }
+/// view_pin!(my_parser, Parser { text, cursor });
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_parser
+/// Type: core::pin::Pin<alloc::boxed::Box<rust_out::main::Parser>>
+/// Addr: 00007ffc1d2e3a48
+/// Size: 8 bytes
+/// Aloc: Likely Stack (handle)
+/// Pinn: 000055d7e2b5eba0 (Likely Heap), 16 bytes of rust_out::main::Parser, won't move until dropped
+/// Self: +0x8 (cursor) → +0x4 (text +4)
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// -------------------------Handle-------------------------
+///  00007ffc1d2e3a48 | a0  | 160 | 10100000 |  ...  | ... | ptr = 000055d7e2b5eba0
+/// ...
+///
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ----------------------Pinned Value----------------------
+///  000055d7e2b5eba0 | 6d  | 109 | 01101101 |   m   | mem_ | text: [u8; 8]
+///  000055d7e2b5eba1 | 65  | 101 | 01100101 |   e   | em_v | text +1
+/// ...
+///  000055d7e2b5eba8 | a4  | 164 | 10100100 |  ...  | ... | cursor: self-reference → +0x4 (text +4)
+///  000055d7e2b5eba9 | eb  | 235 | 11101011 |  ...  | ... | cursor +1
+/// ...
+/// ```
+macro_rules! view_pin {
+    ($var: expr) => {
+        $crate::_show_pin(stringify!($var), &$var, None)
+    };
+    ($var: expr, $($layout: tt)*) => {
+        $crate::_show_pin(stringify!($var), &$var, Some(&$crate::layout_of!($($layout)*)))
+    };
+}
+
+/// Displays a pinned pointer, the value it pins and its self-references.
+///
+/// (This is supposed to be private usage of view_pin! macro usage.)
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `pin` - The pinned pointer to display.
+/// * `layout` - The layout of the pinned struct, if given.
+pub fn _show_pin<P: _Pinned>(name: &str, pin: &P, layout: Option<&StructLayout>) {
+    render::emit(|out| pin.write_pin(out, name, layout));
+}
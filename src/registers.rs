@@ -0,0 +1,237 @@
+//! Peripheral register maps of memory-mapped regions.
+//!
+//! Embedded users describe the registers of a peripheral (usually generated from its SVD file) as static tables of
+//! [`Register`]s and [`BitField`]s. A [`RegisterMap`] dumps an MMIO region with every register labeled at its offset,
+//! its value, and the value of each of its bit fields with its meaning.
+
+use core::fmt;
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use crate::dump::read_volatile_bytes;
+use crate::render::{Table, MEMORY_CONTENT};
+
+/// A bit field of a register, e.g. the `UE` (USART enable) bit 13 of `CR1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitField {
+    /// Name of the field.
+    pub name: &'static str,
+    /// Position of the least significant bit of the field.
+    pub lsb: u8,
+    /// Number of bits of the field.
+    pub width: u8,
+    /// Meanings of the values of the field, e.g. `(0, "8 data bits")`, can be empty.
+    pub values: Cow<'static, [(u64, &'static str)]>,
+}
+
+impl BitField {
+    /// Describes a field of `width` bits starting at bit `lsb`.
+    pub const fn new(name: &'static str, lsb: u8, width: u8) -> Self {
+        BitField { name, lsb, width, values: Cow::Borrowed(&[]) }
+    }
+
+    /// Adds the meanings of the values of the field.
+    pub const fn with_values(mut self, values: &'static [(u64, &'static str)]) -> Self {
+        // Const fns can't run the destructor of the replaced `Cow`, which is borrowed anyway
+        core::mem::forget(core::mem::replace(&mut self.values, Cow::Borrowed(values)));
+        self
+    }
+
+    /// Returns the value of the field in the register value `register`.
+    pub fn extract(&self, register: u64) -> u64 {
+        let mask = if self.width >= 64 { u64::MAX } else { (1 << self.width) - 1 };
+        register.checked_shr(self.lsb as u32).unwrap_or(0) & mask
+    }
+}
+
+/// A register of a memory-mapped peripheral.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Register {
+    /// Name of the register, e.g. `USART1.CR1`.
+    pub name: &'static str,
+    /// Address of the register.
+    pub addr: usize,
+    /// Size of the register in bytes, 1, 2, 4 or 8.
+    pub size: usize,
+    /// Bit fields of the register, can be empty.
+    pub fields: Cow<'static, [BitField]>,
+}
+
+impl Register {
+    /// Describes a register of `size` bytes at `addr`, without bit fields.
+    pub const fn new(name: &'static str, addr: usize, size: usize) -> Self {
+        Register { name, addr, size, fields: Cow::Borrowed(&[]) }
+    }
+
+    /// Adds the bit fields of the register.
+    pub const fn with_fields(mut self, fields: &'static [BitField]) -> Self {
+        // Const fns can't run the destructor of the replaced `Cow`, which is borrowed anyway
+        core::mem::forget(core::mem::replace(&mut self.fields, Cow::Borrowed(fields)));
+        self
+    }
+
+    fn contains(&self, addr: usize) -> bool {
+        addr >= self.addr && addr < self.addr + self.size
+    }
+}
+
+/// Labels dumps of memory-mapped regions with the registers they contain.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// static USART_CR1: [BitField; 3] = [
+///     BitField::new("UE", 0, 1),
+///     BitField::new("M", 12, 1).with_values(&[(0, "8 data bits"), (1, "9 data bits")]),
+///     BitField::new("OVER8", 15, 1),
+/// ];
+///
+/// // The peripheral, e.g. at 0x4001_1000 on a microcontroller
+/// let my_usart: [u32; 2] = [0x0000_1001, 0x0000_0683];
+/// let ptr = my_usart.as_ptr().cast::<u8>();
+/// let base = ptr.addr();
+///
+/// let map = RegisterMap::new([
+///     Register::new("USART1.CR1", base, 4).with_fields(&USART_CR1),
+///     Register::new("USART1.BRR", base + 4, 4),
+/// ]);
+/// unsafe { map.view("my_usart", ptr, 8) };
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_usart
+/// Type: registers
+/// Addr: 00007ffc1bd3c5d8
+/// Size: 8 bytes
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ----------------------Memory Content--------------------
+///  00007ffc1bd3c5d8 | 01  | 001 | 00000001 |  SOH  | ... | USART1.CR1 = 0x00001001: UE=1, M=1 (9 data bits), OVER8=0
+///  00007ffc1bd3c5d9 | 10  | 016 | 00010000 |  DLE  | ... | USART1.CR1 +1
+///  00007ffc1bd3c5da | 00  | 000 | 00000000 |  NUL  | ... | USART1.CR1 +2
+///  00007ffc1bd3c5db | 00  | 000 | 00000000 |  NUL  | ... | USART1.CR1 +3
+///  00007ffc1bd3c5dc | 83  | 131 | 10000011 |  ...  | XXX | USART1.BRR = 0x00000683
+///  00007ffc1bd3c5dd | 06  | 006 | 00000110 |  ACK  | XXX | USART1.BRR +1
+///  00007ffc1bd3c5de | 00  | 000 | 00000000 |  NUL  | XXX | USART1.BRR +2
+///  00007ffc1bd3c5df | 00  | 000 | 00000000 |  NUL  | XXX | USART1.BRR +3
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RegisterMap {
+    registers: Vec<Register>,
+}
+
+impl RegisterMap {
+    /// Creates a map of the given registers.
+    pub fn new(registers: impl IntoIterator<Item = Register>) -> Self {
+        let mut map = RegisterMap::default();
+        for register in registers {
+            map.add(register);
+        }
+        map
+    }
+
+    /// Adds a register to the map.
+    pub fn add(&mut self, register: Register) -> &mut Self {
+        let index = self.registers.partition_point(|other| other.addr < register.addr);
+        self.registers.insert(index, register);
+        self
+    }
+
+    /// Returns the register containing the byte at `addr`, if any.
+    pub fn register_at(&self, addr: usize) -> Option<&Register> {
+        let index = self.registers.partition_point(|register| register.addr <= addr);
+        self.registers[..index].iter().rev().find(|register| register.contains(addr))
+    }
+
+    /// Writes the annotated dump of the memory-mapped region of `len` bytes at `ptr` into `out`.
+    ///
+    /// Every byte is read exactly once with a volatile read, see
+    /// [`MemDump::from_raw_volatile`](crate::MemDump::from_raw_volatile). When
+    /// [`Config::redact`](crate::Config::redact) is set, the registers are annotated with their names only, without
+    /// their values and fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the dump.
+    /// * `name` - The name displayed in the header.
+    /// * `ptr` - The address of the first byte of the region.
+    /// * `len` - The number of bytes to display.
+    ///
+    /// # Safety
+    ///
+    /// The whole region must be readable with byte-wide accesses.
+    pub unsafe fn write<W: fmt::Write + ?Sized>(&self, out: &mut W, name: &str, ptr: *const u8, len: usize) -> fmt::Result {
+        let bytes = read_volatile_bytes(ptr, len);
+        crate::write::write_header(out, name, "registers", ptr.addr(), len)?;
+        self.write_table(out, ptr.addr(), &bytes, crate::config().redact)
+    }
+
+    /// Displays the annotated dump of the memory-mapped region of `len` bytes at `ptr`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name displayed in the header.
+    /// * `ptr` - The address of the first byte of the region.
+    /// * `len` - The number of bytes to display.
+    ///
+    /// # Safety
+    ///
+    /// The whole region must be readable with byte-wide accesses.
+    #[cfg(feature = "std")]
+    pub unsafe fn view(&self, name: &str, ptr: *const u8, len: usize) {
+        let bytes = read_volatile_bytes(ptr, len);
+        crate::render::emit(|out| {
+            crate::write::write_header(out, name, "registers", ptr.addr(), len)?;
+            self.write_table(out, ptr.addr(), &bytes, crate::config().redact)
+        });
+    }
+
+    /// Writes the name of the register containing the byte at `addr`, followed by the offset of the byte in the
+    /// register if it isn't the first one. Writes nothing outside of the registers.
+    pub(crate) fn write_label(&self, addr: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+        match self.register_at(addr) {
+            Some(register) if register.addr == addr => out.write_str(register.name),
+            Some(register) => write!(out, "{} +{}", register.name, addr - register.addr),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes the table of the region, the registers annotated with their values unless `redact` is set.
+    pub(crate) fn write_table<W: fmt::Write + ?Sized>(&self, out: &mut W, addr: usize, bytes: &[u8], redact: bool) -> fmt::Result {
+        let annotate = |index: usize, out: &mut dyn fmt::Write| match self.register_at(addr + index) {
+            Some(register) if register.addr == addr + index && !redact => write_register(out, register, bytes.get(index..index + register.size)),
+            _ => self.write_label(addr + index, out),
+        };
+        let mut table = Table { redact, ..Table::new(MEMORY_CONTENT, addr, bytes) };
+        table.annotate = Some(&annotate);
+        table.write_to(out)
+    }
+}
+
+/// Writes the name of a register, and its value with the values of its fields if it was fully captured.
+fn write_register(out: &mut dyn fmt::Write, register: &Register, bytes: Option<&[u8]>) -> fmt::Result {
+    out.write_str(register.name)?;
+    let Some(bytes) = bytes.filter(|bytes| bytes.len() <= 8) else {
+        return Ok(());
+    };
+    let mut value = [0; 8];
+    if cfg!(target_endian = "little") {
+        value[..bytes.len()].copy_from_slice(bytes);
+    } else {
+        value[8 - bytes.len()..].copy_from_slice(bytes);
+    }
+    let value = u64::from_ne_bytes(value);
+    write!(out, " = {:#0width$x}", value, width = bytes.len() * 2 + 2)?;
+    for (index, field) in register.fields.iter().enumerate() {
+        let field_value = field.extract(value);
+        write!(out, "{}{}={}", if index == 0 { ": " } else { ", " }, field.name, field_value)?;
+        if let Some((_, meaning)) = field.values.iter().find(|(value, _)| *value == field_value) {
+            write!(out, " ({})", meaning)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,722 @@
+//! Formatting of the memory content tables.
+//!
+//! Tables are formatted into a single `String` and written to stdout with one `write_all` on the locked handle,
+//! instead of one `println!` (lock, format, flush) per byte. The cells come from static lookup tables and the
+//! buffer is reused, so formatting a table doesn't allocate.
+//!
+//! The formatting itself only needs `core`: every table can be written into any `fmt::Write`, only the stdout part
+//! requires `std`.
+
+use core::fmt;
+
+use alloc::string::String;
+#[cfg(feature = "rayon")]
+use alloc::vec::Vec;
+
+use crate::checksum::{write_checksums, Checksum};
+use crate::config::{AddressStyle, Preset};
+use crate::elements::ElementType;
+use crate::transform::Transform;
+use crate::words::Word;
+
+/// Names of the control characters (and space) in the ASCII column, indexed by byte value.
+const CONTROL_NAMES: [&str; 33] = [
+    "NUL", "SOH", "STX", "ETX", "EOT", "ENQ", "ACK", "BEL", "BS ", "HT ", "LF ", "VT ", "FF ", "CR ", "SO ", "SI ",
+    "DLE", "DC1", "DC2", "DC3", "DC4", "NAK", "SYN", "ETB", "CAN", "EM ", "SUB", "ESC", "FS ", "GS ", "RS ", "US ",
+    "SPC",
+];
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+const fn ascii_cells() -> [[u8; 3]; 256] {
+    let mut cells = [*b"..."; 256];
+    let mut i = 0;
+    while i < 256 {
+        let byte = i as u8;
+        if byte.is_ascii_graphic() {
+            cells[i] = [b' ', byte, b' '];
+        } else if i < CONTROL_NAMES.len() {
+            let name = CONTROL_NAMES[i].as_bytes();
+            cells[i] = [name[0], name[1], name[2]];
+        } else if i == 127 {
+            cells[i] = *b"DEL";
+        }
+        i += 1;
+    }
+    cells
+}
+
+const fn hex_cells() -> [[u8; 2]; 256] {
+    let mut cells = [[0; 2]; 256];
+    let mut i = 0;
+    while i < 256 {
+        cells[i] = [HEX_DIGITS[i >> 4], HEX_DIGITS[i & 0xf]];
+        i += 1;
+    }
+    cells
+}
+
+const fn dec_cells() -> [[u8; 3]; 256] {
+    let mut cells = [[0; 3]; 256];
+    let mut i = 0;
+    while i < 256 {
+        cells[i] = [b'0' + (i / 100) as u8, b'0' + (i / 10 % 10) as u8, b'0' + (i % 10) as u8];
+        i += 1;
+    }
+    cells
+}
+
+const fn bin_cells() -> [[u8; 8]; 256] {
+    let mut cells = [[0; 8]; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut bit = 0;
+        while bit < 8 {
+            cells[i][bit] = if i & (0x80 >> bit) != 0 { b'1' } else { b'0' };
+            bit += 1;
+        }
+        i += 1;
+    }
+    cells
+}
+
+const fn as_strs<const N: usize>(cells: &'static [[u8; N]; 256]) -> [&'static str; 256] {
+    let mut strs = [""; 256];
+    let mut i = 0;
+    while i < 256 {
+        strs[i] = match core::str::from_utf8(&cells[i]) {
+            Ok(s) => s,
+            Err(_) => panic!("table cells are ASCII"),
+        };
+        i += 1;
+    }
+    strs
+}
+
+static ASCII_CELLS: [[u8; 3]; 256] = ascii_cells();
+static HEX_CELLS: [[u8; 2]; 256] = hex_cells();
+static DEC_CELLS: [[u8; 3]; 256] = dec_cells();
+static BIN_CELLS: [[u8; 8]; 256] = bin_cells();
+
+/// The 3 characters wide ASCII column of every byte value.
+pub(crate) static ASCII: [&str; 256] = as_strs(&ASCII_CELLS);
+/// The `Hex` column of every byte value.
+pub(crate) static HEX: [&str; 256] = as_strs(&HEX_CELLS);
+/// The `Dec` column of every byte value.
+pub(crate) static DEC: [&str; 256] = as_strs(&DEC_CELLS);
+/// The `Bin` column of every byte value.
+pub(crate) static BIN: [&str; 256] = as_strs(&BIN_CELLS);
+
+/// Returns the UTF-8 column of the byte at `index`: the 4 bytes starting there if they are valid UTF-8.
+///
+/// The characters which would break the row, line breaks and the escape character, are shown as `...` too.
+pub(crate) fn utf8_repr(bytes: &[u8], index: usize) -> &str {
+    if bytes.len() - index >= 4 {
+        match core::str::from_utf8(&bytes[index..index + 4]) {
+            Ok(text) if !text.contains(['\n', '\r', '\x0b', '\x0c', '\x1b', '\u{85}', '\u{2028}', '\u{2029}']) => text,
+            _ => "...",
+        }
+    } else {
+        "XXX"
+    }
+}
+
+/// Position markers of the bytes of the multi-byte UTF-8 sequences, indexed by length then position.
+const UTF8_MARKS: [[&str; 4]; 3] = [["1/2", "2/2", "", ""], ["1/3", "2/3", "3/3", ""], ["1/4", "2/4", "3/4", "4/4"]];
+
+/// Returns the ASCII column of the byte at `index` of a text: its position in its UTF-8 sequence, e.g. `2/3` for the
+/// second byte of a 3-byte character, `BAD` for a byte of no valid sequence, or `None` for an ASCII byte.
+pub(crate) fn utf8_mark(bytes: &[u8], index: usize) -> Option<&'static str> {
+    let is_continuation = |byte: u8| byte & 0xc0 == 0x80;
+    if bytes[index].is_ascii() {
+        return None;
+    }
+    // A sequence starts at its only byte which isn't a continuation byte, at most 3 bytes before
+    let start = (index.saturating_sub(3)..=index).rev().find(|start| !is_continuation(bytes[*start]) || *start + 3 == index || *start == 0)?;
+    let len = match bytes[start] {
+        0xc2..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf4 => 4,
+        _ => return Some("BAD"),
+    };
+    match bytes.get(start..start + len) {
+        Some(sequence) if index < start + len && core::str::from_utf8(sequence).is_ok() => Some(UTF8_MARKS[len - 2][index - start]),
+        _ => Some("BAD"),
+    }
+}
+
+/// Returns the 64-bit FNV-1a hash of `bytes`, identifying the contents of the tables.
+pub(crate) fn content_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x0000_0100_0000_01b3))
+}
+
+/// Number of hex digits of an address of the target.
+pub(crate) const ADDR_WIDTH: usize = usize::BITS as usize / 4;
+
+/// Name of the address column, as wide as the addresses with their surrounding spaces.
+#[cfg(target_pointer_width = "64")]
+pub(crate) const ADDR_TITLE: &str = "     Address      ";
+#[cfg(target_pointer_width = "32")]
+pub(crate) const ADDR_TITLE: &str = " Address  ";
+#[cfg(target_pointer_width = "16")]
+pub(crate) const ADDR_TITLE: &str = " Addr ";
+
+/// Shortens a separator line written for 64-bit addresses to the address width of the target.
+///
+/// # Arguments
+///
+/// * `line` - The separator line of a table with 16 digits addresses.
+/// * `address_columns` - The number of address columns of the table.
+pub(crate) fn separator(line: &str, address_columns: usize) -> &str {
+    let trim = (16 - ADDR_WIDTH) / 2 * address_columns;
+    &line[trim..line.len() - trim]
+}
+
+/// Separator line of the tables of `view_mem!`.
+pub(crate) const MEMORY_CONTENT: &str = "----------------------Memory Content--------------------";
+
+/// Rule line between the elements of the memory content tables.
+const ELEMENT_RULE: &str = " - - - - - - - - - - - - - - - - - - - - - - - - - - - -";
+
+/// Separator line of the tables of `safe_view_mem!`.
+pub(crate) const CONTAINER_CONTENT: &str = "---------------------Container Content-------------------";
+
+/// Writes the annotation of the byte at the given offset, e.g. the name of the field containing it.
+pub(crate) type Annotate<'a> = dyn Fn(usize, &mut dyn fmt::Write) -> fmt::Result + Sync + 'a;
+
+/// A memory content table: the bytes to display and how.
+#[derive(Clone, Copy)]
+pub(crate) struct Table<'a> {
+    /// The separator line below the column names, carrying the title of the table.
+    pub separator: &'a str,
+    /// The address displayed for the first byte.
+    pub addr: usize,
+    /// The bytes to display.
+    pub bytes: &'a [u8],
+    /// The cap of displayed bytes, `0` means unlimited.
+    pub max_bytes: usize,
+    /// Fills the `Annotation` column, which is only shown if set.
+    pub annotate: Option<&'a Annotate<'a>>,
+    /// The checksums of the bytes written below the rows.
+    pub checksums: &'a [Checksum],
+    /// Highlight the separator and dim the rows of zero bytes with ANSI escape codes.
+    pub color: bool,
+    /// The sorted ranges of bytes shown in reverse video when colored, e.g. the matches of a search.
+    pub highlight: &'a [core::ops::Range<usize>],
+    /// Cut the annotations so the rows fit in this number of columns.
+    pub columns: Option<usize>,
+    /// Writes the counts of zero, printable, `0xff` and distinct bytes below the rows.
+    pub statistics: bool,
+    /// The number of most frequent byte values listed below the rows, `0` for none.
+    pub histogram: usize,
+    /// Writes the entropy of the bytes and of their rows below the rows, only with the `std` feature.
+    pub entropy: bool,
+    /// Adds the transformed bytes next to the original ones.
+    pub transform: Option<Transform>,
+    /// Shows the transformed bytes instead of the original ones.
+    pub decode: Option<Transform>,
+    /// Adds the high and low nibbles of the bytes and their BCD value.
+    pub nibbles: bool,
+    /// Adds the decoded UTF-8 characters, spanning the rows of their bytes.
+    pub glyphs: bool,
+    /// Adds the little- and big-endian values of the words.
+    pub words: Option<Word>,
+    /// Shows the bytes of every word in reverse order.
+    pub swap: Option<Word>,
+    /// Adds the values of the bytes read as these types, at the offsets aligned to their size.
+    pub interpretations: &'static [ElementType],
+    /// Replace the values of the bytes by `*`, keeping their addresses and annotations.
+    pub redact: bool,
+    /// Skip the runs of zero bytes.
+    pub sparse: bool,
+    /// Writes the runs of debug fill values above the rows.
+    pub fill_patterns: bool,
+    /// Writes the words holding the address of a symbol above the rows, only with the `symbols` feature.
+    pub symbols: bool,
+    /// The bytes are UTF-8 text: the ASCII column shows the position of the other bytes in their character.
+    pub text: bool,
+    /// The size of the elements the bytes are made of: a rule line marks where every element but the first starts,
+    /// `0` or `1` for none.
+    pub element: usize,
+    /// Shows the offsets from the base of the loaded module containing the bytes, only with the `std` feature.
+    pub address_style: AddressStyle,
+    /// The layout of the table.
+    pub preset: Preset,
+    /// Replace the table by a reference to an identical table printed earlier in the run, only with the `std` feature.
+    pub dedup: bool,
+    /// The name of the dumped value in the session summary, see [`enable_session_summary`](crate::enable_session_summary).
+    pub label: &'a str,
+}
+
+impl<'a> Table<'a> {
+    /// Describes the table of `bytes` with the global configuration.
+    pub(crate) fn new(separator: &'a str, addr: usize, bytes: &'a [u8]) -> Self {
+        let config = crate::config();
+        Table {
+            separator,
+            addr,
+            bytes,
+            max_bytes: config.max_bytes,
+            annotate: None,
+            checksums: config.checksums,
+            color: false,
+            highlight: &[],
+            columns: None,
+            statistics: config.statistics,
+            histogram: config.histogram,
+            entropy: config.entropy,
+            transform: config.transform,
+            decode: config.decode,
+            nibbles: config.nibbles,
+            glyphs: config.glyphs,
+            words: config.words,
+            swap: config.swap,
+            interpretations: config.interpretations,
+            redact: config.redact,
+            sparse: config.sparse,
+            fill_patterns: config.fill_patterns,
+            symbols: config.symbols,
+            text: false,
+            element: 0,
+            address_style: config.address_style,
+            preset: config.preset,
+            dedup: config.dedup,
+            label: "",
+        }
+    }
+
+    /// Formats the table into `out`, reserving the room of the whole table first.
+    ///
+    /// The table is meant for stdout: it is colored and fitted to the width of the terminal, if any.
+    pub(crate) fn write(&self, out: &mut String) -> fmt::Result {
+        let shown = if self.max_bytes == 0 { self.bytes.len() } else { self.bytes.len().min(self.max_bytes) };
+        out.reserve((shown + 3) * 64);
+        #[cfg(feature = "std")]
+        {
+            let terminal = crate::term::detect_terminal();
+            Table { color: terminal.color, columns: terminal.columns, ..*self }.write_printed(out)
+        }
+        #[cfg(not(feature = "std"))]
+        self.write_printed(out)
+    }
+
+    /// Formats a table printed or sent to a sink into any writer: the table is recorded in the session summary, and
+    /// replaced by a reference if its content was already printed.
+    ///
+    /// The tables formatted with `write_to` are neither recorded nor replaced.
+    pub(crate) fn write_printed<W: fmt::Write + ?Sized>(&self, out: &mut W) -> fmt::Result {
+        #[cfg(feature = "std")]
+        crate::session::record(self.label, self.bytes);
+        #[cfg(feature = "std")]
+        if self.dedup && !self.bytes.is_empty() {
+            match crate::dedup::record(self.bytes) {
+                (hash, crate::dedup::Seen::First(number)) => writeln!(out, "Hash: {:016x} (table #{})", hash, number)?,
+                (hash, crate::dedup::Seen::Again(number)) => {
+                    writeln!(out, "Same: {} bytes identical to table #{} (hash {:016x}), not repeated.", self.bytes.len(), number, hash)?;
+                    return writeln!(out);
+                }
+            }
+        }
+        self.write_to(out)
+    }
+
+    /// Formats the table into any writer.
+    pub(crate) fn write_to<W: fmt::Write + ?Sized>(&self, out: &mut W) -> fmt::Result {
+        #[cfg(feature = "std")]
+        if self.address_style == AddressStyle::ModuleRelative {
+            if let Some(module) = crate::modules::module_at(self.addr) {
+                writeln!(out, "Base: addresses are offsets from the base of {}", module.name())?;
+                return Table { addr: self.addr - module.base, address_style: AddressStyle::Absolute, ..*self }.write_to(out);
+            }
+        }
+        if let Some(decode) = self.decode {
+            if !self.bytes.is_empty() {
+                writeln!(out, "Deco: bytes shown after the {}", decode)?;
+            }
+            let decoded = decode.apply(self.bytes);
+            return Table { bytes: &decoded, decode: None, ..*self }.write_to(out);
+        }
+        let bytes = self.bytes;
+        if bytes.is_empty() {
+            writeln!(out, "Empty: there is no memory content to show.")?;
+            return writeln!(out);
+        }
+        if self.redact {
+            return self.write_redacted(out);
+        }
+        if self.fill_patterns {
+            crate::fill::write_fill_runs(out, bytes)?;
+        }
+        #[cfg(feature = "symbols")]
+        if self.symbols {
+            crate::symbols::write_symbols(out, self.addr, bytes)?;
+        }
+        match self.preset {
+            Preset::Classic => {}
+            Preset::Hexyl => {
+                crate::hexyl::write_table(out, self)?;
+                return self.write_footer(out);
+            }
+            Preset::Od => {
+                crate::od::write_table(out, self)?;
+                return self.write_footer(out);
+            }
+            Preset::Bits => {
+                crate::bits::write_table(out, self)?;
+                return self.write_footer(out);
+            }
+        }
+
+        if let Some(swap) = self.swap {
+            let order = if cfg!(target_endian = "little") { "little" } else { "big" };
+            writeln!(out, "Endn: {}-endian machine, bytes of every {} shown in reverse order", order, swap.name())?;
+        }
+        write!(out, "{}| Hex | Dec |    Bin   | ASCII | UTF-8", ADDR_TITLE)?;
+        if let Some(transform) = self.transform {
+            write!(out, " | {} | {} Bin", transform.label(), transform.label())?;
+        }
+        if self.nibbles {
+            out.write_str(" | Hi Nib | Lo Nib | BCD")?;
+        }
+        if self.glyphs {
+            crate::glyph::write_title(out)?;
+        }
+        if let Some(words) = self.words {
+            words.write_titles(out)?;
+        }
+        for element in self.interpretations {
+            element.write_title(out)?;
+        }
+        if self.annotate.is_some() {
+            out.write_str(" | Annotation")?;
+        }
+        writeln!(out)?;
+        if self.color {
+            writeln!(out, "{}{}{}", BOLD, separator(self.separator, 1), RESET)?;
+        } else {
+            writeln!(out, "{}", separator(self.separator, 1))?;
+        }
+        match crate::_omitted_range(bytes.len(), self.max_bytes) {
+            Some(omitted) => {
+                self.write_rows(out, 0..omitted.start)?;
+                writeln!(out, " … {} bytes omitted …", crate::Thousands(omitted.len()))?;
+                self.write_rows(out, omitted.end..bytes.len())?;
+            }
+            None => self.write_rows(out, 0..bytes.len())?,
+        }
+        self.write_footer(out)
+    }
+
+    /// Formats the table with the values of the bytes replaced by `*`: only the addresses, the annotations, the size and
+    /// the hash of the content are shown, whatever the preset and the extra columns.
+    fn write_redacted<W: fmt::Write + ?Sized>(&self, out: &mut W) -> fmt::Result {
+        let bytes = self.bytes;
+        writeln!(out, "Rdct: {} bytes redacted, FNV-1a hash {:016x}", crate::Thousands(bytes.len()), content_hash(bytes))?;
+        write!(out, "{}| Hex | Dec |    Bin   | ASCII | UTF-8", ADDR_TITLE)?;
+        if self.annotate.is_some() {
+            out.write_str(" | Annotation")?;
+        }
+        writeln!(out)?;
+        writeln!(out, "{}", separator(self.separator, 1))?;
+        let write_rows = |out: &mut W, range: core::ops::Range<usize>| -> fmt::Result {
+            for index in range {
+                write!(out, " {} | **  | *** | ******** |   *   | *** ", crate::_Addr(self.addr + index))?;
+                if let Some(annotate) = self.annotate {
+                    out.write_str("| ")?;
+                    let room = self.columns.map(|columns| columns.saturating_sub(ADDR_TITLE.len() + ROW_WIDTH_AFTER_ADDR));
+                    annotate(index, &mut Fit { out: &mut *out, room, cut: false })?;
+                }
+                writeln!(out)?;
+            }
+            Ok(())
+        };
+        match crate::_omitted_range(bytes.len(), self.max_bytes) {
+            Some(omitted) => {
+                write_rows(out, 0..omitted.start)?;
+                writeln!(out, " … {} bytes omitted …", crate::Thousands(omitted.len()))?;
+                write_rows(out, omitted.end..bytes.len())?;
+            }
+            None => write_rows(out, 0..bytes.len())?,
+        }
+        writeln!(out)
+    }
+
+    /// Formats the checksums, the statistics, the histogram, the entropy and the blank line ending the table.
+    fn write_footer<W: fmt::Write + ?Sized>(&self, out: &mut W) -> fmt::Result {
+        write_checksums(out, self.checksums, self.bytes)?;
+        if self.statistics {
+            crate::histogram::write_statistics(out, self.bytes)?;
+        }
+        crate::histogram::write_histogram(out, self.histogram, self.bytes)?;
+        #[cfg(feature = "std")]
+        if self.entropy {
+            crate::entropy::write_entropy(out, self.addr, self.bytes, self.max_bytes)?;
+        }
+        writeln!(out)
+    }
+
+    /// Returns the index of the byte shown on the row at `index`: `index`, or the index of the byte swapped with it.
+    fn shown_index(&self, index: usize) -> usize {
+        self.swap.map_or(index, |swap| swap.swapped_index(self.bytes.len(), index))
+    }
+
+    /// Formats the row at `index`, below a rule line if an element starts there.
+    fn write_row<W: fmt::Write + ?Sized>(&self, out: &mut W, index: usize) -> fmt::Result {
+        if self.element > 1 && index > 0 && index.is_multiple_of(self.element) {
+            writeln!(out, "{}", separator(ELEMENT_RULE, 1))?;
+        }
+        let index = self.shown_index(index);
+        let cell = self.bytes[index] as usize;
+        let highlighted = self.color && self.highlight.get(self.highlight.partition_point(|range| range.end <= index)).is_some_and(|range| range.contains(&index));
+        let dim = self.color && cell == 0 && !highlighted;
+        if highlighted {
+            out.write_str(REVERSE)?;
+        } else if dim {
+            out.write_str(DIM)?;
+        }
+        let ascii = if self.text { utf8_mark(self.bytes, index).unwrap_or(ASCII[cell]) } else { ASCII[cell] };
+        write!(out, " {} | {}  | {} | {} |  {}  | {} ", crate::_Addr(self.addr + index), HEX[cell], DEC[cell], BIN[cell], ascii, utf8_repr(self.bytes, index))?;
+        if let Some(transform) = self.transform {
+            let cell = transform.byte_at(self.bytes, index) as usize;
+            write!(out, "| {}   | {} ", HEX[cell], BIN[cell])?;
+        }
+        if self.nibbles {
+            let (high, low) = (cell >> 4, cell & 0xf);
+            write!(out, "| {:x} {:04b} | {:x} {:04b} ", high, high, low, low)?;
+            // Nibbles above 9 aren't BCD digits
+            if high <= 9 && low <= 9 {
+                write!(out, "| {}{}  ", high, low)?;
+            } else {
+                out.write_str("| --  ")?;
+            }
+        }
+        if self.glyphs {
+            crate::glyph::write_column(out, self.bytes, index)?;
+        }
+        if let Some(words) = self.words {
+            words.write_columns(out, self.bytes, index)?;
+        }
+        for element in self.interpretations {
+            element.write_column(out, self.bytes, index)?;
+        }
+        if let Some(annotate) = self.annotate {
+            out.write_str("| ")?;
+            let width = ADDR_TITLE.len()
+                + ROW_WIDTH_AFTER_ADDR
+                + if self.transform.is_some() { TRANSFORM_WIDTH } else { 0 }
+                + if self.nibbles { NIBBLES_WIDTH } else { 0 }
+                + if self.glyphs { crate::glyph::GLYPH_WIDTH } else { 0 }
+                + self.words.map_or(0, Word::width)
+                + self.interpretations.iter().map(|element| element.width()).sum::<usize>();
+            let room = self.columns.map(|columns| columns.saturating_sub(width));
+            let mut fitted = Fit { out: &mut *out, room, cut: false };
+            annotate(index, &mut fitted)?;
+        }
+        if dim || highlighted {
+            out.write_str(RESET)?;
+        }
+        writeln!(out)
+    }
+
+    /// Formats the rows of the bytes in `range`.
+    fn write_rows<W: fmt::Write + ?Sized>(&self, out: &mut W, range: core::ops::Range<usize>) -> fmt::Result {
+        if self.sparse {
+            return self.write_sparse_rows(out, range);
+        }
+        #[cfg(feature = "rayon")]
+        if range.len() >= PARALLEL_THRESHOLD {
+            return self.write_rows_parallel(out, range);
+        }
+
+        for index in range {
+            self.write_row(out, index)?;
+        }
+        Ok(())
+    }
+
+    /// Formats the rows of the bytes in `range`, the runs of at least [`SPARSE_RUN`] zero bytes being replaced by a
+    /// line with their number.
+    fn write_sparse_rows<W: fmt::Write + ?Sized>(&self, out: &mut W, range: core::ops::Range<usize>) -> fmt::Result {
+        let mut index = range.start;
+        while index < range.end {
+            let zeros = (index..range.end).take_while(|row| self.bytes[self.shown_index(*row)] == 0).count();
+            if zeros >= SPARSE_RUN {
+                writeln!(out, " … {} zero bytes skipped …", crate::Thousands(zeros))?;
+                index += zeros;
+                continue;
+            }
+            for row in index..index + zeros.max(1) {
+                self.write_row(out, row)?;
+            }
+            index += zeros.max(1);
+        }
+        Ok(())
+    }
+
+    /// Formats the rows of the bytes in `range`, in chunks formatted in parallel and appended in order.
+    #[cfg(feature = "rayon")]
+    fn write_rows_parallel<W: fmt::Write + ?Sized>(&self, out: &mut W, range: core::ops::Range<usize>) -> fmt::Result {
+        use rayon::prelude::*;
+
+        let starts: Vec<usize> = range.clone().step_by(PARALLEL_CHUNK).collect();
+        let chunks: Vec<String> = starts
+            .into_par_iter()
+            .map(|start| {
+                let end = (start + PARALLEL_CHUNK).min(range.end);
+                let mut chunk = String::with_capacity((end - start) * 64);
+                for index in start..end {
+                    // Formatting into a `String` can't fail
+                    let _ = self.write_row(&mut chunk, index);
+                }
+                chunk
+            })
+            .collect();
+        for chunk in chunks {
+            out.write_str(&chunk)?;
+        }
+        Ok(())
+    }
+}
+
+/// Width of the columns of a row after the address, up to the annotation: `| 41  | 065 | 01000001 |   A   | XXX | `.
+const ROW_WIDTH_AFTER_ADDR: usize = 39;
+
+/// Width of the columns of a transformed byte: `| 41   | 01000001 `.
+const TRANSFORM_WIDTH: usize = 18;
+
+/// Number of consecutive zero bytes from which sparse tables skip them.
+const SPARSE_RUN: usize = 16;
+
+/// Width of the columns of the nibbles of a byte: `| 4 0100 | 1 0001 | 41  `.
+const NIBBLES_WIDTH: usize = 24;
+
+/// ANSI escape codes of the colored tables.
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const REVERSE: &str = "\x1b[7m";
+const RESET: &str = "\x1b[0m";
+
+/// Writes at most `room` characters into `out`, ending with `…` if the text is cut.
+struct Fit<'a, W: ?Sized> {
+    out: &'a mut W,
+    room: Option<usize>,
+    cut: bool,
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for Fit<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let Some(room) = self.room.as_mut() else {
+            return self.out.write_str(s);
+        };
+        if self.cut {
+            return Ok(());
+        }
+        let len = s.chars().count();
+        if len <= *room {
+            *room -= len;
+            return self.out.write_str(s);
+        }
+        // Keep a column for the ellipsis
+        let kept = room.saturating_sub(1);
+        let end = s.char_indices().nth(kept).map_or(s.len(), |(end, _)| end);
+        self.cut = true;
+        *room = 0;
+        self.out.write_str(&s[..end])?;
+        self.out.write_str("…")
+    }
+}
+
+/// Number of bytes from which the rows are formatted in parallel.
+#[cfg(feature = "rayon")]
+const PARALLEL_THRESHOLD: usize = 256 * 1024;
+
+/// Number of bytes formatted by each parallel task.
+#[cfg(feature = "rayon")]
+const PARALLEL_CHUNK: usize = 64 * 1024;
+
+/// Formats the explanation replacing the (empty) table of a zero-sized value.
+pub(crate) fn write_zst_note<W: fmt::Write + ?Sized>(out: &mut W, addr: usize) -> fmt::Result {
+    writeln!(out, "Zero-sized: the value occupies 0 bytes, there is no memory content to show.")?;
+    writeln!(out, "            Its address {} is not backed by any allocation, it is only guaranteed", crate::_HeaderAddr(addr))?;
+    writeln!(out, "            to be non-null and aligned (often the alignment itself, i.e. dangling),")?;
+    writeln!(out, "            and other zero-sized values may share it.")?;
+    writeln!(out)
+}
+
+/// Writes the explanation replacing the memory content in Miri-friendly mode, see
+/// [`Config::miri_friendly`](crate::Config::miri_friendly).
+pub(crate) fn write_miri_note<W: fmt::Write + ?Sized>(out: &mut W) -> fmt::Result {
+    writeln!(out, "Miri-friendly mode: the memory content is not read, the padding bytes of the value may be")?;
+    writeln!(out, "                    uninitialized and reading them is undefined behavior.")?;
+    writeln!(out)
+}
+
+/// Calls `f` with an empty buffer, then writes the buffer at once to the output sink, stdout unless
+/// [`set_output_sink`](crate::set_output_sink) was called.
+///
+/// The buffer is reused by the following calls on the same thread, so dumping again doesn't allocate unless the table
+/// is bigger than any previous one. This keeps the dumps from disturbing the allocator under inspection.
+#[cfg(feature = "std")]
+pub(crate) fn emit(f: impl FnOnce(&mut String) -> fmt::Result) {
+    thread_local! {
+        static BUFFER: std::cell::RefCell<String> = const { std::cell::RefCell::new(String::new()) };
+    }
+
+    // Formatting into a `String` can't fail
+
+    BUFFER.with(|buffer| match buffer.try_borrow_mut() {
+        Ok(mut out) => {
+            out.clear();
+            let _ = f(&mut out);
+            crate::sink::deliver(&out);
+            // Don't keep the memory of a huge dump for the rest of the thread
+            if out.capacity() > MAX_KEPT_CAPACITY {
+                *out = String::new();
+            }
+        }
+        Err(_) => {
+            let mut out = String::new();
+            let _ = f(&mut out);
+            crate::sink::deliver(&out);
+        }
+    });
+}
+
+/// Capacity above which the reused buffer is released after use.
+#[cfg(feature = "std")]
+const MAX_KEPT_CAPACITY: usize = 1 << 20;
+
+/// Writes `out` to stdout at once.
+#[cfg(all(feature = "std", not(all(feature = "web", target_arch = "wasm32"))))]
+pub(crate) fn flush(out: &str) {
+    use std::io::Write as _;
+
+    let mut stdout = std::io::stdout().lock();
+    let _ = stdout.write_all(out.as_bytes());
+    let _ = stdout.flush();
+}
+
+/// Writes `out` to the browser console as a single message, stdout goes nowhere on `wasm32-unknown-unknown`.
+#[cfg(all(feature = "web", target_arch = "wasm32"))]
+pub(crate) fn flush(out: &str) {
+    web_sys::console::log_1(&out.trim_end_matches('\n').into());
+}
+
+#[cfg(all(test, feature = "rayon"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_rows_are_in_order() {
+        let bytes: Vec<u8> = (0..1_000_000u32).map(|i| (i % 251) as u8).collect();
+        let table = Table::new(MEMORY_CONTENT, 0x1000, &bytes);
+        let mut parallel = String::new();
+        table.write_rows(&mut parallel, 0..bytes.len()).unwrap();
+
+        let mut sequential = String::new();
+        for index in 0..bytes.len() {
+            table.write_row(&mut sequential, index).unwrap();
+        }
+        assert!(parallel == sequential);
+    }
+}
@@ -0,0 +1,94 @@
+//! Deterministic example data.
+//!
+//! Reproducible buffers to showcase and test the dumps without handcrafting vectors: the same arguments always
+//! give the same bytes, on every platform.
+//!
+//! # Example
+//!
+//! ```rust
+//! use mem_viewer::*;
+//!
+//! let my_noise = sample::pseudo_random(32, 69);
+//! view_mem!(*my_noise);
+//! ```
+
+use alloc::vec::Vec;
+
+use serde::Serialize;
+
+/// The text repeated by [`lorem_ipsum`].
+pub const LOREM_IPSUM: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. ";
+
+/// Returns `len` bytes counting up from 0, wrapping after 255.
+pub fn counting(len: usize) -> Vec<u8> {
+    (0..len).map(|index| index as u8).collect()
+}
+
+/// Returns `len` pseudo-random bytes generated from `seed` (SplitMix64).
+///
+/// # Arguments
+///
+/// * `len` - The number of bytes.
+/// * `seed` - Any value, the same seed always gives the same bytes.
+pub fn pseudo_random(len: usize, seed: u64) -> Vec<u8> {
+    let mut rng = SplitMix64(seed);
+    let mut bytes = Vec::with_capacity(len + 8);
+    while bytes.len() < len {
+        bytes.extend_from_slice(&rng.next_u64().to_le_bytes());
+    }
+    bytes.truncate(len);
+    bytes
+}
+
+/// Returns `len` bytes of printable ASCII text, [`LOREM_IPSUM`] repeated.
+pub fn lorem_ipsum(len: usize) -> Vec<u8> {
+    LOREM_IPSUM.bytes().cycle().take(len).collect()
+}
+
+/// A small struct with padding, to showcase struct arrays.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SamplePoint {
+    /// Index of the point in the array.
+    pub id: u16,
+    /// Pseudo-random flags, followed by 1 byte of padding.
+    pub flags: u8,
+    /// Pseudo-random horizontal coordinate in `0.0..1.0`.
+    pub x: f32,
+    /// Pseudo-random vertical coordinate in `0.0..1.0`.
+    pub y: f32,
+}
+
+/// Returns `len` points with pseudo-random content generated from `seed`.
+///
+/// # Arguments
+///
+/// * `len` - The number of points.
+/// * `seed` - Any value, the same seed always gives the same points.
+pub fn sample_points(len: usize, seed: u64) -> Vec<SamplePoint> {
+    let mut rng = SplitMix64(seed);
+    (0..len)
+        .map(|id| {
+            let bits = rng.next_u64();
+            SamplePoint {
+                id: id as u16,
+                flags: bits as u8,
+                x: (bits >> 8 & 0xffffff) as f32 / (1 << 24) as f32,
+                y: (bits >> 32 & 0xffffff) as f32 / (1 << 24) as f32,
+            }
+        })
+        .collect()
+}
+
+/// The SplitMix64 generator, small and good enough for example data.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
@@ -0,0 +1,164 @@
+//! Header lines and annotations of the dumps of scalar types whose bytes need explaining.
+//!
+//! The 128-bit integers are decoded (see [`WideInt`]), and the bytes of a `bool` or a `char`, or of an array or slice
+//! of them, are checked for validity: a `bool` other than 0 or 1, or a `char` which isn't a Unicode scalar value, is
+//! undefined behavior, usually left behind by an unsafe cast or transmute. The invalid values are flagged loudly.
+
+use core::fmt;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::wide::WideInt;
+
+/// The element type whose values are checked for validity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Element {
+    Bool,
+    Char,
+}
+
+impl Element {
+    /// Returns the element type of a `bool` or a `char`, or of an array or slice of them, from its type name.
+    fn of(type_name: &str) -> Option<Self> {
+        let element = match type_name.strip_prefix('[') {
+            Some(array) => array.strip_suffix(']')?.split(';').next()?,
+            None => type_name,
+        };
+        match element {
+            "bool" => Some(Element::Bool),
+            "char" => Some(Element::Char),
+            _ => None,
+        }
+    }
+
+    /// Returns the size of an element in bytes.
+    fn size(self) -> usize {
+        match self {
+            Element::Bool => 1,
+            Element::Char => 4,
+        }
+    }
+
+    /// Returns the Rust name of the element type.
+    fn name(self) -> &'static str {
+        match self {
+            Element::Bool => "bool",
+            Element::Char => "char",
+        }
+    }
+
+    /// Returns the value of the element made of `bytes`, or `None` if the bytes aren't a valid value.
+    fn decode(self, bytes: &[u8]) -> Option<Decoded> {
+        match self {
+            Element::Bool => match bytes[0] {
+                0 => Some(Decoded::Bool(false)),
+                1 => Some(Decoded::Bool(true)),
+                _ => None,
+            },
+            Element::Char => char::from_u32(u32::from_ne_bytes(bytes.try_into().ok()?)).map(Decoded::Char),
+        }
+    }
+
+    /// Returns the raw value of the element made of `bytes`, for the invalid ones.
+    fn raw(self, bytes: &[u8]) -> u32 {
+        match self {
+            Element::Bool => bytes[0] as u32,
+            Element::Char => u32::from_ne_bytes(bytes.try_into().unwrap_or_default()),
+        }
+    }
+}
+
+/// A valid `bool` or `char`.
+enum Decoded {
+    Bool(bool),
+    Char(char),
+}
+
+/// The validity of the `bool`s or `char`s of a dump.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Validity {
+    element: Element,
+    /// The number of elements.
+    count: usize,
+    /// The offsets of the invalid elements.
+    invalid: Vec<usize>,
+}
+
+impl Validity {
+    /// Checks the elements of the value of type `type_name` made of `bytes`, if it is a `bool` or a `char`, or an array
+    /// or slice of them.
+    fn check(type_name: &str, bytes: &[u8]) -> Option<Self> {
+        let element = Element::of(type_name)?;
+        let size = element.size();
+        let invalid = (0..bytes.len() / size).map(|index| index * size).filter(|offset| element.decode(&bytes[*offset..offset + size]).is_none()).collect();
+        Some(Validity { element, count: bytes.len() / size, invalid })
+    }
+
+    /// Returns the `Vald` header line.
+    fn header(&self) -> String {
+        let (name, count) = (self.element.name(), self.count);
+        let plural = if count == 1 { "" } else { "s" };
+        let rule = match self.element {
+            Element::Bool => "0x00 or 0x01",
+            Element::Char => "a Unicode scalar value (not a surrogate nor above U+10FFFF)",
+        };
+        match self.invalid.len() {
+            0 => format!("{} {}{}, all valid: {}", count, name, plural, rule),
+            invalid => {
+                let verb = if invalid == 1 { "isn't" } else { "aren't" };
+                format!("INVALID: {} of {} {}{} {} {}, undefined behavior (unsafe cast or transmute?)", invalid, count, name, plural, verb, rule)
+            }
+        }
+    }
+
+    /// Writes the annotation of the byte at `offset`: the value of the element starting there, or `INVALID`, only the
+    /// flag when redacting.
+    fn annotate(&self, bytes: &[u8], offset: usize, redact: bool, out: &mut dyn fmt::Write) -> fmt::Result {
+        let size = self.element.size();
+        if !offset.is_multiple_of(size) || offset + size > bytes.len() {
+            return Ok(());
+        }
+        let element = &bytes[offset..offset + size];
+        match self.element.decode(element) {
+            Some(_) if redact => Ok(()),
+            None if redact => out.write_str("INVALID"),
+            Some(Decoded::Bool(value)) => write!(out, "{}", value),
+            Some(Decoded::Char(value)) => write!(out, "'{}' U+{:04X}", value.escape_debug(), value as u32),
+            None => write!(out, "INVALID {} {:#x}", self.element.name(), self.element.raw(element)),
+        }
+    }
+}
+
+/// What the type of a dumped value tells about its bytes.
+pub(crate) enum Scalar {
+    /// A `u128` or an `i128`, decoded.
+    Wide(WideInt),
+    /// `bool`s or `char`s, checked.
+    Validity(Validity),
+}
+
+impl Scalar {
+    /// Decodes or checks the value of type `type_name` made of `bytes`, if its type is one of the explained ones.
+    pub(crate) fn of(type_name: &str, bytes: &[u8]) -> Option<Self> {
+        WideInt::decode(type_name, bytes).map(Scalar::Wide).or_else(|| Validity::check(type_name, bytes).map(Scalar::Validity))
+    }
+
+    /// Returns the header lines as `(label, value)` pairs, without the ones telling the value when redacting.
+    pub(crate) fn headers(&self, redact: bool) -> Vec<(&'static str, String)> {
+        match self {
+            Scalar::Wide(_) if redact => Vec::new(),
+            Scalar::Wide(wide) => alloc::vec![("Valu", format!("{}", wide.value())), ("Hlvs", format!("{}", wide.halves()))],
+            Scalar::Validity(validity) => alloc::vec![("Vald", validity.header())],
+        }
+    }
+
+    /// Writes the annotation of the byte at `offset` of `bytes`, without the value when redacting.
+    pub(crate) fn annotate(&self, bytes: &[u8], offset: usize, redact: bool, out: &mut dyn fmt::Write) -> fmt::Result {
+        match self {
+            Scalar::Wide(wide) => wide.annotate(offset, redact, out),
+            Scalar::Validity(validity) => validity.annotate(bytes, offset, redact, out),
+        }
+    }
+}
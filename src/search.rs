@@ -0,0 +1,299 @@
+//! Byte patterns and text searched in dumped memory.
+//!
+//! `find_in_mem!` lists the offsets of a pattern, e.g. a magic number or a stack canary, in the memory of a variable
+//! and dumps the memory with the matches marked. `grep_mem!` searches the printable text of the memory instead, like
+//! `strings | grep`, and prints the rows around every hit.
+
+use core::fmt;
+use core::ops::Range;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::dump::{MemDump, DEFAULT_ROW_WIDTH};
+use crate::render::HEX;
+
+/// Returns the offsets of the occurrences of `pattern` in `bytes`, overlapping ones included.
+pub(crate) fn find_all(bytes: &[u8], pattern: &[u8]) -> Vec<usize> {
+    if pattern.is_empty() {
+        return Vec::new();
+    }
+    bytes.windows(pattern.len()).enumerate().filter(|(_, window)| *window == pattern).map(|(offset, _)| offset).collect()
+}
+
+impl MemDump {
+    /// Returns the offsets of the occurrences of `pattern` in the dump, overlapping ones included.
+    ///
+    /// # Argument
+    ///
+    /// * `pattern` - The bytes to search, an empty pattern is never found.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let dump = MemDump::new("my_bytes", "[u8]", 0x1000, vec![0xde, 0xad, 0xde, 0xad, 0xde]);
+    /// assert_eq!(dump.find(&[0xde, 0xad, 0xde]), [0, 2]);
+    /// ```
+    pub fn find(&self, pattern: &[u8]) -> Vec<usize> {
+        find_all(&self.bytes, pattern)
+    }
+
+    /// Returns the text of the dump: a character per byte, printable ASCII as is and anything else as `.`, so the
+    /// offsets in the text are those of the bytes.
+    pub fn text(&self) -> String {
+        self.bytes.iter().map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' }).collect()
+    }
+
+    /// Returns the ranges of bytes whose [text](MemDump::text) is `needle`, without overlaps.
+    ///
+    /// # Argument
+    ///
+    /// * `needle` - The printable ASCII text to find, an empty text is never found.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let dump = MemDump::new("my_buf", "[u8]", 0x1000, b"\0\0user=root\0token=abc\0".to_vec());
+    /// assert_eq!(dump.find_text("="), [6..7, 17..18]);
+    /// ```
+    pub fn find_text(&self, needle: &str) -> Vec<Range<usize>> {
+        if needle.is_empty() {
+            return Vec::new();
+        }
+        self.find_text_with(|text| text.find(needle).map(|start| start..start + needle.len()))
+    }
+
+    /// Returns the ranges of bytes matched by `matcher` in the [text](MemDump::text) of the dump, without overlaps.
+    ///
+    /// This plugs any text search into the dump, e.g. a regex of the `regex` crate:
+    /// `dump.find_text_with(|text| regex.find(text).map(|hit| hit.range()))`.
+    ///
+    /// # Argument
+    ///
+    /// * `matcher` - Returns the range of the first match in the text it is given, the rest of the text after the
+    ///   previous match. Empty matches are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let dump = MemDump::new("my_buf", "[u8]", 0x1000, b"id=42;id=7".to_vec());
+    /// // The runs of digits
+    /// let digits = |text: &str| {
+    ///     let start = text.find(|c: char| c.is_ascii_digit())?;
+    ///     let len = text[start..].find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len() - start);
+    ///     Some(start..start + len)
+    /// };
+    /// assert_eq!(dump.find_text_with(digits), [3..5, 9..10]);
+    /// ```
+    pub fn find_text_with(&self, mut matcher: impl FnMut(&str) -> Option<Range<usize>>) -> Vec<Range<usize>> {
+        let text = self.text();
+        let mut hits = Vec::new();
+        let mut start = 0;
+        while start < text.len() {
+            let Some(hit) = matcher(&text[start..]) else {
+                break;
+            };
+            if hit.is_empty() {
+                start += hit.start + 1;
+                continue;
+            }
+            hits.push(start + hit.start..start + hit.end);
+            start += hit.end;
+        }
+        hits
+    }
+
+    /// Writes the text of every hit of a [text search](MemDump::find_text) with the rows around it: the
+    /// [`DEFAULT_ROW_WIDTH`] bytes rows containing the hit, one more before and one more after. With the `redact`
+    /// configuration only the length of the hits and the addresses of the rows are written.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - Where to write the hits.
+    /// * `hits` - The ranges of the hits in the dump.
+    pub fn write_text_hits<W: fmt::Write + ?Sized>(&self, out: &mut W, hits: &[Range<usize>]) -> fmt::Result {
+        self.write_hit_rows(out, hits, crate::config().redact)
+    }
+
+    /// Writes the hits of a text search like [`MemDump::write_text_hits`], with the values of the bytes replaced by
+    /// `*` when redacting.
+    pub(crate) fn write_hit_rows<W: fmt::Write + ?Sized>(&self, out: &mut W, hits: &[Range<usize>], redact: bool) -> fmt::Result {
+        writeln!(out, "Name: {}", self.name)?;
+        if hits.is_empty() {
+            writeln!(out, "Hits: none")?;
+            return writeln!(out);
+        }
+        writeln!(out, "Hits: {}", hits.len())?;
+        let text = self.text();
+        for hit in hits {
+            match redact {
+                true => writeln!(out, "Text: redacted ({} bytes) at offset {:#x}", hit.len(), hit.start)?,
+                false => writeln!(out, "Text: \"{}\" at offset {:#x}", &text[hit.clone()], hit.start)?,
+            }
+            let first = (hit.start / DEFAULT_ROW_WIDTH).saturating_sub(1);
+            let last = (hit.end - 1) / DEFAULT_ROW_WIDTH + 1;
+            for row in self.rows().skip(first).take(last + 1 - first) {
+                write!(out, " {} |", crate::_Addr(row.addr))?;
+                for byte in row.bytes {
+                    write!(out, " {}", if redact { "**" } else { HEX[*byte as usize] })?;
+                }
+                for _ in row.bytes.len()..DEFAULT_ROW_WIDTH {
+                    out.write_str("   ")?;
+                }
+                match redact {
+                    true => writeln!(out, " | {}", "*".repeat(row.bytes.len()))?,
+                    false => writeln!(out, " | {}", row.ascii)?,
+                }
+            }
+        }
+        writeln!(out)
+    }
+}
+
+/// Writes the `Find` and `Hits` lines of a search of `pattern` found at `offsets`.
+pub(crate) fn write_hits<W: fmt::Write + ?Sized>(out: &mut W, name: &str, pattern: &[u8], offsets: &[usize]) -> fmt::Result {
+    writeln!(out, "Name: {}", name)?;
+    out.write_str("Find:")?;
+    for byte in pattern {
+        write!(out, " {}", HEX[*byte as usize])?;
+    }
+    writeln!(out)?;
+    if offsets.is_empty() {
+        writeln!(out, "Hits: none")?;
+        return writeln!(out);
+    }
+    write!(out, "Hits: {} at offset{}", offsets.len(), if offsets.len() == 1 { "" } else { "s" })?;
+    for (index, offset) in offsets.iter().enumerate() {
+        write!(out, "{}{:#x}", if index == 0 { " " } else { ", " }, offset)?;
+    }
+    writeln!(out)
+}
+
+/// Writes the annotation of the byte at `offset` when `pattern_len` bytes were found at `offsets`: the match containing
+/// it, if any.
+pub(crate) fn annotate(offsets: &[usize], pattern_len: usize, offset: usize, out: &mut dyn fmt::Write) -> fmt::Result {
+    // The last match starting at or before the byte, overlapping matches being reported by the latest one
+    let index = offsets.partition_point(|start| *start <= offset);
+    match index.checked_sub(1).map(|index| (index, offsets[index])) {
+        Some((index, start)) if offset < start + pattern_len => {
+            if offset == start {
+                write!(out, "match #{}", index + 1)
+            } else {
+                write!(out, "match #{} +{}", index + 1, offset - start)
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns the ranges of the bytes of the matches, merged when they overlap.
+pub(crate) fn match_ranges(offsets: &[usize], pattern_len: usize) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for start in offsets {
+        match ranges.last_mut() {
+            Some(last) if *start <= last.end => last.end = start + pattern_len,
+            _ => ranges.push(*start..start + pattern_len),
+        }
+    }
+    ranges
+}
+
+#[cfg(feature = "std")]
+#[macro_export]
+/// Macro to find a byte pattern in the memory content of an arbitrary variable.
+///
+/// Prints the offsets of the occurrences of the pattern, then the memory content with the bytes of every match
+/// annotated (and in reverse video on a color terminal), and returns the offsets. Nothing is dumped if the pattern
+/// isn't found.
+///
+/// # Arguments
+///
+/// * `var` - The variable whose memory content is searched.
+/// * `pattern` - The bytes to find, e.g. an array or a byte string.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_frame: [u8; 10] = [1, 0xde, 0xad, 0xbe, 0xef, 2, 0xde, 0xad, 0xbe, 0xef];
+/// let offsets = find_in_mem!(my_frame, [0xde, 0xad, 0xbe, 0xef]);
+/// assert_eq!(offsets, [1, 6]);
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_frame
+/// Find: de ad be ef
+/// Hits: 2 at offsets 0x1, 0x6
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ----------------------Memory Content--------------------
+///  00007fc8d7a5f30e | 01  | 001 | 00000001 |  SOH  | ... | 
+///  00007fc8d7a5f30f | de  | 222 | 11011110 |  ...  | ... | match #1
+///  00007fc8d7a5f310 | ad  | 173 | 10101101 |  ...  | ... | match #1 +1
+///  00007fc8d7a5f311 | be  | 190 | 10111110 |  ...  | ... | match #1 +2
+///  00007fc8d7a5f312 | ef  | 239 | 11101111 |  ...  | ... | match #1 +3
+///  00007fc8d7a5f313 | 02  | 002 | 00000010 |  STX  | ... | 
+///  00007fc8d7a5f314 | de  | 222 | 11011110 |  ...  | ... | match #2
+///  00007fc8d7a5f315 | ad  | 173 | 10101101 |  ...  | XXX | match #2 +1
+///  00007fc8d7a5f316 | be  | 190 | 10111110 |  ...  | XXX | match #2 +2
+///  00007fc8d7a5f317 | ef  | 239 | 11101111 |  ...  | XXX | match #2 +3
+/// ```
+macro_rules! find_in_mem {
+    ($var: expr, $pattern: expr) => {{
+        #[allow(clippy::size_of_ref)]
+        let size = ::core::mem::size_of_val(&$var);
+        let ptr = &$var as *const _ as *const u8;
+        // The bytes are only read by _find_in_memory, which leaves them alone in Miri-friendly mode
+        let bytes = unsafe { ::core::slice::from_raw_parts(ptr, size) };
+        $crate::_find_in_memory(stringify!($var), bytes, &$pattern)
+    }};
+}
+
+#[cfg(feature = "std")]
+#[macro_export]
+/// Macro to search a text in the memory content of an arbitrary variable, like `strings | grep`.
+///
+/// The printable ASCII bytes of the memory are searched, see [`MemDump::find_text`]. Prints every hit with the rows
+/// of 16 bytes around it, and returns the ranges of the hits. With the `redact` configuration the bytes and the text
+/// of the rows are replaced by `*`.
+///
+/// # Arguments
+///
+/// * `var` - The variable whose memory content is searched.
+/// * `needle` - The text to find.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_env = *b"HOME=/root\0PATH=/usr/bin\0";
+/// let hits = grep_mem!(my_env, "PATH");
+/// assert_eq!(hits, [11..15]);
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_env
+/// Hits: 1
+/// Text: "PATH" at offset 0xb
+///  00007ffc1c5fa2d6 | 48 4f 4d 45 3d 2f 72 6f 6f 74 00 50 41 54 48 3d | HOME=/root.PATH=
+///  00007ffc1c5fa2e6 | 2f 75 73 72 2f 62 69 6e 00                      | /usr/bin.
+/// ```
+macro_rules! grep_mem {
+    ($var: expr, $needle: expr) => {{
+        let dump = $crate::MemDump::of(stringify!($var), &$var);
+        let hits = dump.find_text($needle);
+        $crate::_show_text_hits(&dump, &hits);
+        hits
+    }};
+}
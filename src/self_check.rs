@@ -0,0 +1,161 @@
+//! Self-test of the platform dependent parts of the crate.
+//!
+//! The stack/heap classification of `view_mem!` relies on the address layout of the platform, and the allocator
+//! introspection only works with some allocators and operating systems. [`self_check`] exercises them on the current
+//! system, so their results can be trusted (or not) before a debugging session relies on them.
+
+use core::fmt;
+
+/// The result of a check of [`self_check`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    /// What is checked.
+    pub name: &'static str,
+    /// Whether it works on this system, with this build.
+    pub available: bool,
+    /// What was observed, or why it isn't available.
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, available: bool, detail: impl Into<String>) -> Self {
+        Check { name, available, detail: detail.into() }
+    }
+}
+
+/// Runs the checks of the platform dependent features and returns their results, see [`self_check`].
+pub fn self_check_results() -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let my_local = 0u8;
+    let my_box = Box::new(0u8);
+    let (stack, heap) = ((&my_local as *const u8).addr(), (&*my_box as *const u8).addr());
+    let classified = crate::_likely_stack(stack) && !crate::_likely_stack(heap);
+    let detail = format!(
+        "a local at {} is classified as {}, a heap allocation at {} as {}",
+        crate::_Addr(stack),
+        if crate::_likely_stack(stack) { "stack" } else { "heap" },
+        crate::_Addr(heap),
+        if crate::_likely_stack(heap) { "stack" } else { "heap" },
+    );
+    checks.push(Check::new("Stack/heap classification", classified, detail));
+
+    checks.push(Check::new("Address width", true, format!("{} bits, {} hex digits", usize::BITS, crate::render::ADDR_WIDTH)));
+
+    let terminal = crate::detect_terminal();
+    let detail = match terminal.columns {
+        Some(columns) => format!("color {}, {} columns", if terminal.color { "on" } else { "off" }, columns),
+        None => format!("color {}, width unknown (not a terminal)", if terminal.color { "on" } else { "off" }),
+    };
+    checks.push(Check::new("Terminal", true, detail));
+
+    let miri_friendly = crate::config().miri_friendly;
+    let detail = if miri_friendly { "not read in Miri-friendly mode" } else { "read by view_mem! and the other value dumps" };
+    checks.push(Check::new("Reading value content", !miri_friendly, detail));
+
+    static PROBE: [u8; 4] = *b"mem!";
+    let modules = crate::loaded_modules();
+    let location = crate::locate_static(PROBE.as_ptr().addr());
+    let detail = match &location {
+        Some(location) => format!("{} modules, a static found in {}", modules.len(), location.section.name),
+        None if modules.is_empty() => String::from("no module listed, requires Linux or Windows"),
+        None => format!("{} modules, a static found in none of their sections", modules.len()),
+    };
+    checks.push(Check::new("Loaded modules", location.is_some(), detail));
+
+    #[cfg(all(windows, feature = "heapwalk"))]
+    {
+        let my_block = Box::new([0u8; 24]);
+        let found = crate::heapwalk::find_block(my_block.as_ptr());
+        let detail = format!("{} process heaps, heap allocation {}", crate::heapwalk::process_heaps().len(), if found.is_some() { "found" } else { "not found" });
+        checks.push(Check::new("Heap walking", found.is_some(), detail));
+    }
+    #[cfg(not(all(windows, feature = "heapwalk")))]
+    checks.push(Check::new("Heap walking", false, "requires Windows and the `heapwalk` feature"));
+
+    #[cfg(all(target_os = "linux", target_env = "gnu", feature = "glibc"))]
+    {
+        // Probe with an allocation of glibc itself, the global allocator may be another one
+        let ptr = unsafe { libc::malloc(24) } as *const u8;
+        let available = !ptr.is_null() && {
+            let chunk = unsafe { crate::glibc::chunk_header(ptr) };
+            chunk.mem() == ptr.addr() && chunk.usable_size >= 24 && chunk.size() > chunk.usable_size
+        };
+        unsafe { libc::free(ptr as *mut libc::c_void) };
+        let detail = if available { "chunk headers decoded" } else { "unexpected chunk header" };
+        checks.push(Check::new("glibc chunks", available, detail));
+    }
+    #[cfg(not(all(target_os = "linux", target_env = "gnu", feature = "glibc")))]
+    checks.push(Check::new("glibc chunks", false, "requires Linux with glibc and the `glibc` feature"));
+
+    #[cfg(feature = "jemalloc")]
+    match crate::alloc_stats::jemalloc_stats() {
+        Some(stats) => checks.push(Check::new("jemalloc statistics", true, format!("{} bytes allocated", crate::Thousands(stats.allocated)))),
+        None => checks.push(Check::new("jemalloc statistics", false, "can't be read")),
+    }
+    #[cfg(not(feature = "jemalloc"))]
+    checks.push(Check::new("jemalloc statistics", false, "requires the `jemalloc` feature"));
+
+    #[cfg(feature = "mimalloc")]
+    {
+        let info = crate::alloc_stats::mimalloc_process_info();
+        checks.push(Check::new("mimalloc statistics", true, format!("{} bytes committed", crate::Thousands(info.current_commit))));
+    }
+    #[cfg(not(feature = "mimalloc"))]
+    checks.push(Check::new("mimalloc statistics", false, "requires the `mimalloc` feature"));
+
+    #[cfg(not(target_arch = "wasm32"))]
+    checks.push(Check::new("Session summary at exit", true, "printed by an atexit handler"));
+    #[cfg(target_arch = "wasm32")]
+    checks.push(Check::new("Session summary at exit", false, "no atexit on wasm32, call write_session_summary"));
+
+    checks
+}
+
+/// Writes the results of the checks, one line each.
+fn write_checks<W: fmt::Write + ?Sized>(out: &mut W, checks: &[Check]) -> fmt::Result {
+    let width = checks.iter().map(|check| check.name.len()).max().unwrap_or(0);
+    for check in checks {
+        let status = if check.available { "ok" } else { "--" };
+        writeln!(out, "[{}] {:width$} : {}", status, check.name, check.detail, width = width)?;
+    }
+    writeln!(out)
+}
+
+/// Exercises the platform dependent features on the current system and prints which ones are available.
+///
+/// The checks cover the stack/heap classification of `view_mem!` (a local and a heap allocation are classified), the
+/// terminal detection, the Miri-friendly mode, the loaded-module tables locating statics, and the heap walking, glibc and allocator statistics features, which
+/// are reported as unavailable when they aren't enabled or not supported by the platform. The results are returned as
+/// well, to be checked by a program.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let checks = self_check();
+/// assert!(checks.iter().any(|check| check.name == "Address width" && check.available));
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// [ok] Stack/heap classification : a local at 00007ffd5d1ce3af is classified as stack, a heap allocation at 000055d1c3a3fb10 as heap
+/// [ok] Address width             : 64 bits, 16 hex digits
+/// [ok] Terminal                  : color on, 120 columns
+/// [ok] Reading value content     : read by view_mem! and the other value dumps
+/// [ok] Loaded modules            : 6 modules, a static found in .rodata
+/// [--] Heap walking              : requires Windows and the `heapwalk` feature
+/// [--] glibc chunks              : requires Linux with glibc and the `glibc` feature
+/// [--] jemalloc statistics       : requires the `jemalloc` feature
+/// [--] mimalloc statistics       : requires the `mimalloc` feature
+/// [ok] Session summary at exit   : printed by an atexit handler
+/// ```
+pub fn self_check() -> Vec<Check> {
+    let checks = self_check_results();
+    let mut out = String::new();
+    let _ = write_checks(&mut out, &checks);
+    crate::render::flush(&out);
+    checks
+}
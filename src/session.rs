@@ -0,0 +1,99 @@
+//! Summary of the dumps printed during a run.
+//!
+//! Once [`enable_session_summary`] is called, every table printed to stdout or sent to a sink is recorded with the name
+//! of the dumped value, its size and the hash of its content, and the summary is printed when the process exits: an
+//! index into a long log full of dumps, whose hashes are those of the `Hash` lines of [`Config::dedup`](crate::Config::dedup).
+
+use core::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// A dump recorded in the session summary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpRecord {
+    /// Name of the dumped value, empty if unknown.
+    pub label: String,
+    /// Number of dumped bytes.
+    pub len: usize,
+    /// 64-bit FNV-1a hash of the dumped bytes.
+    pub hash: u64,
+}
+
+/// Whether the printed tables are recorded.
+pub(crate) static RECORDING: AtomicBool = AtomicBool::new(false);
+
+static DUMPS: Mutex<Vec<DumpRecord>> = Mutex::new(Vec::new());
+
+/// Records a printed table of `bytes`, if the session summary is enabled.
+pub(crate) fn record(label: &str, bytes: &[u8]) {
+    if !RECORDING.load(Ordering::Relaxed) {
+        return;
+    }
+    let record = DumpRecord { label: label.into(), len: bytes.len(), hash: crate::render::content_hash(bytes) };
+    DUMPS.lock().unwrap_or_else(|e| e.into_inner()).push(record);
+}
+
+/// Returns the dumps recorded since [`enable_session_summary`] was called, in the order they were printed.
+pub fn session_dumps() -> Vec<DumpRecord> {
+    DUMPS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Starts recording the dumps printed from now on and prints their summary when the process exits.
+///
+/// The summary lists the number of dumps, their total size, and the name, size and hash of every dump, followed by the
+/// bytes still allocated when the allocator statistics are available (`jemalloc` and `mimalloc` features). Calling it
+/// again has no effect. The summary is printed by an `atexit` handler, so it is missing if the process aborts, and
+/// it isn't printed at all on `wasm32`, print it with [`write_session_summary`] there.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// enable_session_summary();
+/// for my_u32 in [1u32, 2] {
+///     view_mem!(my_u32);
+/// }
+/// // Prints "Dump summary: 2 dumps, 8 bytes" and a line per dump at exit
+/// ```
+pub fn enable_session_summary() {
+    #[cfg(target_arch = "wasm32")]
+    RECORDING.store(true, Ordering::Relaxed);
+    #[cfg(not(target_arch = "wasm32"))]
+    if !RECORDING.swap(true, Ordering::Relaxed) {
+        extern "C" {
+            fn atexit(callback: extern "C" fn()) -> core::ffi::c_int;
+        }
+
+        extern "C" fn print_summary() {
+            let mut out = String::new();
+            let _ = write_session_summary(&mut out);
+            crate::render::flush(&out);
+        }
+
+        // The handler only formats into its own buffer and writes it to stdout
+        unsafe { atexit(print_summary) };
+    }
+}
+
+/// Writes the summary of the dumps recorded since [`enable_session_summary`] was called.
+///
+/// # Argument
+///
+/// * `out` - Where to write the summary.
+pub fn write_session_summary<W: fmt::Write + ?Sized>(out: &mut W) -> fmt::Result {
+    let dumps = session_dumps();
+    let total: usize = dumps.iter().map(|dump| dump.len).sum();
+    writeln!(out, "Dump summary: {} dumps, {} bytes", dumps.len(), crate::Thousands(total))?;
+    for (index, dump) in dumps.iter().enumerate() {
+        let label = if dump.label.is_empty() { "(unnamed)" } else { &dump.label };
+        writeln!(out, " #{} | {} | {} bytes | {:016x}", index + 1, label, crate::Thousands(dump.len), dump.hash)?;
+    }
+    #[cfg(feature = "jemalloc")]
+    if let Some(stats) = crate::alloc_stats::jemalloc_stats() {
+        writeln!(out, "Heap: {} bytes still allocated (jemalloc)", crate::Thousands(stats.allocated))?;
+    }
+    #[cfg(feature = "mimalloc")]
+    writeln!(out, "Heap: {} bytes committed (mimalloc)", crate::Thousands(crate::alloc_stats::mimalloc_process_info().current_commit))?;
+    writeln!(out)
+}
@@ -0,0 +1,494 @@
+//! Destinations of the dumps.
+//!
+//! A [`MemSink`] receives a dump as header lines and table rows, and decides where they go: stdout, a file, an RTT
+//! channel, the semihosting console of a debugger, or anything else implementing the trait. [`sink_mem`] renders a
+//! dump into any sink, and [`set_output_sink`] sends the dumps of all the macros to one instead of stdout.
+
+use core::fmt;
+
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+use alloc::string::String;
+
+use crate::dump::read_volatile_bytes;
+use crate::render::{self, Table, MEMORY_CONTENT};
+use crate::scalar::Scalar;
+
+/// The destination of a dump.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+/// use std::fmt;
+///
+/// /// Keeps the rows showing a non-zero byte.
+/// #[derive(Default)]
+/// struct NonZeroRows(Vec<String>);
+///
+/// impl MemSink for NonZeroRows {
+///     fn write_header(&mut self, _label: &str, _value: fmt::Arguments<'_>) -> fmt::Result {
+///         Ok(())
+///     }
+///
+///     fn write_row(&mut self, row: &str) -> fmt::Result {
+///         if row.starts_with(' ') && !row.contains("| 00  |") {
+///             self.0.push(row.to_string());
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let my_u32: u32 = 69;
+/// let mut sink = NonZeroRows::default();
+/// sink_mem(&mut sink, "my_u32", &my_u32).unwrap();
+/// assert_eq!(sink.0.len(), 2); // The column names and the byte 0x45
+/// ```
+pub trait MemSink {
+    /// Writes a header line, e.g. `Name: my_u32` with the label `Name`.
+    fn write_header(&mut self, label: &str, value: fmt::Arguments<'_>) -> fmt::Result;
+
+    /// Writes a line of the body, without its line break: the column names, the separator, a byte row, a note, or
+    /// an empty line ending the dump.
+    fn write_row(&mut self, row: &str) -> fmt::Result;
+
+    /// Delivers what was written, called once at the end of every dump.
+    fn flush(&mut self) -> fmt::Result {
+        Ok(())
+    }
+}
+
+impl<S: MemSink + ?Sized> MemSink for &mut S {
+    fn write_header(&mut self, label: &str, value: fmt::Arguments<'_>) -> fmt::Result {
+        (**self).write_header(label, value)
+    }
+
+    fn write_row(&mut self, row: &str) -> fmt::Result {
+        (**self).write_row(row)
+    }
+
+    fn flush(&mut self) -> fmt::Result {
+        (**self).flush()
+    }
+}
+
+/// Renders the dump of `value` into `sink`, then flushes it.
+///
+/// # Arguments
+///
+/// * `sink` - The destination of the dump.
+/// * `name` - The name displayed in the header.
+/// * `value` - The value to dump, its `size_of_val` bytes are displayed.
+pub fn sink_mem<S: MemSink + ?Sized, T: ?Sized>(sink: &mut S, name: &str, value: &T) -> fmt::Result {
+    let ptr = value as *const T as *const u8;
+    let size = core::mem::size_of_val(value);
+    if crate::config().miri_friendly && size > 0 {
+        render_header(sink, name, core::any::type_name::<T>(), &crate::_HeaderAddr(ptr.addr()), size)?;
+        render::write_miri_note(&mut Rows { sink: &mut *sink, line: String::new() })?;
+        return sink.flush();
+    }
+    let bytes = unsafe { core::slice::from_raw_parts(ptr, size) };
+    render_into(sink, name, core::any::type_name::<T>(), ptr.addr(), bytes, size == 0)
+}
+
+/// Renders the dump of `len` bytes starting at `ptr` into `sink`, then flushes it.
+///
+/// # Arguments
+///
+/// * `sink` - The destination of the dump.
+/// * `name` - The name displayed in the header.
+/// * `ptr` - The address of the first byte.
+/// * `len` - The number of bytes to display.
+///
+/// # Safety
+///
+/// The whole region must be readable.
+pub unsafe fn sink_mem_raw<S: MemSink + ?Sized>(sink: &mut S, name: &str, ptr: *const u8, len: usize) -> fmt::Result {
+    render_into(sink, name, "[u8]", ptr.addr(), core::slice::from_raw_parts(ptr, len), false)
+}
+
+/// Renders the dump of `len` bytes starting at `ptr` into `sink`, reading them with volatile reads, then flushes it.
+///
+/// Every byte is read exactly once, see [`MemDump::from_raw_volatile`](crate::MemDump::from_raw_volatile).
+///
+/// # Arguments
+///
+/// * `sink` - The destination of the dump.
+/// * `name` - The name displayed in the header.
+/// * `ptr` - The address of the first byte, e.g. of a memory-mapped peripheral.
+/// * `len` - The number of bytes to display.
+///
+/// # Safety
+///
+/// The whole region must be readable with byte-wide accesses.
+pub unsafe fn sink_mem_volatile<S: MemSink + ?Sized>(sink: &mut S, name: &str, ptr: *const u8, len: usize) -> fmt::Result {
+    let bytes = read_volatile_bytes(ptr, len);
+    render_into(sink, name, "[u8]", ptr.addr(), &bytes, false)
+}
+
+fn render_into<S: MemSink + ?Sized>(sink: &mut S, name: &str, type_name: &str, addr: usize, bytes: &[u8], zero_sized: bool) -> fmt::Result {
+    render_header(sink, name, type_name, &crate::StyledAddr(addr), bytes.len())?;
+    let redact = crate::config().redact;
+    let scalar = Scalar::of(type_name, bytes);
+    for (label, value) in scalar.iter().flat_map(|scalar| scalar.headers(redact)) {
+        sink.write_header(label, format_args!("{}", value))?;
+    }
+
+    let mut rows = Rows { sink: &mut *sink, line: String::new() };
+    if zero_sized {
+        render::write_zst_note(&mut rows, addr)?;
+    } else {
+        let annotate = |offset: usize, out: &mut dyn fmt::Write| scalar.as_ref().map_or(Ok(()), |scalar| scalar.annotate(bytes, offset, redact, out));
+        let mut table = Table { label: name, text: type_name == "str", redact, ..Table::new(MEMORY_CONTENT, addr, bytes) };
+        if scalar.is_some() {
+            table.annotate = Some(&annotate);
+        }
+        table.write_printed(&mut rows)?;
+    }
+    sink.flush()
+}
+
+fn render_header<S: MemSink + ?Sized>(sink: &mut S, name: &str, type_name: &str, addr: &dyn fmt::Display, len: usize) -> fmt::Result {
+    sink.write_header("Name", format_args!("{}", name))?;
+    sink.write_header("Type", format_args!("{}", type_name))?;
+    sink.write_header("Addr", format_args!("{}", addr))?;
+    sink.write_header("Size", format_args!("{} bytes", len))
+}
+
+/// Splits the formatted body into the rows of a sink.
+struct Rows<'a, S: ?Sized> {
+    sink: &'a mut S,
+    line: String,
+}
+
+impl<S: MemSink + ?Sized> fmt::Write for Rows<'_, S> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut rest = s;
+        while let Some(end) = rest.find('\n') {
+            if self.line.is_empty() {
+                self.sink.write_row(&rest[..end])?;
+            } else {
+                self.line.push_str(&rest[..end]);
+                self.sink.write_row(&self.line)?;
+                self.line.clear();
+            }
+            rest = &rest[end + 1..];
+        }
+        self.line.push_str(rest);
+        Ok(())
+    }
+}
+
+/// Writes the dumps as text lines into any `fmt::Write`, e.g. a `String` or a [`SliceWriter`](crate::SliceWriter).
+#[derive(Debug, Default)]
+pub struct FmtSink<W>(pub W);
+
+impl<W: fmt::Write> MemSink for FmtSink<W> {
+    fn write_header(&mut self, label: &str, value: fmt::Arguments<'_>) -> fmt::Result {
+        writeln!(self.0, "{}: {}", label, value)
+    }
+
+    fn write_row(&mut self, row: &str) -> fmt::Result {
+        writeln!(self.0, "{}", row)
+    }
+}
+
+/// Writes the dumps to stdout, each one at once when it is flushed.
+///
+/// With the `web` feature on `wasm32`, each dump is logged to the browser console instead.
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct StdoutSink {
+    out: String,
+}
+
+#[cfg(feature = "std")]
+impl MemSink for StdoutSink {
+    fn write_header(&mut self, label: &str, value: fmt::Arguments<'_>) -> fmt::Result {
+        fmt::Write::write_fmt(&mut self.out, format_args!("{}: {}\n", label, value))
+    }
+
+    fn write_row(&mut self, row: &str) -> fmt::Result {
+        self.out.push_str(row);
+        self.out.push('\n');
+        Ok(())
+    }
+
+    fn flush(&mut self) -> fmt::Result {
+        render::flush(&self.out);
+        self.out.clear();
+        Ok(())
+    }
+}
+
+/// Sink receiving the dumps of the macros, stdout when none is set.
+#[cfg(feature = "std")]
+static OUTPUT_SINK: std::sync::Mutex<Option<Box<dyn MemSink + Send>>> = std::sync::Mutex::new(None);
+
+/// Sends the dumps of the `view_*!` macros and of the `view` methods to `sink` instead of stdout, e.g. to a
+/// [`FileSink`] to keep them out of the output of the program.
+///
+/// Every dump is passed to the sink as its header lines, the `Name: value` lines it starts with, then as rows, and the
+/// sink is flushed at its end. The dumps of the threads wait for each other, so the sink must not dump anything
+/// itself.
+///
+/// Returns the sink set before, if any.
+///
+/// # Argument
+///
+/// * `sink` - The destination of the dumps from now on.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let path = std::env::temp_dir().join("mem_viewer_output_sink.txt");
+/// set_output_sink(FileSink::create(&path).unwrap());
+/// let my_f32: f32 = 3.14;
+/// view_float!(my_f32);
+/// take_output_sink();
+/// assert!(std::fs::read_to_string(&path).unwrap().contains("Name: my_f32\nType: f32\n"));
+/// ```
+#[cfg(feature = "std")]
+pub fn set_output_sink(sink: impl MemSink + Send + 'static) -> Option<Box<dyn MemSink + Send>> {
+    OUTPUT_SINK.lock().unwrap_or_else(|e| e.into_inner()).replace(Box::new(sink))
+}
+
+/// Sends the dumps back to stdout, returning the sink set by [`set_output_sink`], if any.
+#[cfg(feature = "std")]
+pub fn take_output_sink() -> Option<Box<dyn MemSink + Send>> {
+    OUTPUT_SINK.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// Delivers a formatted dump to the output sink, or to stdout when none is set.
+#[cfg(feature = "std")]
+pub(crate) fn deliver(text: &str) {
+    let mut output = OUTPUT_SINK.lock().unwrap_or_else(|e| e.into_inner());
+    match output.as_mut() {
+        Some(sink) => {
+            let _ = write_text(&mut **sink, text);
+        }
+        None => {
+            drop(output);
+            render::flush(text);
+        }
+    }
+}
+
+/// Passes a formatted dump to `sink`: the `Label: value` lines it starts with as headers, the other lines as rows,
+/// then flushes it.
+#[cfg(feature = "std")]
+fn write_text<S: MemSink + ?Sized>(sink: &mut S, text: &str) -> fmt::Result {
+    let mut lines = text.lines().peekable();
+    while let Some((label, value)) = lines.peek().and_then(|line| split_header(line)) {
+        sink.write_header(label, format_args!("{}", value))?;
+        lines.next();
+    }
+    for row in lines {
+        sink.write_row(row)?;
+    }
+    sink.flush()
+}
+
+/// Splits a header line, made of a label of words and its value, e.g. `Name: my_u16` or the padded
+/// `Container Len: 13` of `safe_view_mem!`.
+#[cfg(feature = "std")]
+fn split_header(line: &str) -> Option<(&str, &str)> {
+    let (label, value) = line.split_once(": ")?;
+    let label = label.trim_end();
+    (label.starts_with(|c: char| c.is_ascii_alphabetic()) && label.bytes().all(|byte| byte.is_ascii_alphabetic() || byte == b' ')).then_some((label, value))
+}
+
+/// Writes the dumps as text lines into any `io::Write`, e.g. a file with [`FileSink`].
+///
+/// `MemSink` reports `fmt::Error`, the underlying I/O error is kept and can be taken with [`IoSink::take_error`].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct IoSink<W: std::io::Write> {
+    inner: W,
+    error: Option<std::io::Error>,
+}
+
+/// Writes the dumps into a file, buffered.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let path = std::env::temp_dir().join("mem_viewer_file_sink.txt");
+/// let mut sink = FileSink::create(&path).unwrap();
+/// sink_mem(&mut sink, "my_u16", &69u16).unwrap();
+/// assert!(std::fs::read_to_string(&path).unwrap().starts_with("Name: my_u16\n"));
+/// ```
+#[cfg(feature = "std")]
+pub type FileSink = IoSink<std::io::BufWriter<std::fs::File>>;
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> IoSink<W> {
+    /// Creates a sink writing into `inner`.
+    pub fn new(inner: W) -> Self {
+        IoSink { inner, error: None }
+    }
+
+    /// Returns the I/O error which made the last write fail, if any.
+    pub fn take_error(&mut self) -> Option<std::io::Error> {
+        self.error.take()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn check(&mut self, result: std::io::Result<()>) -> fmt::Result {
+        result.map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl FileSink {
+    /// Creates (or truncates) the file at `path` and writes the dumps into it.
+    pub fn create(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        Ok(IoSink::new(std::io::BufWriter::new(std::fs::File::create(path)?)))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> MemSink for IoSink<W> {
+    fn write_header(&mut self, label: &str, value: fmt::Arguments<'_>) -> fmt::Result {
+        let result = writeln!(self.inner, "{}: {}", label, value);
+        self.check(result)
+    }
+
+    fn write_row(&mut self, row: &str) -> fmt::Result {
+        let result = writeln!(self.inner, "{}", row);
+        self.check(result)
+    }
+
+    fn flush(&mut self) -> fmt::Result {
+        let result = self.inner.flush();
+        self.check(result)
+    }
+}
+
+/// Writes the dumps into an RTT up channel, to be read by the debug probe (e.g. with probe-rs).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use mem_viewer::*;
+///
+/// let channels = rtt_target::rtt_init! { up: { 0: { size: 1024, name: "Dumps" } } };
+/// let mut sink = RttSink::new(channels.up.0);
+/// sink_mem(&mut sink, "my_u16", &69u16).ok();
+/// ```
+#[cfg(feature = "rtt")]
+pub struct RttSink {
+    channel: rtt_target::UpChannel,
+}
+
+#[cfg(feature = "rtt")]
+impl RttSink {
+    /// Creates a sink writing into `channel`.
+    pub fn new(channel: rtt_target::UpChannel) -> Self {
+        RttSink { channel }
+    }
+}
+
+#[cfg(feature = "rtt")]
+impl MemSink for RttSink {
+    fn write_header(&mut self, label: &str, value: fmt::Arguments<'_>) -> fmt::Result {
+        fmt::Write::write_fmt(&mut self.channel, format_args!("{}: {}\n", label, value))
+    }
+
+    fn write_row(&mut self, row: &str) -> fmt::Result {
+        fmt::Write::write_fmt(&mut self.channel, format_args!("{}\n", row))
+    }
+
+    fn flush(&mut self) -> fmt::Result {
+        self.channel.flush();
+        Ok(())
+    }
+}
+
+/// Writes the dumps to the console of the debugger through ARM semihosting.
+///
+/// Semihosting halts the core for every call, so it is slow, but it needs nothing but a debugger.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use mem_viewer::*;
+///
+/// if let Some(mut sink) = SemihostingSink::stdout() {
+///     sink_mem(&mut sink, "my_u16", &69u16).ok();
+/// }
+/// ```
+#[cfg(all(feature = "semihosting", target_arch = "arm"))]
+pub struct SemihostingSink {
+    stream: cortex_m_semihosting::hio::HostStream,
+}
+
+#[cfg(all(feature = "semihosting", target_arch = "arm"))]
+impl SemihostingSink {
+    /// Opens the stdout of the host, or returns `None` if the debugger refuses it.
+    pub fn stdout() -> Option<Self> {
+        cortex_m_semihosting::hio::hstdout().ok().map(|stream| SemihostingSink { stream })
+    }
+}
+
+#[cfg(all(feature = "semihosting", target_arch = "arm"))]
+impl MemSink for SemihostingSink {
+    fn write_header(&mut self, label: &str, value: fmt::Arguments<'_>) -> fmt::Result {
+        fmt::Write::write_fmt(&mut self.stream, format_args!("{}: {}\n", label, value))
+    }
+
+    fn write_row(&mut self, row: &str) -> fmt::Result {
+        fmt::Write::write_fmt(&mut self.stream, format_args!("{}\n", row))
+    }
+}
+
+/// Writes the dumps to the console of the target: the print channel of `rtt-target` (set up with `rtt_init_print!`)
+/// with the `rtt` feature, the stdout of the host through semihosting otherwise.
+///
+/// This is where `view_mem!` writes without the `std` feature.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use mem_viewer::*;
+///
+/// rtt_target::rtt_init_print!();
+/// sink_mem(&mut ConsoleSink, "my_u16", &69u16).ok();
+/// ```
+#[cfg(any(feature = "rtt", all(feature = "semihosting", target_arch = "arm")))]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ConsoleSink;
+
+#[cfg(any(feature = "rtt", all(feature = "semihosting", target_arch = "arm")))]
+impl ConsoleSink {
+    fn print(args: fmt::Arguments<'_>) {
+        #[cfg(feature = "rtt")]
+        rtt_target::rprint!("{}", args);
+        #[cfg(not(feature = "rtt"))]
+        cortex_m_semihosting::hprint!("{}", args);
+    }
+}
+
+#[cfg(any(feature = "rtt", all(feature = "semihosting", target_arch = "arm")))]
+impl MemSink for ConsoleSink {
+    fn write_header(&mut self, label: &str, value: fmt::Arguments<'_>) -> fmt::Result {
+        ConsoleSink::print(format_args!("{}: {}\n", label, value));
+        Ok(())
+    }
+
+    fn write_row(&mut self, row: &str) -> fmt::Result {
+        ConsoleSink::print(format_args!("{}\n", row));
+        Ok(())
+    }
+}
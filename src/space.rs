@@ -0,0 +1,140 @@
+//! Address spaces other than the memory of the current process.
+//!
+//! An [`AddressSpace`] reads bytes at addresses of its own, e.g. the linear memory of a WebAssembly guest where
+//! addresses are offsets into the memory. [`MemDump::from_space`](crate::MemDump::from_space) captures a region of any
+//! address space, which can then be printed, iterated by rows or compared like any other dump.
+//!
+//! Code running inside a WebAssembly instance reads its own linear memory with [`WasmMemory`], where pointers are
+//! already offsets into the memory.
+
+/// A memory readable at addresses of its own.
+pub trait AddressSpace {
+    /// Reads the bytes starting at `addr` into `buf`.
+    ///
+    /// Returns the number of bytes read, less than `buf.len()` if the region ends outside of the address space.
+    fn read(&self, addr: usize, buf: &mut [u8]) -> usize;
+}
+
+impl<A: AddressSpace + ?Sized> AddressSpace for &A {
+    fn read(&self, addr: usize, buf: &mut [u8]) -> usize {
+        (**self).read(addr, buf)
+    }
+}
+
+/// The linear memory of a WebAssembly instance, addressed as the guest does: from 0.
+///
+/// Hosts get the memory as a byte slice from their runtime, e.g. `memory.data(&store)` with wasmtime or
+/// `memory.view(&store)` then `view.copy_to_vec()` with wasmer, so no runtime is needed as a dependency.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// // The guest memory, e.g. `memory.data(&store)`, with a guest `u32` at address 0x400
+/// let mut data = vec![0u8; 65536];
+/// data[0x400..0x404].copy_from_slice(&0xdeadbeefu32.to_le_bytes());
+///
+/// let guest = LinearMemory::new(&data);
+/// let dump = MemDump::from_space("guest_u32", &guest, 0x400, 4);
+/// assert_eq!(dump.addr, 0x400);
+/// print!("{}", dump);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LinearMemory<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> LinearMemory<'a> {
+    /// Wraps the bytes of a linear memory.
+    pub fn new(data: &'a [u8]) -> Self {
+        LinearMemory { data }
+    }
+
+    /// Returns the size of the linear memory in bytes.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if the linear memory has no pages.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+impl AddressSpace for LinearMemory<'_> {
+    fn read(&self, addr: usize, buf: &mut [u8]) -> usize {
+        let available = self.data.get(addr..).unwrap_or_default();
+        let len = buf.len().min(available.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        len
+    }
+}
+
+/// The linear memory of the WebAssembly instance running this code, where every address below its current size is
+/// readable.
+///
+/// Bytes are read with volatile reads, so regions outside of any Rust allocation (e.g. the data of another module,
+/// or address 0) can be dumped too.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use mem_viewer::*;
+///
+/// // The data section and the start of the stack
+/// let dump = MemDump::from_space("data", &WasmMemory, 1024, 256);
+/// ```
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WasmMemory;
+
+#[cfg(target_arch = "wasm32")]
+impl WasmMemory {
+    /// Returns the current size of the linear memory in bytes.
+    pub fn len(&self) -> usize {
+        core::arch::wasm32::memory_size(0) * 65536
+    }
+
+    /// Returns `true` if the linear memory has no pages.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl AddressSpace for WasmMemory {
+    fn read(&self, addr: usize, buf: &mut [u8]) -> usize {
+        let len = buf.len().min(self.len().saturating_sub(addr));
+        for (index, byte) in buf[..len].iter_mut().enumerate() {
+            // The memory can't shrink, every address below its size stays readable
+            *byte = unsafe { core::ptr::with_exposed_provenance::<u8>(addr + index).read_volatile() };
+        }
+        len
+    }
+}
+
+/// Displays `len` bytes of the linear memory of the current WebAssembly instance, starting at the offset `offset`.
+///
+/// The dump ends early at the end of the memory. With the `web` feature, the dump goes to the browser console.
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `offset` - The offset of the first byte in the linear memory.
+/// * `len` - The number of bytes to display.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use mem_viewer::*;
+///
+/// view_linear_memory("data", 1024, 256);
+/// ```
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
+pub fn view_linear_memory(name: &str, offset: usize, len: usize) {
+    use core::fmt::Write;
+
+    let dump = crate::MemDump::from_space(name, &WasmMemory, offset, len);
+    crate::render::emit(|out| write!(out, "{}", dump));
+}
@@ -0,0 +1,268 @@
+//! Dumps of the stack of the current thread.
+//!
+//! The stack of a thread is a region reserved when the thread starts, filled from its top (the base) down to the
+//! stack pointer. [`stack_bounds`] asks the system for the region: `pthread_getattr_np` on Linux and Android,
+//! `pthread_get_stackaddr_np` on Apple systems, the thread information block on Windows. The
+//! [`view_stack!`](crate::view_stack) macro dumps the bytes in use from the stack pointer up, the frames of the
+//! function calling it and of its callers.
+
+use core::ops::Range;
+use std::fmt::{self, Write};
+
+use crate::render::{self, Table, MEMORY_CONTENT};
+
+/// Returns the address range of the stack of the current thread, from the lowest address it may grow down to, to its
+/// base, past its highest byte.
+///
+/// The range of the main thread on Linux is the one its stack may grow to, up to the stack size limit, not only the
+/// pages mapped so far. On Windows the low end is the lowest page committed so far. `None` is returned on the other
+/// systems, and under Miri.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_local = 0u32;
+/// if let Some(stack) = stack_bounds() {
+///     assert!(stack.contains(&(&my_local as *const u32 as usize)));
+///     println!("{:x}..{:x}, {} KiB", stack.start, stack.end, stack.len() / 1024);
+/// }
+/// ```
+pub fn stack_bounds() -> Option<Range<usize>> {
+    if cfg!(miri) {
+        return None;
+    }
+    platform::stack_bounds()
+}
+
+/// Returns the stack pointer of the calling function, or the address of one of its locals where it can't be read.
+#[inline(always)]
+pub(crate) fn stack_pointer() -> usize {
+    let sp: usize;
+    // SAFETY: the stack pointer is copied to a register, nothing is read or written
+    #[cfg(all(target_arch = "x86_64", not(miri)))]
+    unsafe {
+        core::arch::asm!("mov {}, rsp", out(reg) sp, options(nomem, nostack, preserves_flags))
+    };
+    #[cfg(all(target_arch = "x86", not(miri)))]
+    unsafe {
+        core::arch::asm!("mov {}, esp", out(reg) sp, options(nomem, nostack, preserves_flags))
+    };
+    #[cfg(all(any(target_arch = "aarch64", target_arch = "arm"), not(miri)))]
+    unsafe {
+        core::arch::asm!("mov {}, sp", out(reg) sp, options(nomem, nostack, preserves_flags))
+    };
+    #[cfg(all(any(target_arch = "riscv64", target_arch = "riscv32"), not(miri)))]
+    unsafe {
+        core::arch::asm!("mv {}, sp", out(reg) sp, options(nomem, nostack, preserves_flags))
+    };
+    #[cfg(any(
+        miri,
+        not(any(
+            target_arch = "x86_64",
+            target_arch = "x86",
+            target_arch = "aarch64",
+            target_arch = "arm",
+            target_arch = "riscv64",
+            target_arch = "riscv32"
+        ))
+    ))]
+    {
+        let marker = 0u8;
+        sp = (core::hint::black_box(&marker) as *const u8).addr();
+    }
+    sp
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod platform {
+    use core::ffi::{c_int, c_void};
+    use core::ops::Range;
+
+    /// Storage of a `pthread_attr_t`, larger than its 36 to 64 bytes on the supported targets.
+    #[repr(C, align(16))]
+    struct PthreadAttr([u8; 128]);
+
+    extern "C" {
+        fn pthread_self() -> usize;
+        fn pthread_getattr_np(thread: usize, attr: *mut PthreadAttr) -> c_int;
+        fn pthread_attr_getstack(attr: *const PthreadAttr, addr: *mut *mut c_void, size: *mut usize) -> c_int;
+        fn pthread_attr_destroy(attr: *mut PthreadAttr) -> c_int;
+    }
+
+    /// Reads the stack of the current thread from its attributes.
+    pub(super) fn stack_bounds() -> Option<Range<usize>> {
+        let mut attr = PthreadAttr([0; 128]);
+        let (mut addr, mut size) = (core::ptr::null_mut(), 0);
+        // SAFETY: the attributes are initialized by `pthread_getattr_np` before they are read, and destroyed after
+        unsafe {
+            if pthread_getattr_np(pthread_self(), &mut attr) != 0 {
+                return None;
+            }
+            let found = pthread_attr_getstack(&attr, &mut addr, &mut size) == 0;
+            pthread_attr_destroy(&mut attr);
+            found.then(|| (addr as usize)..(addr as usize + size))
+        }
+    }
+}
+
+#[cfg(target_vendor = "apple")]
+mod platform {
+    use core::ffi::c_void;
+    use core::ops::Range;
+
+    extern "C" {
+        fn pthread_self() -> *mut c_void;
+        fn pthread_get_stackaddr_np(thread: *mut c_void) -> *mut c_void;
+        fn pthread_get_stacksize_np(thread: *mut c_void) -> usize;
+    }
+
+    /// Reads the stack of the current thread, whose address is its base.
+    pub(super) fn stack_bounds() -> Option<Range<usize>> {
+        // SAFETY: the functions only read the descriptor of the current thread
+        let (base, size) = unsafe { (pthread_get_stackaddr_np(pthread_self()) as usize, pthread_get_stacksize_np(pthread_self())) };
+        Some(base - size..base)
+    }
+}
+
+#[cfg(all(windows, any(target_arch = "x86_64", target_arch = "x86")))]
+mod platform {
+    use core::ops::Range;
+
+    /// Reads the `StackBase` and `StackLimit` fields of the thread information block of the current thread.
+    pub(super) fn stack_bounds() -> Option<Range<usize>> {
+        let (base, limit): (usize, usize);
+        // SAFETY: gs (fs on x86) points to the thread information block of the current thread
+        #[cfg(target_arch = "x86_64")]
+        unsafe {
+            core::arch::asm!("mov {}, qword ptr gs:[0x08]", out(reg) base, options(nostack, readonly, preserves_flags));
+            core::arch::asm!("mov {}, qword ptr gs:[0x10]", out(reg) limit, options(nostack, readonly, preserves_flags));
+        }
+        #[cfg(target_arch = "x86")]
+        unsafe {
+            core::arch::asm!("mov {}, dword ptr fs:[0x04]", out(reg) base, options(nostack, readonly, preserves_flags));
+            core::arch::asm!("mov {}, dword ptr fs:[0x08]", out(reg) limit, options(nostack, readonly, preserves_flags));
+        }
+        Some(limit..base)
+    }
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_vendor = "apple",
+    all(windows, any(target_arch = "x86_64", target_arch = "x86"))
+)))]
+mod platform {
+    use core::ops::Range;
+
+    /// The stack of the threads isn't known on this system.
+    pub(super) fn stack_bounds() -> Option<Range<usize>> {
+        None
+    }
+}
+
+/// Formats the top of the stack: its bounds, then the `len` bytes from the stack pointer, copied in `bytes` (empty in
+/// Miri-friendly mode), annotated with it and with the local of the caller.
+pub(crate) fn write_stack(out: &mut String, sp: usize, caller: usize, len: usize, bytes: &[u8], stack: Option<Range<usize>>) -> fmt::Result {
+    let thread = std::thread::current();
+    match thread.name() {
+        Some(name) => writeln!(out, "Name: stack of thread \"{}\"", name)?,
+        None => writeln!(out, "Name: stack of thread {:?}", thread.id())?,
+    }
+    writeln!(out, "Addr: {}", crate::StyledAddr(sp))?;
+    writeln!(out, "Size: {} bytes", len)?;
+    match &stack {
+        Some(stack) => writeln!(
+            out,
+            "Stck: {}..{}, {} bytes, {} in use above the stack pointer",
+            crate::_Addr(stack.start),
+            crate::_Addr(stack.end),
+            stack.len(),
+            stack.end - sp
+        )?,
+        None => writeln!(out, "Stck: bounds unknown on this system, dumped up to the frame of the caller")?,
+    }
+    if crate::config().miri_friendly {
+        return render::write_miri_note(out);
+    }
+
+    let annotate = |offset: usize, out: &mut dyn fmt::Write| match offset {
+        0 => write!(out, "sp, stack pointer of the dump"),
+        offset if sp + offset == caller => write!(out, "a local of the caller of view_stack!"),
+        offset if offset + 1 == bytes.len() && stack.as_ref().is_some_and(|stack| sp + offset + 1 == stack.end) => write!(out, "last byte, below the stack base"),
+        _ => Ok(()),
+    };
+    Table { label: "stack", annotate: Some(&annotate), ..Table::new(MEMORY_CONTENT, sp, bytes) }.write(out)
+}
+
+#[macro_export]
+/// Macro to view the top of the stack of the current thread: the bytes from the stack pointer up to `depth_bytes`
+/// above it, the frames of the calling function and of its callers.
+///
+/// The bounds of the stack are asked to the system (`pthread_getattr_np`, the thread information block on Windows,
+/// see [`stack_bounds`](crate::stack_bounds)) and the dump stops at the base of the stack. A `Stck` line gives the
+/// bounds and the number of bytes in use. The first byte is at the stack pointer of the dump, a few frames below the
+/// caller, whose frame is located by one of its locals. Where the bounds are unknown the dump stops at this local. The
+/// bytes are copied before they are shown. In Miri-friendly mode the memory content isn't shown.
+///
+/// # Argument
+///
+/// * `depth_bytes` - The maximal number of bytes dumped.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_counter: u64 = 0x1122334455667788;
+/// view_stack!(256);
+/// # assert_eq!(my_counter, 0x1122334455667788);
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: stack of thread "main"
+/// Addr: 00007ffc8d1e2b40
+/// Size: 256 bytes
+/// Stck: 00007ffc8c9e6000..00007ffc8d1e6000, 8388608 bytes, 13504 in use above the stack pointer
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ----------------------Memory Content--------------------
+///  00007ffc8d1e2b40 | 00  | 000 | 00000000 |  NUL  | ... | sp, stack pointer of the dump
+/// ...
+///  00007ffc8d1e2c17 | 00  | 000 | 00000000 |  NUL  | ... | a local of the caller of view_stack!
+/// ...
+/// ```
+macro_rules! view_stack {
+    ($depth_bytes: expr) => {{
+        let caller = 0u8;
+        $crate::_show_stack(&caller as *const u8 as usize, $depth_bytes)
+    }};
+}
+
+/// Displays the top `depth_bytes` bytes of the stack of the current thread.
+///
+/// (This is supposed to be private usage of view_stack! macro usage.)
+///
+/// # Arguments
+///
+/// * `caller` - The address of a local of the function calling the macro.
+/// * `depth_bytes` - The maximal number of bytes dumped.
+#[inline(never)]
+pub fn _show_stack(caller: usize, depth_bytes: usize) {
+    let sp = stack_pointer();
+    // On an alternate signal stack the stack pointer is out of the bounds of the thread
+    let stack = stack_bounds().filter(|stack| stack.contains(&sp));
+    // The bytes above the stack pointer belong to this frame and its callers, which the formatting doesn't touch
+    let end = stack.as_ref().map_or(caller.max(sp) + 1, |stack| stack.end);
+    let len = depth_bytes.min(end - sp);
+    let bytes = if crate::config().miri_friendly {
+        Vec::new()
+    } else {
+        // SAFETY: the bytes from the stack pointer to the base of the stack are mapped
+        unsafe { crate::dump::read_volatile_bytes(core::ptr::with_exposed_provenance(sp), len) }
+    };
+    render::emit(|out| write_stack(out, sp, caller, len, &bytes, stack.clone()));
+}
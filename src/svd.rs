@@ -0,0 +1,245 @@
+//! Register maps read from CMSIS-SVD files.
+//!
+//! Vendors describe the peripherals of their microcontrollers in SVD files: the base address of every peripheral, and
+//! the offset, size and bit fields of its registers. [`parse_svd_registers`] reads the registers of one peripheral into
+//! a [`RegisterMap`], so its MMIO window can be dumped with labeled registers and decoded bit fields.
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use crate::registers::{BitField, Register, RegisterMap};
+
+/// Returns the register map of a peripheral of an SVD file, or `None` if the file has no such peripheral.
+///
+/// The registers are placed at the base address of the peripheral, following `derivedFrom` to the registers of the
+/// peripheral it copies. Fields are read from `bitOffset`/`bitWidth`, `lsb`/`msb` or `bitRange`, and their enumerated
+/// values give the meanings of their values. Register arrays (`dim`) and clusters are skipped, as well as registers
+/// and fields whose numbers can't be read. The names are borrowed from the file, which is usually included with
+/// `include_str!`.
+///
+/// # Arguments
+///
+/// * `svd` - The content of the SVD file.
+/// * `peripheral` - The name of the peripheral, e.g. `USART1`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use mem_viewer::*;
+///
+/// static SVD: &str = include_str!("STM32F411.svd");
+///
+/// let usart1 = parse_svd_registers(SVD, "USART1").unwrap();
+/// unsafe { usart1.view("USART1", core::ptr::with_exposed_provenance(0x4001_1000), 0x1c) };
+/// ```
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let map = parse_svd_registers(r#"
+///     <device>
+///       <peripherals>
+///         <peripheral>
+///           <name>USART1</name>
+///           <baseAddress>0x40011000</baseAddress>
+///           <registers>
+///             <register>
+///               <name>CR1</name>
+///               <addressOffset>0xC</addressOffset>
+///               <size>32</size>
+///               <fields>
+///                 <field><name>UE</name><bitOffset>13</bitOffset><bitWidth>1</bitWidth></field>
+///               </fields>
+///             </register>
+///           </registers>
+///         </peripheral>
+///       </peripherals>
+///     </device>
+/// "#, "USART1").unwrap();
+/// let cr1 = map.register_at(0x4001_100d).unwrap();
+/// assert_eq!((cr1.name, cr1.addr, cr1.size), ("CR1", 0x4001_100c, 4));
+/// assert_eq!(cr1.fields[0].lsb, 13);
+/// ```
+pub fn parse_svd_registers(svd: &'static str, peripheral: &str) -> Option<RegisterMap> {
+    let device = elements(svd).find(|element| element.name == "device").map_or(svd, |device| device.content);
+    let peripherals: Vec<Element> = elements(child(device, "peripherals")?).filter(|element| element.name == "peripheral").collect();
+    let found = peripherals.iter().find(|element| child(element.content, "name") == Some(peripheral))?;
+    let base = number(child(found.content, "baseAddress")?)? as usize;
+
+    // A derived peripheral only has its own name and base address, unless it overrides the registers
+    let mut source = found;
+    if child(found.content, "registers").is_none() {
+        if let Some(derived_from) = found.attribute("derivedFrom") {
+            source = peripherals.iter().find(|element| child(element.content, "name") == Some(derived_from))?;
+        }
+    }
+    let default_size = child(source.content, "size").or_else(|| child(device, "size")).and_then(number).unwrap_or(32);
+
+    let mut map = RegisterMap::default();
+    for register in elements(child(source.content, "registers").unwrap_or_default()).filter(|element| element.name == "register") {
+        if let Some(register) = parse_register(register.content, base, default_size) {
+            map.add(register);
+        }
+    }
+    Some(map)
+}
+
+fn parse_register(content: &'static str, base: usize, default_size: u64) -> Option<Register> {
+    if child(content, "dim").is_some() {
+        return None;
+    }
+    let name = child(content, "name")?;
+    let offset = number(child(content, "addressOffset")?)? as usize;
+    let size = child(content, "size").map_or(Some(default_size), number)?;
+    let fields = child(content, "fields").unwrap_or_default();
+    let fields: Vec<BitField> = elements(fields).filter(|element| element.name == "field").filter_map(|field| parse_field(field.content)).collect();
+    Some(Register {
+        name,
+        addr: base + offset,
+        size: (size / 8) as usize,
+        fields: Cow::Owned(fields),
+    })
+}
+
+fn parse_field(content: &'static str) -> Option<BitField> {
+    let name = child(content, "name")?;
+    let (lsb, width) = if let (Some(offset), Some(width)) = (child(content, "bitOffset"), child(content, "bitWidth")) {
+        (number(offset)?, number(width)?)
+    } else if let (Some(lsb), Some(msb)) = (child(content, "lsb"), child(content, "msb")) {
+        let (lsb, msb) = (number(lsb)?, number(msb)?);
+        (lsb, msb.checked_sub(lsb)? + 1)
+    } else {
+        // `[msb:lsb]`
+        let range = child(content, "bitRange")?.strip_prefix('[')?.strip_suffix(']')?;
+        let (msb, lsb) = range.split_once(':')?;
+        let (lsb, msb) = (number(lsb)?, number(msb)?);
+        (lsb, msb.checked_sub(lsb)? + 1)
+    };
+
+    let mut values = Vec::new();
+    for enumerated in elements(content).filter(|element| element.name == "enumeratedValues") {
+        for value in elements(enumerated.content).filter(|element| element.name == "enumeratedValue") {
+            // `isDefault` values and values with don't care bits (`#1x0`) have no single number
+            if let (Some(name), Some(value)) = (child(value.content, "name"), child(value.content, "value").and_then(number)) {
+                values.push((value, name));
+            }
+        }
+    }
+    Some(BitField {
+        name,
+        lsb: lsb.try_into().ok()?,
+        width: width.try_into().ok()?,
+        values: Cow::Owned(values),
+    })
+}
+
+/// An XML element: its name, the text of its attributes and its content.
+struct Element {
+    name: &'static str,
+    attributes: &'static str,
+    content: &'static str,
+}
+
+impl Element {
+    /// Returns the value of the attribute `name`, if any.
+    fn attribute(&self, name: &str) -> Option<&'static str> {
+        let mut rest = self.attributes;
+        while let Some((key, value)) = rest.split_once('=') {
+            let value = value.trim_start();
+            let quote = value.chars().next().filter(|quote| *quote == '"' || *quote == '\'')?;
+            let (value, after) = value[1..].split_once(quote)?;
+            if key.trim() == name {
+                return Some(value);
+            }
+            rest = after;
+        }
+        None
+    }
+}
+
+/// Returns the trimmed content of the first child element of `content` named `name`.
+fn child(content: &'static str, name: &str) -> Option<&'static str> {
+    elements(content).find(|element| element.name == name).map(|element| element.content.trim())
+}
+
+/// Iterates over the elements at the top level of `content`, skipping text, comments and declarations.
+fn elements(content: &'static str) -> impl Iterator<Item = Element> {
+    let mut rest = content;
+    core::iter::from_fn(move || loop {
+        let start = rest.find('<')?;
+        rest = &rest[start..];
+        if let Some(skipped) = skip_markup(rest) {
+            rest = skipped;
+            continue;
+        }
+        if rest.starts_with("</") {
+            // Closing tag without its opening one, the content is malformed
+            return None;
+        }
+        let (tag, after) = rest[1..].split_once('>')?;
+        let name_len = tag.find(|c: char| c.is_whitespace() || c == '/').unwrap_or(tag.len());
+        let (name, attributes) = tag.split_at(name_len);
+        if let Some(attributes) = attributes.strip_suffix('/') {
+            rest = after;
+            return Some(Element { name, attributes, content: "" });
+        }
+        let (content, after) = element_end(after)?;
+        rest = after;
+        return Some(Element { name, attributes, content });
+    })
+}
+
+/// Returns the text after a comment, a declaration (`<?xml ... ?>`) or a CDATA section starting `text`, if it starts
+/// with one.
+fn skip_markup(text: &'static str) -> Option<&'static str> {
+    for (open, close) in [("<!--", "-->"), ("<![CDATA[", "]]>"), ("<?", "?>"), ("<!", ">")] {
+        if let Some(markup) = text.strip_prefix(open) {
+            return markup.split_once(close).map(|(_, after)| after).or(Some(""));
+        }
+    }
+    None
+}
+
+/// Splits `text`, following an opening tag, into the content of the element and the text after its closing tag.
+fn element_end(text: &'static str) -> Option<(&'static str, &'static str)> {
+    let mut depth = 0usize;
+    let mut rest = text;
+    loop {
+        let start = rest.find('<')?;
+        let tag = &rest[start..];
+        if let Some(skipped) = skip_markup(tag) {
+            rest = skipped;
+            continue;
+        }
+        let (inner, after) = tag[1..].split_once('>')?;
+        if inner.starts_with('/') {
+            if depth == 0 {
+                let end = text.len() - tag.len();
+                return Some((&text[..end], after));
+            }
+            depth -= 1;
+        } else if !inner.ends_with('/') {
+            depth += 1;
+        }
+        rest = after;
+    }
+}
+
+/// Parses an SVD number: decimal, hex (`0x`) or binary (`#` or `0b`), with an optional `k`, `M` or `G` multiplier.
+fn number(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let (text, multiplier) = match text.as_bytes().last()? {
+        b'k' | b'K' => (&text[..text.len() - 1], 1 << 10),
+        b'M' | b'm' => (&text[..text.len() - 1], 1 << 20),
+        b'G' | b'g' => (&text[..text.len() - 1], 1 << 30),
+        _ => (text, 1),
+    };
+    let value = if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        u64::from_str_radix(hex, 16).ok()?
+    } else if let Some(binary) = text.strip_prefix('#').or_else(|| text.strip_prefix("0b")) {
+        u64::from_str_radix(binary, 2).ok()?
+    } else {
+        text.parse().ok()?
+    };
+    value.checked_mul(multiplier)
+}
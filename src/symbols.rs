@@ -0,0 +1,176 @@
+//! Symbolication of the code and data addresses found in dumps.
+//!
+//! Function pointers, the entries of vtables and return addresses are addresses in the code or the read-only data of
+//! a loaded module. With the `symbols` feature, [`resolve_symbol`] names them with the `backtrace` crate (the symbol
+//! tables and debug info of the modules, `dladdr` as a fallback), and [`Config::symbols`](crate::Config::symbols)
+//! lists the words of the memory content tables holding such addresses as `addr <symbol+offset>`.
+//! [`Config::header_backtrace`](crate::Config::header_backtrace) names the callers of a dump the same way.
+
+use core::fmt;
+
+/// Maximal number of `Symb` lines above a table.
+const MAX_SYMBOL_LINES: usize = 16;
+
+/// Maximal distance searched back for the start of a symbol.
+const MAX_SYMBOL_SIZE: usize = 1 << 24;
+
+/// A symbol an address resolves to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// Demangled name of the symbol, e.g. `my_crate::handler`.
+    pub name: String,
+    /// Address of the first byte of the symbol.
+    pub addr: usize,
+    /// Offset of the resolved address from the start of the symbol.
+    pub offset: usize,
+    /// Source file of the resolved address, when the debug info tells it.
+    pub file: Option<String>,
+    /// Source line of the resolved address, when the debug info tells it.
+    pub line: Option<u32>,
+}
+
+impl fmt::Display for Symbol {
+    /// Formats the symbol as `<name+0x1a>`, or `<name>` at its first byte.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.offset {
+            0 => write!(f, "<{}>", self.name),
+            offset => write!(f, "<{}+{:#x}>", self.name, offset),
+        }
+    }
+}
+
+/// Returns the symbol containing `addr`, if any: the function or the static (e.g. a vtable) whose bytes it points to.
+///
+/// The symbols come from the symbol tables and the debug info of the loaded modules, so the functions of a stripped
+/// executable aren't found. An inlined function is named after the function it is inlined into, the file and the
+/// line are those of the inlined code. The start of the symbol is found by resolving the bytes before the address.
+///
+/// # Argument
+///
+/// * `addr` - The address to resolve, e.g. the value of a function pointer.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// fn my_handler() {}
+///
+/// let my_fn: fn() = my_handler;
+/// if let Some(symbol) = resolve_symbol(my_fn as usize) {
+///     println!("{:x} {}", my_fn as usize, symbol); // 55d1c3a3fb10 <rust_out::main::my_handler>
+/// }
+/// ```
+pub fn resolve_symbol(addr: usize) -> Option<Symbol> {
+    let (name, file, line) = resolve(addr)?;
+    // The symbol start isn't told by the debug info: look back for the first byte of the same symbol, doubling the
+    // step then halving it
+    let same = |at: usize| resolve(at).is_some_and(|(other, _, _)| other == name);
+    let (mut outside, mut inside, mut step) = (None, addr, 1usize);
+    while step <= MAX_SYMBOL_SIZE {
+        match inside.checked_sub(step) {
+            Some(at) if same(at) => inside = at,
+            at => {
+                outside = at;
+                break;
+            }
+        }
+        step *= 2;
+    }
+    if let Some(mut outside) = outside {
+        while inside - outside > 1 {
+            let middle = outside + (inside - outside) / 2;
+            if same(middle) {
+                inside = middle;
+            } else {
+                outside = middle;
+            }
+        }
+    }
+    Some(Symbol { name, addr: inside, offset: addr - inside, file, line })
+}
+
+/// Resolves the byte at `addr` to the name of its symbol, with the source file and line of the innermost inlined
+/// function at this byte.
+fn resolve(addr: usize) -> Option<(String, Option<String>, Option<u32>)> {
+    if addr == 0 {
+        return None;
+    }
+    let (mut name, mut file, mut line) = (None, None, None);
+    // `resolve` looks up the byte before the address, as for a return address. The frames of the inlined functions
+    // come first, the symbol itself last
+    backtrace::resolve(core::ptr::without_provenance_mut(addr.wrapping_add(1)), |symbol| {
+        if file.is_none() {
+            file = symbol.filename().map(|file| file.display().to_string());
+            line = symbol.lineno();
+        }
+        if let Some(symbol) = symbol.name() {
+            name = Some(format!("{:#}", symbol));
+        }
+    });
+    Some((name?, file, line))
+}
+
+/// Returns the words of `bytes`, aligned in memory, whose value resolves to a symbol: their offset, value and symbol.
+///
+/// Only the values within a loaded module are resolved, when the modules of the system are known.
+pub(crate) fn symbol_words(addr: usize, bytes: &[u8]) -> Vec<(usize, usize, Symbol)> {
+    const WORD: usize = core::mem::size_of::<usize>();
+    let modules = crate::loaded_modules();
+    let first = addr.next_multiple_of(WORD) - addr;
+    (first..bytes.len().saturating_sub(WORD - 1))
+        .step_by(WORD)
+        .filter_map(|offset| {
+            let word = usize::from_ne_bytes(bytes[offset..offset + WORD].try_into().unwrap());
+            if word == 0 || !(modules.is_empty() || modules.iter().any(|module| module.section_at(word).is_some())) {
+                return None;
+            }
+            Some((offset, word, resolve_symbol(word)?))
+        })
+        .collect()
+}
+
+/// Writes a `Symb` line per word of `bytes` holding the address of a symbol, e.g.
+/// `Symb: +0x8 = 000055d1c3a3fb10 <my_crate::handler>`.
+pub(crate) fn write_symbols<W: fmt::Write + ?Sized>(out: &mut W, addr: usize, bytes: &[u8]) -> fmt::Result {
+    let words = symbol_words(addr, bytes);
+    for (offset, word, symbol) in words.iter().take(MAX_SYMBOL_LINES) {
+        writeln!(out, "Symb: +{:#x} = {} {}", offset, crate::StyledAddr(*word), symbol)?;
+    }
+    if words.len() > MAX_SYMBOL_LINES {
+        writeln!(out, "Symb: … {} more words holding the address of a symbol", words.len() - MAX_SYMBOL_LINES)?;
+    }
+    Ok(())
+}
+
+/// Returns the innermost `count` frames of the call stack of the dump, formatted as `my_crate::handler at
+/// src/handler.rs:42`: the frames of this crate and of the `backtrace` crate are skipped, the function where the dump
+/// macro is expanded comes first. The frames of the inlined functions are listed, the frames of the runtime below
+/// `main` or the closure of a thread aren't.
+#[inline(never)]
+pub(crate) fn call_site_frames(count: usize) -> Vec<String> {
+    let mut frames = Vec::new();
+    let mut done = count == 0;
+    backtrace::trace(|frame| {
+        backtrace::resolve_frame(frame, |symbol| {
+            let Some(name) = symbol.name().map(|name| format!("{:#}", name)) else {
+                return;
+            };
+            // The frames of the dump itself come first, those of the runtime last
+            if done || ["backtrace::", "mem_viewer::context::", "mem_viewer::symbols::"].iter().any(|skipped| name.starts_with(skipped)) {
+                return;
+            }
+            if name.contains("__rust_begin_short_backtrace") {
+                done = true;
+                return;
+            }
+            frames.push(match (symbol.filename(), symbol.lineno()) {
+                (Some(file), Some(line)) => format!("{} at {}:{}", name, file.display(), line),
+                _ => name,
+            });
+            done = frames.len() >= count;
+        });
+        !done
+    });
+    frames
+}
@@ -0,0 +1,75 @@
+//! Capabilities of the terminal receiving the dumps printed to stdout.
+//!
+//! Colors are only written to a terminal, and never when `NO_COLOR` is set (see <https://no-color.org>) or `TERM` is
+//! `dumb`. The width of the terminal comes from `COLUMNS`, or from the terminal itself on Linux, Android, macOS and
+//! FreeBSD. Redirected output is neither colored nor cut, so logs and files keep the whole dump.
+
+use std::io::IsTerminal;
+
+/// What the terminal receiving stdout supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Terminal {
+    /// Whether the dumps are colored.
+    pub color: bool,
+    /// Number of columns of the terminal, `None` if stdout isn't a terminal or its width is unknown.
+    pub columns: Option<usize>,
+}
+
+/// Detects the capabilities of the terminal receiving stdout, with the `color` override of the global configuration.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let terminal = detect_terminal();
+/// println!("color: {}, columns: {:?}", terminal.color, terminal.columns);
+/// ```
+pub fn detect_terminal() -> Terminal {
+    let stdout = std::io::stdout();
+    let is_terminal = stdout.is_terminal();
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    let dumb = std::env::var_os("TERM").is_some_and(|term| term == "dumb");
+    let color = crate::config().color.unwrap_or(is_terminal && !no_color && !dumb);
+
+    let columns = if is_terminal {
+        let from_env = std::env::var("COLUMNS").ok().and_then(|columns| columns.trim().parse().ok()).filter(|columns| *columns > 0);
+        from_env.or_else(window_columns)
+    } else {
+        None
+    };
+    Terminal { color, columns }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+fn window_columns() -> Option<usize> {
+    use std::os::raw::{c_int, c_ulong};
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct WinSize {
+        ws_row: u16,
+        ws_col: u16,
+        ws_xpixel: u16,
+        ws_ypixel: u16,
+    }
+
+    extern "C" {
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    #[cfg(all(any(target_os = "linux", target_os = "android"), not(any(target_arch = "mips", target_arch = "mips64", target_arch = "powerpc", target_arch = "powerpc64", target_arch = "sparc64"))))]
+    const TIOCGWINSZ: c_ulong = 0x5413;
+    #[cfg(not(all(any(target_os = "linux", target_os = "android"), not(any(target_arch = "mips", target_arch = "mips64", target_arch = "powerpc", target_arch = "powerpc64", target_arch = "sparc64")))))]
+    const TIOCGWINSZ: c_ulong = 0x4008_7468;
+
+    let mut size = WinSize::default();
+    // TIOCGWINSZ only writes a `struct winsize` through the pointer
+    let result = unsafe { ioctl(1, TIOCGWINSZ, &mut size as *mut WinSize) };
+    (result == 0 && size.ws_col > 0).then_some(size.ws_col as usize)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos", target_os = "ios", target_os = "freebsd")))]
+fn window_columns() -> Option<usize> {
+    None
+}
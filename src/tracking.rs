@@ -0,0 +1,394 @@
+//! A global allocator recording the live allocations, to find and dump any heap block by its id.
+//!
+//! [`MemViewerAlloc`] wraps the allocator of the program, the system one by default, and keeps a record per live
+//! allocation: a sequential id, the address, the size and the alignment, a tag telling what it holds, and with the
+//! `symbols` feature the return addresses of the allocating call stack. [`live_allocations`] lists them,
+//! [`view_allocations`] prints them, and [`view_alloc!`](crate::view_alloc) dumps the bytes of one of them.
+//!
+//! The records are kept in the wrapped allocator: the allocations made while recording, by the tracker itself, are
+//! passed through without being recorded, so the tracker never calls itself.
+
+use core::alloc::{GlobalAlloc, Layout};
+use core::cell::Cell;
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::alloc::System;
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::sync::Mutex;
+
+use crate::render::{self, Table, MEMORY_CONTENT};
+
+/// Maximal number of return addresses recorded per allocation, the frames of the allocator included.
+const MAX_BACKTRACE: usize = 16;
+
+/// The live allocations by address.
+static REGISTRY: Mutex<BTreeMap<usize, Allocation>> = Mutex::new(BTreeMap::new());
+
+/// The id of the next allocation.
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    /// Whether the thread is recording, its allocations are then passed through.
+    static RECORDING: Cell<bool> = const { Cell::new(false) };
+    /// Tag of the allocations of the thread, set by `with_alloc_tag`.
+    static CURRENT_TAG: Cell<Option<&'static str>> = const { Cell::new(None) };
+}
+
+/// A live allocation recorded by [`MemViewerAlloc`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Allocation {
+    /// Sequential id of the allocation, from 1, given by [`view_alloc!`](crate::view_alloc).
+    pub id: u64,
+    /// Address of the first byte of the block.
+    pub addr: usize,
+    /// Number of bytes requested.
+    pub size: usize,
+    /// Alignment requested.
+    pub align: usize,
+    /// What the block holds, e.g. a type name, set by [`with_alloc_tag`] or [`tag_allocation`].
+    pub tag: Option<&'static str>,
+    /// Return addresses of the allocating call stack, innermost first, the frames of the allocator included. Empty
+    /// unless the backtraces are enabled with [`MemViewerAlloc::with_backtraces`].
+    pub backtrace: Vec<usize>,
+}
+
+/// A global allocator wrapping another one, recording the live allocations.
+///
+/// Installed with `#[global_allocator]`, every allocation of the program is recorded until it is freed: a reallocated
+/// block keeps its id and tag. Recording takes a global lock, so the allocations are slower, much slower with the
+/// backtraces, which are only recorded with the `symbols` feature.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// #[global_allocator]
+/// static GLOBAL: MemViewerAlloc = MemViewerAlloc::new().with_backtraces();
+///
+/// let my_buffer = with_alloc_tag("request buffer", || vec![0u8; 64]);
+/// let my_block = find_allocation(my_buffer.as_ptr() as usize).unwrap();
+/// view_alloc!(my_block.id);
+/// view_allocations();
+/// ```
+#[derive(Debug, Default)]
+pub struct MemViewerAlloc<A = System> {
+    inner: A,
+    backtraces: bool,
+}
+
+impl MemViewerAlloc<System> {
+    /// Wraps the system allocator.
+    pub const fn new() -> Self {
+        MemViewerAlloc { inner: System, backtraces: false }
+    }
+}
+
+impl<A> MemViewerAlloc<A> {
+    /// Wraps another allocator, e.g. `tikv_jemallocator::Jemalloc`.
+    pub const fn wrap(inner: A) -> Self {
+        MemViewerAlloc { inner, backtraces: false }
+    }
+
+    /// Records the return addresses of the allocating call stack with every allocation. Requires the `symbols`
+    /// feature, nothing is recorded without it.
+    pub const fn with_backtraces(mut self) -> Self {
+        self.backtraces = true;
+        self
+    }
+
+    /// Records a new block, or a reallocated one keeping the id, tag and backtrace of its `reused` record, unless the
+    /// thread is already recording.
+    fn record(&self, ptr: *mut u8, layout: Layout, reused: Option<Allocation>) {
+        if ptr.is_null() {
+            return;
+        }
+        let backtraces = self.backtraces;
+        recording(|| {
+            let (id, tag, backtrace) = match reused {
+                Some(old) => (old.id, old.tag, old.backtrace),
+                None => (
+                    NEXT_ID.fetch_add(1, Ordering::Relaxed),
+                    CURRENT_TAG.with(Cell::get),
+                    if backtraces { capture_backtrace() } else { Vec::new() },
+                ),
+            };
+            let allocation = Allocation { id, addr: ptr.addr(), size: layout.size(), align: layout.align(), tag, backtrace };
+            REGISTRY.lock().unwrap_or_else(|e| e.into_inner()).insert(ptr.addr(), allocation);
+        });
+    }
+
+    /// Forgets a freed block, returning its record, unless the thread is already recording.
+    fn forget(&self, ptr: *mut u8) -> Option<Allocation> {
+        recording(|| REGISTRY.lock().unwrap_or_else(|e| e.into_inner()).remove(&ptr.addr())).flatten()
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for MemViewerAlloc<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc(layout);
+        self.record(ptr, layout, None);
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc_zeroed(layout);
+        self.record(ptr, layout, None);
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.forget(ptr);
+        self.inner.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // Forgotten before it is freed, as another thread may get the old block as soon as it is
+        let old = self.forget(ptr);
+        let new_ptr = self.inner.realloc(ptr, layout, new_size);
+        match old {
+            Some(old) if new_ptr.is_null() => self.record(ptr, layout, Some(old)),
+            old => self.record(new_ptr, Layout::from_size_align_unchecked(new_size, layout.align()), old),
+        }
+        new_ptr
+    }
+}
+
+/// Runs `f` with the allocations of the thread passed through, or returns `None` without running it if the thread is
+/// already recording, or is being torn down.
+fn recording<R>(f: impl FnOnce() -> R) -> Option<R> {
+    let entered = RECORDING.try_with(|recording| !recording.replace(true)).unwrap_or(false);
+    if !entered {
+        return None;
+    }
+    let result = f();
+    RECORDING.with(|recording| recording.set(false));
+    Some(result)
+}
+
+/// Returns the return addresses of the current call stack, innermost first.
+fn capture_backtrace() -> Vec<usize> {
+    #[cfg(feature = "symbols")]
+    {
+        let mut backtrace = Vec::with_capacity(MAX_BACKTRACE);
+        backtrace::trace(|frame| {
+            backtrace.push(frame.ip() as usize);
+            backtrace.len() < MAX_BACKTRACE
+        });
+        backtrace
+    }
+    #[cfg(not(feature = "symbols"))]
+    Vec::new()
+}
+
+/// Runs `f` with the registry locked and the allocations of the thread passed through.
+fn with_registry<R>(f: impl FnOnce(&mut BTreeMap<usize, Allocation>) -> R) -> R {
+    let entered = RECORDING.try_with(|recording| !recording.replace(true)).unwrap_or(false);
+    let result = f(&mut REGISTRY.lock().unwrap_or_else(|e| e.into_inner()));
+    if entered {
+        RECORDING.with(|recording| recording.set(false));
+    }
+    result
+}
+
+/// Returns the live allocations recorded by [`MemViewerAlloc`], by id. Empty if it isn't the global allocator.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// for block in live_allocations().iter().filter(|block| block.size >= 4096) {
+///     println!("#{} {:x} {} bytes", block.id, block.addr, block.size);
+/// }
+/// ```
+pub fn live_allocations() -> Vec<Allocation> {
+    let mut allocations: Vec<Allocation> = with_registry(|registry| registry.values().cloned().collect());
+    allocations.sort_by_key(|allocation| allocation.id);
+    allocations
+}
+
+/// Returns the live allocation containing the byte at `addr`, if any.
+///
+/// # Argument
+///
+/// * `addr` - An address within the block, e.g. the address of a field of a boxed struct.
+pub fn find_allocation(addr: usize) -> Option<Allocation> {
+    with_registry(|registry| {
+        let (_, allocation) = registry.range(..=addr).next_back()?;
+        (addr < allocation.addr + allocation.size.max(1)).then(|| allocation.clone())
+    })
+}
+
+/// Tags the live allocation holding `value`, e.g. with its type name, and returns whether it is found.
+///
+/// # Arguments
+///
+/// * `value` - A value stored in the block.
+/// * `tag` - What the block holds.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_config = Box::new([0u32; 16]);
+/// tag_allocation(&*my_config, core::any::type_name::<[u32; 16]>());
+/// ```
+pub fn tag_allocation<T: ?Sized>(value: &T, tag: &'static str) -> bool {
+    let addr = (value as *const T as *const u8).addr();
+    with_registry(|registry| match registry.range_mut(..=addr).next_back() {
+        Some((_, allocation)) if addr < allocation.addr + allocation.size.max(1) => {
+            allocation.tag = Some(tag);
+            true
+        }
+        _ => false,
+    })
+}
+
+/// Runs `f` with the allocations it makes on this thread tagged with `tag`.
+///
+/// # Arguments
+///
+/// * `tag` - What the blocks hold, e.g. `"parser"`.
+/// * `f` - The code allocating them.
+pub fn with_alloc_tag<R>(tag: &'static str, f: impl FnOnce() -> R) -> R {
+    let outer = CURRENT_TAG.with(|current| current.replace(Some(tag)));
+    let result = f();
+    CURRENT_TAG.with(|current| current.set(outer));
+    result
+}
+
+/// Writes the list of the live allocations: an `Allc` line with their number and size, then a line per allocation.
+pub(crate) fn write_allocations(out: &mut String, allocations: &[Allocation]) -> fmt::Result {
+    let size: usize = allocations.iter().map(|allocation| allocation.size).sum();
+    writeln!(out, "Allc: {} live allocations, {} bytes", allocations.len(), size)?;
+    for allocation in allocations {
+        write!(out, "  #{:<6} {} {:>8} bytes, align {:>2}", allocation.id, crate::StyledAddr(allocation.addr), allocation.size, allocation.align)?;
+        if let Some(tag) = allocation.tag {
+            write!(out, ", {}", tag)?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(out)
+}
+
+/// Prints the live allocations recorded by [`MemViewerAlloc`], by id.
+pub fn view_allocations() {
+    let allocations = live_allocations();
+    render::emit(|out| write_allocations(out, &allocations));
+}
+
+/// Writes the return addresses of the allocating call stack, after the frames of the allocator and before those of the
+/// runtime.
+fn write_backtrace(out: &mut String, backtrace: &[usize]) -> fmt::Result {
+    #[cfg(feature = "symbols")]
+    {
+        const INTERNAL: [&str; 7] = ["backtrace::", "mem_viewer::tracking::", "<mem_viewer::tracking::", "__rust", "__rdl", "alloc::", "<alloc::"];
+        let frames = backtrace
+            .iter()
+            .map(|ip| (*ip, crate::frames::resolve_return_address(*ip)))
+            .skip_while(|(_, symbol)| symbol.as_ref().is_some_and(|symbol| INTERNAL.iter().any(|prefix| symbol.name.starts_with(prefix))))
+            .take_while(|(_, symbol)| !symbol.as_ref().is_some_and(|symbol| symbol.name.contains("__rust_begin_short_backtrace")));
+        for (index, (ip, symbol)) in frames.enumerate() {
+            let label = if index == 0 { "Bktr: " } else { "      " };
+            match symbol {
+                Some(symbol) => {
+                    write!(out, "{}{} {}", label, crate::StyledAddr(ip), symbol)?;
+                    if let (Some(file), Some(line)) = (&symbol.file, symbol.line) {
+                        write!(out, " at {}:{}", file, line)?;
+                    }
+                    writeln!(out)?;
+                }
+                None => writeln!(out, "{}{}", label, crate::StyledAddr(ip))?,
+            }
+        }
+    }
+    #[cfg(not(feature = "symbols"))]
+    for (index, ip) in backtrace.iter().enumerate() {
+        writeln!(out, "{}{}", if index == 0 { "Bktr: " } else { "      " }, crate::StyledAddr(*ip))?;
+    }
+    Ok(())
+}
+
+/// Formats a live allocation: its header, tag and backtrace, then its bytes copied in `bytes`.
+pub(crate) fn write_alloc(out: &mut String, allocation: &Allocation, bytes: &[u8]) -> fmt::Result {
+    crate::write::write_header(out, &format!("allocation #{}", allocation.id), allocation.tag.unwrap_or("untagged"), allocation.addr, allocation.size)?;
+    writeln!(out, "Algn: {}", allocation.align)?;
+    write_backtrace(out, &allocation.backtrace)?;
+    if crate::config().miri_friendly {
+        return render::write_miri_note(out);
+    }
+    Table { label: "allocation", ..Table::new(MEMORY_CONTENT, allocation.addr, bytes) }.write(out)
+}
+
+#[macro_export]
+/// Macro to view the memory content of a live allocation recorded by [`MemViewerAlloc`](crate::MemViewerAlloc), by
+/// its id.
+///
+/// The header gives the tag of the block as its type, its alignment, and with the backtraces enabled a `Bktr` line per
+/// frame of the allocating call stack, from the caller of the allocator. The bytes are copied while the block can't be
+/// freed. An id which isn't live prints a line saying so. In Miri-friendly mode the memory content isn't shown.
+///
+/// # Argument
+///
+/// * `id` - The id of the allocation, from [`live_allocations`](crate::live_allocations) or
+///   [`find_allocation`](crate::find_allocation).
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// #[global_allocator]
+/// static GLOBAL: MemViewerAlloc = MemViewerAlloc::new();
+///
+/// let my_name = with_alloc_tag("name", || String::from("mem"));
+/// view_alloc!(find_allocation(my_name.as_ptr() as usize).unwrap().id);
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: allocation #7
+/// Type: name
+/// Addr: 000055d1c4b2e9d0
+/// Size: 3 bytes
+/// Algn: 1
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8
+/// ----------------------Memory Content--------------------
+///  000055d1c4b2e9d0 | 6d  | 109 | 01101101 |   m   | m
+///  000055d1c4b2e9d1 | 65  | 101 | 01100101 |   e   | e
+///  000055d1c4b2e9d2 | 6d  | 109 | 01101101 |   m   | m
+/// ```
+macro_rules! view_alloc {
+    ($id: expr) => {
+        $crate::_show_alloc($id)
+    };
+}
+
+/// Displays the memory content of the live allocation `id`.
+///
+/// (This is supposed to be private usage of view_alloc! macro usage.)
+///
+/// # Argument
+///
+/// * `id` - The id of the allocation.
+pub fn _show_alloc(id: u64) {
+    let miri_friendly = crate::config().miri_friendly;
+    // The registry stays locked while the bytes are copied, so the block can't be freed meanwhile
+    let found = with_registry(|registry| {
+        let allocation = registry.values().find(|allocation| allocation.id == id)?.clone();
+        let bytes = if miri_friendly {
+            Vec::new()
+        } else {
+            // SAFETY: the block is live, the allocator can't free it without the lock
+            unsafe { crate::dump::read_volatile_bytes(core::ptr::with_exposed_provenance(allocation.addr), allocation.size) }
+        };
+        Some((allocation, bytes))
+    });
+    match found {
+        Some((allocation, bytes)) => render::emit(|out| write_alloc(out, &allocation, &bytes)),
+        None => render::emit(|out| writeln!(out, "Allc: no live allocation #{}, freed or not recorded by MemViewerAlloc\n", id)),
+    }
+}
@@ -0,0 +1,105 @@
+//! Byte transformations previewed next to the original bytes, or applied before display.
+//!
+//! Set [`Config::transform`](crate::Config::transform) to add the transformed bytes to the memory content tables, e.g.
+//! to see at a glance whether a codec wrote big-endian words where little-endian ones were expected. Set
+//! [`Config::decode`](crate::Config::decode) to show the transformed bytes instead of the original ones, e.g. a
+//! payload obfuscated with a XOR key.
+
+use core::fmt;
+
+use alloc::vec::Vec;
+
+/// A transformation of the bytes of a region.
+///
+/// Swaps reverse the bytes of every group of 2, 4 or 8 bytes, counted from the start of the region. The bytes of a
+/// last incomplete group are left as they are. The XOR key repeats from the start of the region as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transform {
+    /// Reverses the bytes of every 16-bit word.
+    Swap16,
+    /// Reverses the bytes of every 32-bit word.
+    Swap32,
+    /// Reverses the bytes of every 64-bit word.
+    Swap64,
+    /// Reverses the bits of every byte, e.g. for LSB-first serial protocols.
+    BitReverse,
+    /// XORs the bytes with a repeating key, e.g. `Xor(&[0x5a])` for a single-byte key. An empty key changes nothing.
+    Xor(&'static [u8]),
+    /// Keeps the bits of every byte set in the mask, e.g. `Mask(0x7f)` to clear the parity bits.
+    Mask(u8),
+}
+
+impl Transform {
+    /// Returns the 4 letters name of the transformation, the title of its columns.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Transform::Swap16 => "Sw16",
+            Transform::Swap32 => "Sw32",
+            Transform::Swap64 => "Sw64",
+            Transform::BitReverse => "BRev",
+            Transform::Xor(_) => "XorK",
+            Transform::Mask(_) => "Mask",
+        }
+    }
+
+    /// Returns the transformed byte at `index` of `bytes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The original bytes of the region.
+    /// * `index` - The index of the byte, which must be in `bytes`.
+    pub fn byte_at(&self, bytes: &[u8], index: usize) -> u8 {
+        let group = match self {
+            Transform::Swap16 => 2,
+            Transform::Swap32 => 4,
+            Transform::Swap64 => 8,
+            Transform::BitReverse => return bytes[index].reverse_bits(),
+            Transform::Xor([]) => return bytes[index],
+            Transform::Xor(key) => return bytes[index] ^ key[index % key.len()],
+            Transform::Mask(mask) => return bytes[index] & mask,
+        };
+        let start = index - index % group;
+        if start + group > bytes.len() {
+            return bytes[index];
+        }
+        bytes[start + group - 1 - index % group]
+    }
+
+    /// Returns the transformed bytes.
+    ///
+    /// # Argument
+    ///
+    /// * `bytes` - The original bytes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// assert_eq!(Transform::Swap32.apply(&[1, 2, 3, 4, 5, 6]), [4, 3, 2, 1, 5, 6]);
+    /// assert_eq!(Transform::BitReverse.apply(&[0b0000_0001]), [0b1000_0000]);
+    /// assert_eq!(Transform::Xor(&[0x20]).apply(b"HELLO"), *b"hello");
+    /// ```
+    pub fn apply(&self, bytes: &[u8]) -> Vec<u8> {
+        (0..bytes.len()).map(|index| self.byte_at(bytes, index)).collect()
+    }
+}
+
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Transform::Swap16 => f.write_str("byte swap of every 16-bit word"),
+            Transform::Swap32 => f.write_str("byte swap of every 32-bit word"),
+            Transform::Swap64 => f.write_str("byte swap of every 64-bit word"),
+            Transform::BitReverse => f.write_str("bit reversal of every byte"),
+            Transform::Xor(key) => {
+                f.write_str("XOR with the key")?;
+                for byte in key.iter() {
+                    write!(f, " {:02x}", byte)?;
+                }
+                Ok(())
+            }
+            Transform::Mask(mask) => write!(f, "AND with the mask {:02x}", mask),
+        }
+    }
+}
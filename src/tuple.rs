@@ -0,0 +1,134 @@
+//! Layouts of the tuples, with their elements in memory order.
+//!
+//! The compiler is free to store the elements of a tuple in any order, and usually sorts them to reduce the padding:
+//! the bytes of `(u8, u32, u16)` start with the `u32`. The dumps of a tuple list its elements with their offset, size
+//! and type, in the order they are stored, and annotate the bytes of each element and the padding.
+
+use core::fmt;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::layout::{FieldLayout, StructLayout};
+use crate::_ValueProbe;
+
+/// Returns the layout of a tuple.
+///
+/// (This is supposed to be private usage of view_mem! macro usage.)
+pub trait _TupleLayout {
+    /// Returns the layout of the tuple, its elements named `.0`, `.1`, ...
+    fn tuple_layout(&self) -> Option<StructLayout>;
+}
+
+/// Returns no layout for the values which aren't tuples.
+///
+/// (This is supposed to be private usage of view_mem! macro usage.)
+pub trait _NoTupleLayout {
+    /// Returns `None`.
+    fn tuple_layout(&self) -> Option<StructLayout>;
+}
+
+impl<T: ?Sized> _NoTupleLayout for &_ValueProbe<'_, T> {
+    fn tuple_layout(&self) -> Option<StructLayout> {
+        None
+    }
+}
+
+macro_rules! tuple_layouts {
+    ($(($($index: tt $element: ident),+)),* $(,)?) => {$(
+        tuple_layouts!(@impl ($($element,)+), $($index $element),+);
+    )*};
+    (@impl $tuple: ty, $($index: tt $element: ident),+) => {
+        impl<$($element),+> _TupleLayout for _ValueProbe<'_, $tuple> {
+            fn tuple_layout(&self) -> Option<StructLayout> {
+                Some(StructLayout {
+                    name: core::any::type_name::<$tuple>(),
+                    size: core::mem::size_of::<$tuple>(),
+                    align: core::mem::align_of::<$tuple>(),
+                    fields: alloc::vec![$(FieldLayout {
+                        name: concat!(".", stringify!($index)),
+                        type_name: core::any::type_name::<$element>(),
+                        offset: core::mem::offset_of!($tuple, $index),
+                        size: core::mem::size_of::<$element>(),
+                        align: core::mem::align_of::<$element>(),
+                    }),+],
+                })
+            }
+        }
+    };
+}
+
+tuple_layouts!(
+    (0 A),
+    (0 A, 1 B),
+    (0 A, 1 B, 2 C),
+    (0 A, 1 B, 2 C, 3 D),
+    (0 A, 1 B, 2 C, 3 D, 4 E),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K, 11 L),
+);
+
+/// Writes the `Elem`, `Ordr` and `Padd` header lines of a tuple: its elements in memory order with their offset, size
+/// and type, whether the compiler reordered them, and its padding.
+pub(crate) fn write_elements<W: fmt::Write + ?Sized>(out: &mut W, layout: &StructLayout) -> fmt::Result {
+    let stored = layout.fields_by_offset();
+    for (index, field) in stored.iter().enumerate() {
+        let label = if index == 0 { "Elem: " } else { "      " };
+        let plural = if field.size == 1 { "" } else { "s" };
+        writeln!(out, "{}{}: {} at +{:#x}, {} byte{}", label, field.name, field.type_name, field.offset, field.size, plural)?;
+    }
+    let order: Vec<&str> = stored.iter().map(|field| field.name).collect();
+    if stored.iter().map(|field| field.name).eq(layout.fields.iter().map(|field| field.name)) {
+        writeln!(out, "Ordr: {} in memory, the declaration order", order.join(" "))?;
+    } else {
+        writeln!(out, "Ordr: {} in memory, reordered by the compiler to reduce the padding", order.join(" "))?;
+    }
+    let padding = layout.padding();
+    if padding.is_empty() {
+        return writeln!(out, "Padd: none");
+    }
+    let ranges: Vec<String> = padding.iter().map(|range| format!("{} at +{:#x}", range.len(), range.start)).collect();
+    let total: usize = padding.iter().map(|range| range.len()).sum();
+    writeln!(out, "Padd: {} byte{} ({}), to align the elements", total, if total == 1 { "" } else { "s" }, ranges.join(", "))
+}
+
+// `safe_view_mem!` serializes the elements of a tuple one after the other, in declaration order and without padding:
+// the container tells where each one starts
+#[cfg(feature = "std")]
+macro_rules! safe_tuples {
+    ($(($($index: tt $element: ident),+)),* $(,)?) => {$(
+        impl<$($element: serde::Serialize),+> crate::_SafeElements for crate::_SafeValue<'_, ($($element,)+)> {
+            fn container(&self, _size: usize) -> (Vec<u8>, Option<crate::_ContainerElements>) {
+                let mut container = Vec::new();
+                let elements = alloc::vec![$({
+                    let offset = container.len();
+                    bincode::serialize_into(&mut container, &(self.0).$index).unwrap();
+                    (core::any::type_name::<$element>(), offset)
+                }),+];
+                (container, Some(crate::_ContainerElements::Tuple(elements)))
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "std")]
+safe_tuples!(
+    (0 A),
+    (0 A, 1 B),
+    (0 A, 1 B, 2 C),
+    (0 A, 1 B, 2 C, 3 D),
+    (0 A, 1 B, 2 C, 3 D, 4 E),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K),
+    (0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H, 8 I, 9 J, 10 K, 11 L),
+);
@@ -0,0 +1,88 @@
+//! Dumps of `MaybeUninit` values, whose bytes may be uninitialized.
+//!
+//! Casting a `MaybeUninit<T>` to bytes to dump it is easy to get wrong, and the bytes may mean nothing. The
+//! [`view_maybe_uninit!`](crate::view_maybe_uninit) macro dumps them with the caveat in the header and every byte
+//! marked as possibly uninitialized.
+
+use std::fmt::{self, Write};
+use std::mem::MaybeUninit;
+
+use crate::render::{self, Table, MEMORY_CONTENT};
+
+/// Formats the bytes of a `MaybeUninit`, marked as possibly uninitialized.
+pub(crate) fn write_maybe_uninit<T>(out: &mut String, name: &str, value: &MaybeUninit<T>) -> fmt::Result {
+    let ptr = value.as_ptr() as *const u8;
+    let size = core::mem::size_of::<T>();
+    writeln!(out, "Name: {}", name)?;
+    writeln!(out, "Type: {}", core::any::type_name::<MaybeUninit<T>>())?;
+    writeln!(out, "Addr: {}", crate::_HeaderAddr(ptr.addr()))?;
+    writeln!(out, "Size: {} bytes", size)?;
+    writeln!(out, "Unin: the bytes may be uninitialized: their values are leftovers of the memory, can change from a dump to the")?;
+    writeln!(out, "      next, and don't tell whether the value was written")?;
+    if crate::config().miri_friendly {
+        return render::write_miri_note(out);
+    }
+    if size == 0 {
+        return render::write_zst_note(out, ptr.addr());
+    }
+
+    // Volatile reads keep the compiler from assuming anything about the uninitialized bytes
+    let bytes = unsafe { crate::dump::read_volatile_bytes(ptr, size) };
+    let annotate = |_: usize, out: &mut dyn fmt::Write| out.write_str("possibly uninitialized");
+    Table { label: name, annotate: Some(&annotate), ..Table::new(MEMORY_CONTENT, ptr.addr(), &bytes) }.write(out)
+}
+
+#[macro_export]
+/// Macro to view the bytes of a `MaybeUninit<T>`, whether it was initialized or not.
+///
+/// The header warns that the bytes may be uninitialized, and every byte is annotated as possibly uninitialized: they
+/// are read with volatile reads, but the values of uninitialized bytes are leftovers of the memory which can change
+/// from a dump to the next. A debugging aid, not a way to tell whether the value was written. In Miri-friendly mode
+/// the bytes aren't read, as reading uninitialized memory is undefined behavior.
+///
+/// # Argument
+///
+/// * `x` - The `MaybeUninit` to dump.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+/// use std::mem::MaybeUninit;
+///
+/// let mut my_slot: MaybeUninit<u16> = MaybeUninit::uninit();
+/// my_slot.write(0x0145);
+/// view_maybe_uninit!(my_slot);
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_slot
+/// Type: core::mem::maybe_uninit::MaybeUninit<u16>
+/// Addr: 00007ffe59ac3f3e
+/// Size: 2 bytes
+/// Unin: the bytes may be uninitialized: their values are leftovers of the memory, can change from a dump to the
+///       next, and don't tell whether the value was written
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ----------------------Memory Content--------------------
+///  00007ffe59ac3f3e | 45  | 069 | 01000101 |   E   | ... | possibly uninitialized
+///  00007ffe59ac3f3f | 01  | 001 | 00000001 |  SOH  | XXX | possibly uninitialized
+/// ```
+macro_rules! view_maybe_uninit {
+    ($x: expr) => {
+        $crate::_show_maybe_uninit(stringify!($x), &$x)
+    };
+}
+
+/// Displays the bytes of a `MaybeUninit`, marked as possibly uninitialized.
+///
+/// (This is supposed to be private usage of view_maybe_uninit! macro usage.)
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `value` - The `MaybeUninit` to display.
+pub fn _show_maybe_uninit<T>(name: &str, value: &MaybeUninit<T>) {
+    render::emit(|out| write_maybe_uninit(out, name, value));
+}
@@ -0,0 +1,248 @@
+//! Dumps of UTF-16 text.
+//!
+//! Windows strings (`OsString` and the `W` functions of its API), the `widestring` crate, Java and JavaScript engines
+//! store text as 16-bit code units, 2 bytes per unit and 4 for the characters above U+FFFF, split in a surrogate
+//! pair. The UTF-8 and ASCII columns don't read them: the UTF-16 view decodes the text in the header and annotates
+//! every code unit with its character, pairing the surrogates and flagging the unpaired ones.
+
+use core::fmt;
+
+use alloc::vec::Vec;
+
+use crate::render::{Table, MEMORY_CONTENT};
+
+/// The byte order of UTF-16 text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Utf16 {
+    /// UTF-16LE, the low byte of every code unit first: Windows, and most machines (default).
+    #[default]
+    Le,
+    /// UTF-16BE, the high byte of every code unit first: Java class files, network protocols.
+    Be,
+}
+
+impl Utf16 {
+    /// Returns the name of the encoding, `UTF-16LE` or `UTF-16BE`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Utf16::Le => "UTF-16LE",
+            Utf16::Be => "UTF-16BE",
+        }
+    }
+
+    /// Returns the code units made of `bytes`, without the last byte if it is alone.
+    fn units(self, bytes: &[u8]) -> Vec<u16> {
+        let unit = |pair: &[u8]| match self {
+            Utf16::Le => u16::from_le_bytes([pair[0], pair[1]]),
+            Utf16::Be => u16::from_be_bytes([pair[0], pair[1]]),
+        };
+        bytes.chunks_exact(2).map(unit).collect()
+    }
+}
+
+/// What a code unit is in the text.
+enum Unit {
+    /// A character by itself.
+    Char(char),
+    /// The high surrogate of a pair, with the character of the pair.
+    High(char),
+    /// The low surrogate of a pair.
+    Low,
+    /// A surrogate without its other half.
+    Unpaired,
+}
+
+/// Returns what the code unit at `index` of `units` is.
+fn unit_at(units: &[u16], index: usize) -> Unit {
+    let is_high = |unit: u16| (0xd800..0xdc00).contains(&unit);
+    let is_low = |unit: u16| (0xdc00..0xe000).contains(&unit);
+    let unit = units[index];
+    if is_high(unit) {
+        match units.get(index + 1) {
+            Some(&low) if is_low(low) => {
+                let scalar = 0x10000 + (((unit as u32) - 0xd800) << 10) + (low as u32 - 0xdc00);
+                char::from_u32(scalar).map_or(Unit::Unpaired, Unit::High)
+            }
+            _ => Unit::Unpaired,
+        }
+    } else if is_low(unit) {
+        match index.checked_sub(1).map(|previous| units[previous]) {
+            Some(high) if is_high(high) => Unit::Low,
+            _ => Unit::Unpaired,
+        }
+    } else {
+        char::from_u32(unit as u32).map_or(Unit::Unpaired, Unit::Char)
+    }
+}
+
+/// Writes the annotation of the byte at `offset` of UTF-16 text: the code unit starting there, with its character, or
+/// only whether it is a surrogate when redacting.
+fn annotate(units: &[u16], offset: usize, redact: bool, out: &mut dyn fmt::Write) -> fmt::Result {
+    let index = offset / 2;
+    if index >= units.len() {
+        return out.write_str("(not a whole code unit)");
+    }
+    if offset % 2 == 1 {
+        return Ok(());
+    }
+    let unit = units[index];
+    match unit_at(units, index) {
+        Unit::Char(_) if redact => Ok(()),
+        Unit::High(_) if redact => out.write_str("high surrogate"),
+        Unit::Low if redact => out.write_str("low surrogate"),
+        Unit::Unpaired if redact => out.write_str("UNPAIRED surrogate, not a character"),
+        Unit::Char(c) => write!(out, "U+{:04X} '{}'", unit, c.escape_debug()),
+        Unit::High(c) => write!(out, "U+{:04X} high surrogate, with the next: U+{:04X} '{}'", unit, c as u32, c.escape_debug()),
+        Unit::Low => write!(out, "U+{:04X} low surrogate", unit),
+        Unit::Unpaired => write!(out, "U+{:04X} UNPAIRED surrogate, not a character", unit),
+    }
+}
+
+/// Writes the `Encd` and `Text` lines of UTF-16 text, then its bytes annotated with their code units.
+///
+/// The text is decoded in the header, the unpaired surrogates replaced by `\u{fffd}`, with the number of code units
+/// and characters; the bytes follow in a memory content table, every code unit annotated with its character. A last
+/// byte alone isn't part of the text. With the `redact` configuration the text and the characters are left out.
+///
+/// # Arguments
+///
+/// * `out` - The writer receiving the lines and the table.
+/// * `addr` - The address shown for the first byte.
+/// * `bytes` - The bytes of the text.
+/// * `order` - The byte order of the code units.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_bytes = [0x48, 0x00, 0xac, 0x20];
+/// let mut out = String::new();
+/// write_utf16(&mut out, 0, &my_bytes, Utf16::Le).unwrap();
+/// assert!(out.starts_with("Encd: UTF-16LE, 2 code units, 2 chars\nText: \"H€\"\n"));
+/// assert!(out.contains("| U+20AC '€'\n"));
+/// ```
+pub fn write_utf16<W: fmt::Write + ?Sized>(out: &mut W, addr: usize, bytes: &[u8], order: Utf16) -> fmt::Result {
+    write_units(out, addr, bytes, order, crate::config().redact)
+}
+
+/// Writes UTF-16 text like [`write_utf16`], without the text and the characters when redacting.
+pub(crate) fn write_units<W: fmt::Write + ?Sized>(out: &mut W, addr: usize, bytes: &[u8], order: Utf16, redact: bool) -> fmt::Result {
+    let units = order.units(bytes);
+    let chars = char::decode_utf16(units.iter().copied()).count();
+    let unpaired = (0..units.len()).filter(|index| matches!(unit_at(&units, *index), Unit::Unpaired)).count();
+    write!(out, "Encd: {}, {} code unit{}, {} char{}", order.name(), units.len(), if units.len() == 1 { "" } else { "s" }, chars, if chars == 1 { "" } else { "s" })?;
+    if unpaired > 0 {
+        write!(out, ", INVALID: {} unpaired surrogate{}", unpaired, if unpaired == 1 { "" } else { "s" })?;
+    }
+    if bytes.len() % 2 == 1 {
+        write!(out, ", the last byte isn't a whole code unit")?;
+    }
+    writeln!(out)?;
+    if redact {
+        writeln!(out, "Text: redacted")?;
+    } else {
+        out.write_str("Text: \"")?;
+        for c in char::decode_utf16(units.iter().copied()) {
+            write!(out, "{}", c.unwrap_or(char::REPLACEMENT_CHARACTER).escape_debug())?;
+        }
+        writeln!(out, "\"")?;
+    }
+
+    let annotate = |offset: usize, out: &mut dyn fmt::Write| annotate(&units, offset, redact, out);
+    Table { annotate: Some(&annotate), redact, ..Table::new(MEMORY_CONTENT, addr, bytes) }.write_printed(out)
+}
+
+#[cfg(feature = "std")]
+#[macro_export]
+/// Macro to view the memory content of UTF-16 text, decoded, with every code unit annotated.
+///
+/// Works with any value holding UTF-16 code units: a `[u16]` slice or array, the content of a `Vec<u16>` from
+/// `OsStr::encode_wide`, a `widestring` string or a buffer filled over FFI. The text is decoded on a `Text` line, below
+/// an `Encd` line with the number of code units and characters, flagging the unpaired surrogates; every code unit is
+/// annotated with its character in the table, the high surrogates with the character of their pair. The byte order
+/// follows the value, `Le` (default) or `Be`. With the `redact` configuration the text and the characters are left
+/// out. In Miri-friendly mode the memory content isn't shown.
+///
+/// # Arguments
+///
+/// * `var` - The text to dump, dereferenced like for `view_mem!`.
+/// * `order` - Optionally, the byte order of the code units, `Le` or `Be`.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_wide: Vec<u16> = "Hé🦀".encode_utf16().collect();
+/// view_utf16!(*my_wide);
+///
+/// let my_java_bytes = [0x00u8, 0x4f, 0x00, 0x4b];
+/// view_utf16!(my_java_bytes, Be);
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: *my_wide
+/// Type: [u16]
+/// Addr: 000055d0f1f2bb10
+/// Size: 8 bytes
+/// Encd: UTF-16LE, 4 code units, 3 chars
+/// Text: "Hé🦀"
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ----------------------Memory Content--------------------
+///  000055d0f1f2bb10 | 48  | 072 | 01001000 |   H   | ... | U+0048 'H'
+///  000055d0f1f2bb11 | 00  | 000 | 00000000 |  NUL  | ... |
+///  000055d0f1f2bb12 | e9  | 233 | 11101001 |  ...  | ... | U+00E9 'é'
+///  000055d0f1f2bb13 | 00  | 000 | 00000000 |  NUL  | ... |
+///  000055d0f1f2bb14 | 3e  | 062 | 00111110 |   >   | ... | U+D83E high surrogate, with the next: U+1F980 '🦀'
+///  000055d0f1f2bb15 | d8  | 216 | 11011000 |  ...  | XXX |
+///  000055d0f1f2bb16 | 80  | 128 | 10000000 |  ...  | XXX | U+DD80 low surrogate
+///  000055d0f1f2bb17 | dd  | 221 | 11011101 |  ...  | XXX |
+///
+/// Name: my_java_bytes
+/// Type: [u8; 4]
+/// Addr: 00007ffd2c4e1a1c
+/// Size: 4 bytes
+/// Encd: UTF-16BE, 2 code units, 2 chars
+/// Text: "OK"
+///      Address      | Hex | Dec |    Bin   | ASCII | UTF-8 | Annotation
+/// ----------------------Memory Content--------------------
+///  00007ffd2c4e1a1c | 00  | 000 | 00000000 |  NUL  |  O K | U+004F 'O'
+///  00007ffd2c4e1a1d | 4f  | 079 | 01001111 |   O   | XXX |
+///  00007ffd2c4e1a1e | 00  | 000 | 00000000 |  NUL  | XXX | U+004B 'K'
+///  00007ffd2c4e1a1f | 4b  | 075 | 01001011 |   K   | XXX |
+/// ```
+macro_rules! view_utf16 {
+    ($var: expr) => {
+        $crate::_show_utf16(stringify!($var), &$var, $crate::Utf16::Le)
+    };
+    ($var: expr, $order: ident) => {
+        $crate::_show_utf16(stringify!($var), &$var, $crate::Utf16::$order)
+    };
+}
+
+/// Displays the memory content of UTF-16 text, decoded.
+///
+/// (This is supposed to be private usage of view_utf16! macro usage.)
+///
+/// # Arguments
+///
+/// * `name` - The name displayed in the header.
+/// * `text` - The text to display.
+/// * `order` - The byte order of the code units.
+#[cfg(feature = "std")]
+pub fn _show_utf16<T: ?Sized>(name: &str, text: &T, order: Utf16) {
+    crate::render::emit(|out| {
+        let addr = (text as *const T as *const u8).addr();
+        let size = core::mem::size_of_val(text);
+        crate::write::write_header(out, name, core::any::type_name::<T>(), addr, size)?;
+        if crate::config().miri_friendly {
+            return crate::render::write_miri_note(out);
+        }
+        // The code unit types and byte arrays have no padding, all their bytes are initialized
+        let bytes = unsafe { core::slice::from_raw_parts(text as *const T as *const u8, size) };
+        write_utf16(out, addr, bytes, order)
+    });
+}
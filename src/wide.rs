@@ -0,0 +1,92 @@
+//! Decoded values of the 128-bit integers.
+//!
+//! The 16 bytes of a `u128` or an `i128` are hard to read back, the more so in little-endian order. The dumps of these
+//! types print the decoded value in decimal and hexadecimal in the header, with its two 64-bit halves, and annotate the
+//! bytes of each half.
+
+use core::fmt;
+
+/// Offset of the low 64-bit half of a 128-bit integer in memory.
+#[cfg(target_endian = "little")]
+const LOW: usize = 0;
+#[cfg(target_endian = "big")]
+const LOW: usize = 8;
+
+/// Offset of the high 64-bit half of a 128-bit integer in memory.
+const HIGH: usize = 8 - LOW;
+
+/// A `u128` or an `i128` decoded from its bytes, in the byte order of the machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WideInt {
+    /// The bits of the value.
+    bits: u128,
+    /// Whether the value is an `i128`.
+    signed: bool,
+}
+
+impl WideInt {
+    /// Decodes the value of type `type_name` made of `bytes`, if it is a `u128` or an `i128`.
+    pub(crate) fn decode(type_name: &str, bytes: &[u8]) -> Option<Self> {
+        let signed = match type_name {
+            "u128" => false,
+            "i128" => true,
+            _ => return None,
+        };
+        Some(WideInt { bits: u128::from_ne_bytes(bytes.try_into().ok()?), signed })
+    }
+
+    /// Returns the low 64 bits.
+    fn low(self) -> u64 {
+        self.bits as u64
+    }
+
+    /// Returns the high 64 bits.
+    fn high(self) -> u64 {
+        (self.bits >> 64) as u64
+    }
+
+    /// Returns the `Valu` header line: the value in decimal, then in hexadecimal.
+    pub(crate) fn value(self) -> Value {
+        Value(self)
+    }
+
+    /// Returns the `Hlvs` header line: the 64-bit halves with the bytes they occupy.
+    pub(crate) fn halves(self) -> Halves {
+        Halves(self)
+    }
+
+    /// Writes the annotation of the byte at `offset`: the half containing it, with its value unless redacting.
+    pub(crate) fn annotate(self, offset: usize, redact: bool, out: &mut dyn fmt::Write) -> fmt::Result {
+        match offset {
+            LOW | HIGH if redact => write!(out, "{} 64 bits", if offset == LOW { "low" } else { "high" }),
+            LOW => write!(out, "low 64 bits = {:#x}", self.low()),
+            HIGH => write!(out, "high 64 bits = {:#x}", self.high()),
+            offset if (LOW..LOW + 8).contains(&offset) => write!(out, "low +{}", offset - LOW),
+            offset => write!(out, "high +{}", offset - HIGH),
+        }
+    }
+}
+
+/// The `Valu` header line of a 128-bit integer, see [`WideInt::value`].
+pub(crate) struct Value(WideInt);
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let WideInt { bits, signed } = self.0;
+        match signed {
+            true => write!(f, "{} ({:#034x})", bits as i128, bits),
+            false => write!(f, "{} ({:#034x})", bits, bits),
+        }
+    }
+}
+
+/// The `Hlvs` header line of a 128-bit integer, see [`WideInt::halves`].
+pub(crate) struct Halves(WideInt);
+
+impl fmt::Display for Halves {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let order = if cfg!(target_endian = "little") { "little-endian" } else { "big-endian" };
+        write!(f, "low {:#018x} at +{:#x}..+{:#x}, ", self.0.low(), LOW, LOW + 8)?;
+        write!(f, "high {:#018x} at +{:#x}..+{:#x} ({})", self.0.high(), HIGH, HIGH + 8, order)
+    }
+}
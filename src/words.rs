@@ -0,0 +1,107 @@
+//! Words decoded next to the bytes in the memory content tables.
+//!
+//! Set [`Config::words`](crate::Config::words) to add the value of every 2, 4 or 8-byte word to the tables, in little-
+//! and big-endian, e.g. to read the lengths and pointers stored in a struct without decoding the bytes by hand.
+
+use core::fmt;
+
+/// A word size of the decoded words.
+///
+/// The words are counted from the start of the region: the value of a word is shown on the row of its first byte, and
+/// the bytes of a last incomplete word have none.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Word {
+    /// 16-bit words.
+    U16,
+    /// 32-bit words.
+    U32,
+    /// 64-bit words.
+    U64,
+}
+
+impl Word {
+    /// Returns the size of a word in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            Word::U16 => 2,
+            Word::U32 => 4,
+            Word::U64 => 8,
+        }
+    }
+
+    /// Returns the Rust name of the word type, e.g. `u32`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Word::U16 => "u16",
+            Word::U32 => "u32",
+            Word::U64 => "u64",
+        }
+    }
+
+    /// Returns the little- and big-endian values of the word starting at `index` of `bytes`, `None` if no word starts
+    /// there.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The bytes of the region.
+    /// * `index` - The index of a byte of the region.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mem_viewer::*;
+    ///
+    /// let bytes = [0x01, 0x02, 0x03, 0x04, 0x05];
+    /// assert_eq!(Word::U16.values_at(&bytes, 2), Some((0x0403, 0x0304)));
+    /// assert_eq!(Word::U16.values_at(&bytes, 3), None);
+    /// assert_eq!(Word::U32.values_at(&bytes, 4), None);
+    /// ```
+    pub fn values_at(self, bytes: &[u8], index: usize) -> Option<(u64, u64)> {
+        let size = self.size();
+        if !index.is_multiple_of(size) || index + size > bytes.len() {
+            return None;
+        }
+        let word = &bytes[index..index + size];
+        let little = word.iter().rev().fold(0, |value, byte| value << 8 | *byte as u64);
+        let big = word.iter().fold(0, |value, byte| value << 8 | *byte as u64);
+        Some((little, big))
+    }
+
+    /// Returns the index of the byte shown at `index` when the bytes of every word are swapped: the bytes of a word
+    /// are shown from the last one, the bytes of a last incomplete word in order.
+    pub(crate) fn swapped_index(self, len: usize, index: usize) -> usize {
+        let size = self.size();
+        let start = index - index % size;
+        if start + size > len {
+            index
+        } else {
+            start + size - 1 - index % size
+        }
+    }
+
+    /// Width of the value of a word: `0x` and 2 hex digits per byte.
+    fn value_width(self) -> usize {
+        2 + 2 * self.size()
+    }
+
+    /// Width of the columns in a row: `| 0x0403 | 0x0304 `.
+    pub(crate) fn width(self) -> usize {
+        2 * (self.value_width() + 3)
+    }
+
+    /// Writes the titles of the columns of the words, e.g. ` | u16 LE | u16 BE`.
+    pub(crate) fn write_titles<W: fmt::Write + ?Sized>(self, out: &mut W) -> fmt::Result {
+        // Padded to the width of the values
+        let padding = self.value_width() - "u16 LE".len();
+        write!(out, " | {} LE{:padding$} | {} BE{:padding$}", self.name(), "", self.name(), "", padding = padding)
+    }
+
+    /// Writes the columns of the byte at `index` of `bytes`: the values of the word starting there, or blanks.
+    pub(crate) fn write_columns<W: fmt::Write + ?Sized>(self, out: &mut W, bytes: &[u8], index: usize) -> fmt::Result {
+        let width = self.value_width();
+        match self.values_at(bytes, index) {
+            Some((little, big)) => write!(out, "| {:#0width$x} | {:#0width$x} ", little, big, width = width),
+            None => write!(out, "| {:width$} | {:width$} ", "", "", width = width),
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Dumps written into a user-supplied writer.
+//!
+//! These functions only need `core`, so they also work without the `std` feature, e.g. on embedded targets or in a
+//! kernel: write the dump into any `fmt::Write` (a UART or log sink), or into a byte buffer with [`SliceWriter`].
+
+use core::fmt;
+
+use crate::sink::{sink_mem, sink_mem_raw, sink_mem_volatile, FmtSink};
+
+/// Writes the dump of `value` (header and memory content table) into `out`.
+///
+/// The output is the same as `view_mem!` without the `Aloc` line, which relies on a user-space address heuristic.
+///
+/// # Arguments
+///
+/// * `out` - The writer receiving the dump.
+/// * `name` - The name displayed in the header.
+/// * `value` - The value to dump, its `size_of_val` bytes are displayed.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let my_u32: u32 = 0xdeadbeef;
+/// let mut buf = [0u8; 1024];
+/// let mut out = SliceWriter::new(&mut buf);
+/// write_mem(&mut out, "my_u32", &my_u32).unwrap();
+/// assert!(out.as_str().starts_with("Name: my_u32\nType: u32\n"));
+/// ```
+pub fn write_mem<W: fmt::Write + ?Sized, T: ?Sized>(out: &mut W, name: &str, value: &T) -> fmt::Result {
+    sink_mem(&mut FmtSink(out), name, value)
+}
+
+/// Writes the dump of `len` bytes starting at `ptr` into `out`.
+///
+/// # Arguments
+///
+/// * `out` - The writer receiving the dump.
+/// * `name` - The name displayed in the header.
+/// * `ptr` - The address of the first byte.
+/// * `len` - The number of bytes to display.
+///
+/// # Safety
+///
+/// The whole region must be readable.
+pub unsafe fn write_mem_raw<W: fmt::Write + ?Sized>(out: &mut W, name: &str, ptr: *const u8, len: usize) -> fmt::Result {
+    sink_mem_raw(&mut FmtSink(out), name, ptr, len)
+}
+
+/// Writes the dump of `len` bytes starting at `ptr` into `out`, reading them with volatile reads.
+///
+/// Every byte is read exactly once, see [`MemDump::from_raw_volatile`](crate::MemDump::from_raw_volatile).
+///
+/// # Arguments
+///
+/// * `out` - The writer receiving the dump.
+/// * `name` - The name displayed in the header.
+/// * `ptr` - The address of the first byte, e.g. of a memory-mapped peripheral.
+/// * `len` - The number of bytes to display.
+///
+/// # Safety
+///
+/// The whole region must be readable with byte-wide accesses.
+pub unsafe fn write_mem_volatile<W: fmt::Write + ?Sized>(out: &mut W, name: &str, ptr: *const u8, len: usize) -> fmt::Result {
+    sink_mem_volatile(&mut FmtSink(out), name, ptr, len)
+}
+
+/// Writes the `Name`, `Type`, `Addr` and `Size` lines of a dump header.
+pub(crate) fn write_header<W: fmt::Write + ?Sized>(out: &mut W, name: &str, type_name: &str, addr: usize, size: usize) -> fmt::Result {
+    writeln!(out, "Name: {}", name)?;
+    writeln!(out, "Type: {}", type_name)?;
+    writeln!(out, "Addr: {}", crate::StyledAddr(addr))?;
+    writeln!(out, "Size: {} bytes", size)
+}
+
+/// A `fmt::Write` filling a byte buffer, for dumps without allocation.
+///
+/// When the buffer is full the writes fail with `fmt::Error`, the buffer then holds as much of the text as fits
+/// (never a partial UTF-8 character).
+#[derive(Debug)]
+pub struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> SliceWriter<'a> {
+    /// Creates a writer filling `buf` from its start.
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        SliceWriter { buf, len: 0 }
+    }
+
+    /// Returns the number of bytes written.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if nothing was written.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the written bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Returns the written text.
+    pub fn as_str(&self) -> &str {
+        // Only whole `str`s or prefixes cut on a character boundary are copied
+        core::str::from_utf8(self.as_bytes()).unwrap_or_default()
+    }
+}
+
+impl fmt::Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let room = self.buf.len() - self.len;
+        let mut fits = s.len().min(room);
+        while !s.is_char_boundary(fits) {
+            fits -= 1;
+        }
+        self.buf[self.len..self.len + fits].copy_from_slice(&s.as_bytes()[..fits]);
+        self.len += fits;
+        if fits < s.len() {
+            Err(fmt::Error)
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Verification that a secret was scrubbed from memory.
+//!
+//! `verify_zeroized!` reads again the memory of a [`MemDump`](crate::MemDump) captured while it held a secret, e.g. a
+//! key or a password, once the secret was zeroized, and lists the bytes which aren't zero: the bytes still
+//! holding their captured value are leftovers of the secret, the others were overwritten by something else.
+
+use core::fmt;
+
+use alloc::vec::Vec;
+
+use crate::render::HEX;
+
+/// Returns the offsets of the bytes of `current` which aren't zero.
+pub(crate) fn nonzero_offsets(current: &[u8]) -> Vec<usize> {
+    current.iter().enumerate().filter(|(_, byte)| **byte != 0).map(|(offset, _)| offset).collect()
+}
+
+/// Writes the `Zero` line of a region captured as `captured` and holding `current` now, then a row per byte which
+/// isn't zero.
+pub(crate) fn write_zeroize_report<W: fmt::Write + ?Sized>(out: &mut W, name: &str, addr: usize, captured: &[u8], current: &[u8]) -> fmt::Result {
+    writeln!(out, "Name: {}", name)?;
+    let offsets = nonzero_offsets(current);
+    if offsets.is_empty() {
+        writeln!(out, "Zero: all {} bytes are zero", crate::Thousands(current.len()))?;
+        return writeln!(out);
+    }
+    let survived = offsets.iter().filter(|offset| captured.get(**offset) == Some(&current[**offset])).count();
+    writeln!(
+        out,
+        "Zero: NOT zeroized, {} of {} bytes aren't zero, {} still holding the captured secret",
+        crate::Thousands(offsets.len()),
+        crate::Thousands(current.len()),
+        crate::Thousands(survived)
+    )?;
+    for offset in offsets {
+        let byte = current[offset];
+        write!(out, " {} | +{:<4x} | {} | ", crate::_Addr(addr + offset), offset, HEX[byte as usize])?;
+        match captured.get(offset) {
+            Some(captured) if *captured == byte => writeln!(out, "survived")?,
+            Some(captured) => writeln!(out, "overwritten, was {}", HEX[*captured as usize])?,
+            None => writeln!(out, "not captured")?,
+        }
+    }
+    writeln!(out)
+}
+
+#[cfg(feature = "std")]
+#[macro_export]
+/// Macro to check that the memory of a secret is all zeros after it was zeroized.
+///
+/// The region is a [`MemDump`](crate::MemDump) captured while it held the secret, e.g. with `MemDump::of`. Its memory
+/// is read again with volatile reads, then the macro prints whether it is all zeros, lists every byte which isn't with
+/// whether it still holds its captured value (`survived`) or was `overwritten` since, and returns the offsets of these
+/// bytes. In Miri-friendly mode the memory isn't read and no offset is returned.
+///
+/// The memory is read through the captured address, with `with_exposed_provenance`, so the macro must be called in an
+/// `unsafe` block: a `MemDump` may hold any address, e.g. one built by `MemDump::new` or parsed from a file.
+///
+/// The region keeps a copy of the secret in its `bytes`, on the heap, which isn't scrubbed when it is dropped: capture
+/// secrets this way in tests and debug builds only, and overwrite `region.bytes` once checked, e.g. with the `zeroize`
+/// crate.
+///
+/// # Safety
+///
+/// The region must be captured from a reference, e.g. with `MemDump::of`, which exposes the provenance of the memory,
+/// and its memory must still be allocated and readable: a value scrubbed in place while it is in scope, or a value
+/// scrubbing itself in `Drop`, dropped in place in a [`ManuallyDrop`](core::mem::ManuallyDrop) which keeps its memory
+/// allocated (see the second example). Reading the memory of a value dropped at the end of its scope or moved, or of a
+/// freed heap block, is undefined behavior, as it may be reused or unmapped.
+///
+/// # Argument
+///
+/// * `region` - The dump of the memory of the secret, captured before it was scrubbed.
+///
+/// # Example
+///
+/// ```rust
+/// use mem_viewer::*;
+///
+/// let mut my_key: [u8; 8] = *b"hunter2!";
+/// let region = MemDump::of("my_key", &my_key);
+/// // A buggy scrub forgetting the last 2 bytes
+/// for byte in my_key[..6].iter_mut() {
+///     unsafe { core::ptr::write_volatile(byte, 0) };
+/// }
+/// let left = unsafe { verify_zeroized!(region) };
+/// # if !config().miri_friendly {
+/// assert_eq!(left, [6, 7]);
+/// # }
+/// ```
+///
+/// A key scrubbing itself when dropped is checked by dropping it in place, its memory staying allocated until the end
+/// of the scope:
+///
+/// ```rust
+/// use core::mem::ManuallyDrop;
+/// use mem_viewer::*;
+///
+/// struct MyKey([u8; 8]);
+///
+/// impl Drop for MyKey {
+///     fn drop(&mut self) {
+///         for byte in self.0.iter_mut() {
+///             unsafe { core::ptr::write_volatile(byte, 0) };
+///         }
+///     }
+/// }
+///
+/// let mut my_key = ManuallyDrop::new(MyKey(*b"hunter2!"));
+/// let mut region = MemDump::of("my_key", &*my_key);
+/// unsafe { ManuallyDrop::drop(&mut my_key) };
+/// assert!(unsafe { verify_zeroized!(region) }.is_empty());
+/// // Scrub the captured copy too
+/// for byte in region.bytes.iter_mut() {
+///     unsafe { core::ptr::write_volatile(byte, 0) };
+/// }
+/// ```
+///
+/// # Output
+///
+/// ```none
+/// Name: my_key
+/// Zero: NOT zeroized, 2 of 8 bytes aren't zero, 2 still holding the captured secret
+///  00007ffd2c4bd8fe | +6    | 32 | survived
+///  00007ffd2c4bd8ff | +7    | 21 | survived
+/// ```
+macro_rules! verify_zeroized {
+    ($region: expr) => {
+        $crate::_verify_zeroized(&$region)
+    };
+}